@@ -1,5 +1,6 @@
 use crate::*;
-// use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_contract_standards::fungible_token::core_impl::ext_fungible_token;
+use near_sdk::PromiseOrValue;
 
 #[near_bindgen]
 impl Contract {
@@ -20,47 +21,51 @@ impl Contract {
         self.internal_set_farmer(&farmer_id, farmer);
     }
 
-    // /// Withdraws given reward token of given user.
-    // /// when amount is None, withdraw all balance of the token.
-    // pub fn withdraw_reward(
-    //     &mut self,
-    //     token_id: AccountId,
-    //     amount: Option<U128>,
-    // ) -> PromiseOrValue<bool> {
-    //     assert!(
-    //         self.data().state == RunningState::Running,
-    //         E004_CONTRACT_PAUSED
-    //     );
+    /// Withdraws given reward token of given user.
+    /// when amount is None, withdraw all balance of the token.
+    pub fn withdraw_reward(
+        &mut self,
+        token_id: AccountId,
+        amount: Option<U128>,
+    ) -> PromiseOrValue<bool> {
+        assert!(
+            self.data().state == RunningState::Running,
+            "{}", E004_CONTRACT_PAUSED
+        );
 
-    //     let farmer_id = env::predecessor_account_id();
-    //     let mut farmer = self.internal_unwrap_farmer(&farmer_id);
+        let farmer_id = env::predecessor_account_id();
+        let mut farmer = self.internal_unwrap_farmer(&farmer_id);
 
-    //     let total = farmer.rewards.get(&token_id).unwrap_or(&0_u128);
-    //     let amount: u128 = amount.map(|v| v.into()).unwrap_or(total.clone());
+        let total = farmer.rewards.get(&token_id).cloned().unwrap_or(0_u128);
+        let amount: u128 = amount.map(|v| v.into()).unwrap_or(total);
+        assert!(amount <= total, "{}", E101_INSUFFICIENT_BALANCE);
 
-    //     if amount > 0 {
-    //         // Note: subtraction, will be reverted if the promise fails.
-    //         farmer.sub_reward(&token_id, amount);
-    //         self.internal_set_farmer(&farmer_id, farmer);
+        if amount > 0 {
+            // Note: subtraction, will be reverted if the promise fails.
+            farmer.sub_reward(&token_id, amount);
+            self.internal_set_farmer(&farmer_id, farmer);
 
-    //         ext_ft_core::ext(token_id.clone())
-    //             .with_attached_deposit(1)
-    //             .with_static_gas(GAS_FOR_REWARD_TRANSFER)
-    //             .ft_transfer(farmer_id.clone(), amount.into(), None)
-    //             .then(
-    //                 Self::ext(env::current_account_id())
-    //                     .with_static_gas(GAS_FOR_RESOLVE_REWARD_TRANSFER)
-    //                     .callback_post_withdraw_reward(
-    //                         token_id.clone(),
-    //                         farmer_id.clone(),
-    //                         amount.into(),
-    //                     ),
-    //             )
-    //             .into()
-    //     } else {
-    //         PromiseOrValue::Value(true)
-    //     }
-    // }
+            ext_fungible_token::ft_transfer(
+                farmer_id.clone(),
+                amount.into(),
+                None,
+                &token_id,
+                ONE_YOCTO,
+                GAS_FOR_REWARD_TRANSFER,
+            )
+            .then(ext_self::callback_post_withdraw_reward(
+                token_id.clone(),
+                farmer_id.clone(),
+                amount.into(),
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                GAS_FOR_RESOLVE_REWARD_TRANSFER,
+            ))
+            .into()
+        } else {
+            PromiseOrValue::Value(true)
+        }
+    }
 
     #[private]
     pub fn callback_post_withdraw_reward(
@@ -1,5 +1,6 @@
 use crate::*;
-// use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_contract_standards::fungible_token::core_impl::ext_fungible_token;
+use near_sdk::PromiseOrValue;
 
 #[near_bindgen]
 impl Contract {
@@ -30,7 +31,7 @@ impl Contract {
 
         self.internal_do_farmer_claim(&mut farmer, &mut seed);
 
-        let mut farmer_seed = farmer.seeds.get(&seed_id).unwrap();
+        let mut farmer_seed = farmer.get_seed(&seed_id).unwrap();
         let amount = if let Some(request) = amount {
             request.0
         } else {
@@ -38,7 +39,7 @@ impl Contract {
         };
 
         let increased_seed_power = farmer_seed.free_to_lock(amount, duration_sec, &config);
-        farmer.seeds.insert(&seed_id, &farmer_seed);
+        farmer.set_seed(&seed_id, &farmer_seed);
 
         seed.total_seed_power += increased_seed_power;
 
@@ -57,72 +58,82 @@ impl Contract {
         .emit();
     }
 
-    // #[payable]
-    // pub fn unlock_and_withdraw_seed(
-    //     &mut self,
-    //     seed_id: SeedId,
-    //     unlock_amount: U128,
-    //     withdraw_amount: U128,
-    // ) -> PromiseOrValue<bool> {
-    //     assert_one_yocto();
-    //     assert!(
-    //         self.data().state == RunningState::Running,
-    //         E004_CONTRACT_PAUSED
-    //     );
-
-    //     let unlock_amount: Balance = unlock_amount.into();
-    //     let withdraw_amount: Balance = withdraw_amount.into();
-
-    //     let farmer_id = env::predecessor_account_id();
-
-    //     let mut farmer = self.internal_unwrap_farmer(&farmer_id);
-    //     let mut seed = self.internal_unwrap_seed(&seed_id);
-
-    //     self.internal_do_farmer_claim(&mut farmer, &mut seed);
-
-    //     let mut farmer_seed = farmer.seeds.get(&seed_id).unwrap();
-
-    //     let prev = farmer_seed.get_seed_power();
-
-    //     let decreased_seed_power = if unlock_amount > 0 {
-    //         farmer_seed.unlock_to_free(unlock_amount)
-    //     } else {
-    //         0
-    //     };
-    //     let ret: PromiseOrValue<bool> = if withdraw_amount > 0 {
-    //         farmer_seed.withdraw_free(withdraw_amount);
-    //         self.transfer_seed_token(&farmer_id, &seed_id, withdraw_amount)
-    //             .into()
-    //     } else {
-    //         PromiseOrValue::Value(true)
-    //     };
-
-    //     seed.total_seed_amount -= withdraw_amount;
-    //     seed.total_seed_power = seed.total_seed_power - prev + farmer_seed.get_seed_power();
-
-    //     if farmer_seed.is_empty() {
-    //         farmer.seeds.remove(&seed_id);
-    //     } else {
-    //         farmer.seeds.insert(&seed_id, &farmer_seed);
-    //     }
-
-    //     self.update_impacted_seeds(&mut farmer, &seed_id);
-
-    //     self.internal_set_farmer(&farmer_id, farmer);
-    //     self.internal_set_seed(&seed_id, seed);
-
-    //     if unlock_amount > 0 {
-    //         Event::SeedUnlock {
-    //             farmer_id: &farmer_id,
-    //             seed_id: &seed_id,
-    //             unlock_amount: &U128(unlock_amount),
-    //             decreased_power: &U128(decreased_seed_power),
-    //             slashed_seed: &U128(0),
-    //         }
-    //         .emit();
-    //     }
-    //     ret
-    // }
+    #[payable]
+    pub fn unlock_and_withdraw_seed(
+        &mut self,
+        seed_id: SeedId,
+        unlock_amount: U128,
+        withdraw_amount: U128,
+    ) -> PromiseOrValue<bool> {
+        assert_one_yocto();
+        assert!(
+            self.data().state == RunningState::Running,
+            "{}", E004_CONTRACT_PAUSED
+        );
+
+        let unlock_amount: Balance = unlock_amount.into();
+        let withdraw_amount: Balance = withdraw_amount.into();
+
+        let farmer_id = env::predecessor_account_id();
+
+        let mut farmer = self.internal_unwrap_farmer(&farmer_id);
+        let mut seed = self.internal_unwrap_seed(&seed_id);
+
+        self.internal_do_farmer_claim(&mut farmer, &mut seed);
+
+        let mut farmer_seed = farmer.get_seed(&seed_id).expect(E301_SEED_NOT_EXIST);
+
+        let prev = farmer_seed.get_seed_power();
+
+        let decreased_seed_power = if unlock_amount > 0 {
+            farmer_seed.unlock_to_free(unlock_amount)
+        } else {
+            0
+        };
+        let ret: PromiseOrValue<bool> = if withdraw_amount > 0 {
+            farmer_seed.withdraw_free(withdraw_amount);
+            self.internal_transfer_seed(&farmer_id, &seed_id, withdraw_amount)
+                .then(ext_self::callback_withdraw_seed(
+                    seed_id.clone(),
+                    farmer_id.clone(),
+                    withdraw_amount.into(),
+                    &env::current_account_id(),
+                    NO_DEPOSIT,
+                    GAS_FOR_RESOLVE_SEED_TRANSFER,
+                ))
+                .into()
+        } else {
+            PromiseOrValue::Value(true)
+        };
+
+        seed.total_seed_amount -= withdraw_amount;
+        seed.total_seed_power = (seed.total_seed_power + farmer_seed.get_seed_power())
+            .checked_sub(prev)
+            .expect(E310_SEED_POWER_UNDERFLOW);
+
+        if farmer_seed.is_empty() {
+            farmer.remove_seed(&seed_id);
+        } else {
+            farmer.set_seed(&seed_id, &farmer_seed);
+        }
+
+        self.update_impacted_seeds(&mut farmer, &seed_id);
+
+        self.internal_set_farmer(&farmer_id, farmer);
+        self.internal_set_seed(&seed_id, seed);
+
+        if unlock_amount > 0 {
+            Event::SeedUnlock {
+                farmer_id: &farmer_id,
+                seed_id: &seed_id,
+                unlock_amount: &U128(unlock_amount),
+                decreased_power: &U128(decreased_seed_power),
+                slashed_seed: &U128(0),
+            }
+            .emit();
+        }
+        ret
+    }
 
     #[payable]
     pub fn force_unlock(&mut self, seed_id: SeedId, unlock_amount: U128) {
@@ -141,20 +152,22 @@ impl Contract {
 
         self.internal_do_farmer_claim(&mut farmer, &mut seed);
 
-        let mut farmer_seed = farmer.seeds.get(&seed_id).unwrap();
+        let mut farmer_seed = farmer.get_seed(&seed_id).unwrap();
 
         let (reduced_seed_power, seed_slashed) =
             farmer_seed.unlock_to_free_with_slashed(unlock_amount, seed.slash_rate);
 
         seed.total_seed_amount -= seed_slashed;
-        seed.total_seed_power -= reduced_seed_power;
+        seed.total_seed_power = seed.total_seed_power
+            .checked_sub(reduced_seed_power)
+            .expect(E310_SEED_POWER_UNDERFLOW);
 
         let slashed_amount = self.data().seeds_slashed.get(&seed_id).unwrap_or(0);
         self.data_mut()
             .seeds_slashed
             .insert(&seed_id, &(slashed_amount + seed_slashed));
 
-        farmer.seeds.insert(&seed_id, &farmer_seed);
+        farmer.set_seed(&seed_id, &farmer_seed);
 
         self.update_impacted_seeds(&mut farmer, &seed_id);
 
@@ -209,43 +222,35 @@ impl Contract {
 }
 
 impl Contract {
-    // fn transfer_seed_token(
-    //     &mut self,
-    //     farmer_id: &AccountId,
-    //     seed_id: &SeedId,
-    //     amount: Balance,
-    // ) -> Promise {
-    //     let (token, token_id) = parse_seed_id(seed_id);
-
-    //     if let Some(token_id) = token_id {
-    //         ext_multi_fungible_token::ext(token.clone())
-    //             .with_attached_deposit(1)
-    //             .with_static_gas(GAS_FOR_SEED_TRANSFER)
-    //             .mft_transfer(
-    //                 wrap_mft_token_id(&token_id),
-    //                 farmer_id.clone(),
-    //                 amount.into(),
-    //                 None,
-    //             )
-    //             .then(
-    //                 Self::ext(env::current_account_id())
-    //                     .with_static_gas(GAS_FOR_RESOLVE_SEED_TRANSFER)
-    //                     .callback_withdraw_seed(seed_id.clone(), farmer_id.clone(), amount.into()),
-    //             )
-    //     } else {
-    //         ext_ft_core::ext(token.clone())
-    //         .with_attached_deposit(1)
-    //         .with_static_gas(GAS_FOR_SEED_TRANSFER)
-    //         .ft_transfer(
-    //             farmer_id.clone(),
-    //             amount.into(),
-    //             None,
-    //         )
-    //         .then(
-    //             Self::ext(env::current_account_id())
-    //                 .with_static_gas(GAS_FOR_RESOLVE_SEED_TRANSFER)
-    //                 .callback_withdraw_seed(seed_id.clone(), farmer_id.clone(), amount.into()),
-    //         )
-    //     }
-    // }
+    /// Transfer seed token to receiver_id, mft_transfer for a ref-exchange LP share, ft_transfer otherwise.
+    /// Callers chain their own callback to handle a failed transfer.
+    pub(crate) fn internal_transfer_seed(
+        &self,
+        receiver_id: &AccountId,
+        seed_id: &SeedId,
+        amount: Balance,
+    ) -> Promise {
+        let (token, token_id) = parse_seed_id(seed_id);
+
+        if let Some(token_id) = token_id {
+            ext_multi_fungible_token::mft_transfer(
+                wrap_mft_token_id(&token_id),
+                receiver_id.clone(),
+                amount.into(),
+                None,
+                &token,
+                ONE_YOCTO,
+                GAS_FOR_SEED_TRANSFER,
+            )
+        } else {
+            ext_fungible_token::ft_transfer(
+                receiver_id.clone(),
+                amount.into(),
+                None,
+                &token,
+                ONE_YOCTO,
+                GAS_FOR_SEED_TRANSFER,
+            )
+        }
+    }
 }
@@ -25,6 +25,8 @@ pub(crate) const MAX_RATIO: u32 = 10000;
 const NUM_DECIMALS: u8 = 27;
 const BIG_DIVISOR: u128 = 10u128.pow(NUM_DECIMALS as u32);
 const HALF_DIVISOR: u128 = BIG_DIVISOR / 2;
+/// ln(2) with NUM_DECIMALS decimals
+const LN_2: u128 = 693147180559945309417232121;
 
 pub type LowU128 = U128;
 
@@ -230,6 +232,40 @@ impl BigDecimal {
         Self(U384::from(BIG_DIVISOR))
     }
 
+    /// Natural logarithm in fixed point, self should be no less than 1.
+    /// ln(x) = k * ln(2) + ln(y), where x = y * 2^k and 1 <= y < 2,
+    /// ln(y) = 2 * atanh(z) = 2 * (z + z^3/3 + z^5/5 + ...), where z = (y - 1) / (y + 1) < 1/3.
+    pub fn ln(&self) -> Self {
+        assert!(*self >= BigDecimal::one(), "{}", E206_INVALID_LOG_INPUT);
+        let one = BigDecimal::one();
+        let two = BigDecimal::from(2u32);
+
+        let mut y = *self;
+        let mut k = 0u32;
+        while y >= two {
+            y = y.div_u128(2);
+            k += 1;
+        }
+
+        let z = (y - one) / (y + one);
+        let z2 = z * z;
+        let mut term = z;
+        let mut sum = BigDecimal::zero();
+        let mut n = 1u128;
+        while term > BigDecimal::zero() {
+            sum = sum + term.div_u128(n);
+            term = term * z2;
+            n += 2;
+        }
+
+        BigDecimal::from(k) * BigDecimal::from(U128(LN_2)) + sum + sum
+    }
+
+    /// Logarithm of self to base in fixed point, both should be no less than 1 and base not 1.
+    pub fn log(&self, base: &BigDecimal) -> Self {
+        self.ln() / base.ln()
+    }
+
     pub fn pow(&self, mut exponent: u64) -> Self {
         let mut res = BigDecimal::one();
         let mut x = *self;
@@ -377,6 +413,32 @@ mod tests {
         almost_eq(val, initial_val * 2, 15);
     }
 
+    #[test]
+    fn test_ln() {
+        // ln(1) = 0, ln(2) = LN_2, ln(e) = 1
+        assert_eq!(BigDecimal::one().ln(), BigDecimal::zero());
+        almost_eq(LowU128::from(b(2).ln()).0, LN_2, 25);
+        let e = BigDecimal::from(U128(2718281828459045235360287471));
+        almost_eq(LowU128::from(e.ln()).0, BIG_DIVISOR, 25);
+        // ln(10^24) = 24 * ln(10)
+        almost_eq(LowU128::from(b(10u128.pow(24)).ln()).0, 55262042231857096416431794912, 20);
+    }
+
+    #[test]
+    fn test_log() {
+        assert_eq!(b(1000).log(&b(10)).round_u128(), 3);
+        almost_eq(LowU128::from(b(1000).log(&b(10))).0, 3 * BIG_DIVISOR, 24);
+        almost_eq(LowU128::from(b(1024).log(&b(2))).0, 10 * BIG_DIVISOR, 24);
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let x = rng.next_u64() as u128 % 10u128.pow(12) + 1;
+            let base = rng.next_u32() % 100 + 2;
+            let expected = (x as f64).log(base as f64);
+            let actual = LowU128::from(b(x).log(&BigDecimal::from(base))).0 as f64 / BIG_DIVISOR as f64;
+            assert!((expected - actual).abs() < 1e-9, "log({}, {}): {} vs {}", x, base, expected, actual);
+        }
+    }
+
     #[test]
     fn test_display() {
         assert_eq!("1.0", BigDecimal::one().to_string());
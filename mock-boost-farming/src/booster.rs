@@ -54,24 +54,24 @@ impl Contract {
     }
 
     /// generate booster ratios map for a given seed
-    /// booster-ratio = ((booster_balance as f64) / (booster_base as f64)).log(log_base as f64)
-    /// where log_base if from Config.global_booster_seeds.get(seed_id).unwrap().get(self.seed_id).unwrap()
-    pub fn gen_booster_ratios(&self, seed_id: &SeedId, farmer: &Farmer) -> HashMap<SeedId, f64> {
+    /// booster-ratio = (booster_balance / booster_base).log(log_base) in fixed point BigDecimal
+    /// where log_base if from Config.global_booster_seeds.get(seed_id).unwrap().get(self.seed_id).unwrap(),
+    /// log_base no more than 1 gives no boost.
+    pub fn gen_booster_ratios(&self, seed_id: &SeedId, farmer: &Farmer) -> HashMap<SeedId, BigDecimal> {
         let mut ratios = HashMap::new();
         let log_bases = self.internal_config().get_boosters_from_seed(seed_id);
         for (booster, booster_decimal, log_base) in &log_bases {
             let booster_balance = farmer
-                .seeds
-                .get(booster)
+                .get_seed(booster)
                 .map(|v| v.get_basic_seed_power())
                 .unwrap_or(0_u128);
-            if booster_balance > 0 && log_base > &0 {
+            if booster_balance > 0 && log_base > &1 {
                 let booster_base = 10u128.pow(*booster_decimal);
-                let booster_amount = booster_balance as f64 / booster_base as f64;
-                let ratio = if booster_amount > 1f64 {
-                    booster_amount.log(*log_base as f64)
+                let booster_amount = BigDecimal::from(booster_balance) / BigDecimal::from(booster_base);
+                let ratio = if booster_amount > BigDecimal::one() {
+                    booster_amount.log(&BigDecimal::from(*log_base))
                 } else {
-                    0f64
+                    BigDecimal::zero()
                 };
                 ratios.insert(booster.clone(), ratio);
             }
@@ -84,7 +84,7 @@ impl Contract {
         if let Some(booster_info) = self.internal_config().get_affected_seeds_from_booster(booster_id) {
             for seed_id in booster_info.affected_seeds.keys() {
                 // here we got each affected seed_id, then if the farmer has those seeds, should be updated on by one
-                if farmer.seeds.get(seed_id).is_some() {
+                if farmer.get_seed(seed_id).is_some() {
                     // first claim that farmer's current reward and update boost_ratios for the seed
                     let mut seed = self.internal_unwrap_seed(seed_id);
                     self.internal_do_farmer_claim(farmer, &mut seed);
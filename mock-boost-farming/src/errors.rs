@@ -20,6 +20,7 @@ pub const E202_FORBID_SELF_BOOST: &str = "E202: self boost is forbidden";
 pub const E203_EXCEED_FARM_NUM_IN_BOOST: &str = "E203: exceed max farm num in one boost";
 pub const E204_EXCEED_SEED_NUM_IN_BOOSTER: &str = "E204: exceed max seed num in one booster";
 pub const E205_INVALID_SLASH_RATE: &str = "E205: invalid slash rate";
+pub const E206_INVALID_LOG_INPUT: &str = "E206: logarithm of value less than 1";
 
 pub const E300_FORBID_LOCKING: &str = "E300: locking on this seed is forbidden";
 pub const E301_SEED_NOT_EXIST: &str = "E301: seed not exist";
@@ -31,6 +32,7 @@ pub const E306_LOCK_AMOUNT_TOO_SMALL: &str = "E306: locking amount is too small"
 pub const E307_BELOW_MIN_DEPOSIT: &str = "E307: below minimum deposit amount";
 pub const E308_INVALID_SEED_ID: &str = "E308: invalid seed id";
 pub const E309_NO_NEED_FORCE: &str = "E309: can directly unlock without force";
+pub const E310_SEED_POWER_UNDERFLOW: &str = "E310: seed power underflow";
 
 pub const E401_FARM_NOT_EXIST: &str = "E401: farm not exist";
 pub const E403_FARM_ALREADY_DEPOSIT_REWARD: &str = "E403: farm can not be cancelled due to already deposit reward";
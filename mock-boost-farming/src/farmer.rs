@@ -12,18 +12,25 @@ pub struct Farmer {
     /// Various seed tokens the farmer staked.
     #[serde(skip_serializing)]
     pub seeds: UnorderedMap<SeedId, FarmerSeed>,
+    /// Seeds of a farmer loaded from V1, stored with f64 boost ratios,
+    /// they are read through this and rewritten into seeds when the farmer is saved.
+    #[borsh_skip]
+    #[serde(skip_serializing)]
+    pub legacy_seeds: Option<UnorderedMap<SeedId, FarmerSeedV0>>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum VFarmer {
     V0(FarmerV0),
+    V1(FarmerV1),
     Current(Farmer),
 }
 
 impl From<VFarmer> for Farmer {
     fn from(v: VFarmer) -> Self {
         match v {
-            VFarmer::V0(c) => c.into(),
+            VFarmer::V0(c) => FarmerV1::from(c).into(),
+            VFarmer::V1(c) => c.into(),
             VFarmer::Current(c) => c,
         }
     }
@@ -44,6 +51,46 @@ impl Farmer {
             seeds: UnorderedMap::new(StorageKeys::FarmerSeed {
                 account_id: farmer_id.clone(),
             }),
+            legacy_seeds: None,
+        }
+    }
+
+    pub fn get_seed(&self, seed_id: &SeedId) -> Option<FarmerSeed> {
+        if let Some(legacy_seeds) = &self.legacy_seeds {
+            legacy_seeds.get(seed_id).map(|v| v.into())
+        } else {
+            self.seeds.get(seed_id)
+        }
+    }
+
+    pub fn list_seeds(&self, from_index: u64, limit: u64) -> Vec<(SeedId, FarmerSeed)> {
+        let keys = self.seeds.keys_as_vector();
+        (from_index..std::cmp::min(keys.len(), from_index + limit))
+            .map(|idx| {
+                let key = keys.get(idx).unwrap();
+                let farmer_seed = self.get_seed(&key).unwrap();
+                (key, farmer_seed)
+            })
+            .collect()
+    }
+
+    pub fn set_seed(&mut self, seed_id: &SeedId, farmer_seed: &FarmerSeed) {
+        self.upgrade_seeds();
+        self.seeds.insert(seed_id, farmer_seed);
+    }
+
+    pub fn remove_seed(&mut self, seed_id: &SeedId) {
+        self.upgrade_seeds();
+        self.seeds.remove(seed_id);
+    }
+
+    /// Rewrite legacy seeds with BigDecimal boost ratios,
+    /// only on state changing calls as views can't write storage.
+    pub fn upgrade_seeds(&mut self) {
+        if let Some(legacy_seeds) = self.legacy_seeds.take() {
+            for (seed_id, farmer_seed) in legacy_seeds.to_vec() {
+                self.seeds.insert(&seed_id, &farmer_seed.into());
+            }
         }
     }
     pub fn add_rewards(&mut self, rewards: &HashMap<AccountId, Balance>) {
@@ -82,9 +129,7 @@ impl Contract {
         let mut claimed = HashMap::new();
 
         let mut farmer_seed: FarmerSeed = farmer
-            .seeds
-            .get(&seed.seed_id)
-            .map(|v| v.into())
+            .get_seed(&seed.seed_id)
             .unwrap_or_else(|| FarmerSeed {
                 free_amount: 0,
                 locked_amount: 0,
@@ -130,9 +175,11 @@ impl Contract {
         // sync booster info
         let prev = farmer_seed.get_seed_power();
         farmer_seed.boost_ratios = self.gen_booster_ratios(&seed.seed_id, farmer);
-        seed.total_seed_power = seed.total_seed_power + farmer_seed.get_seed_power() - prev;
+        seed.total_seed_power = (seed.total_seed_power + farmer_seed.get_seed_power())
+            .checked_sub(prev)
+            .expect(E310_SEED_POWER_UNDERFLOW);
 
-        farmer.seeds.insert(&seed.seed_id, &farmer_seed);
+        farmer.set_seed(&seed.seed_id, &farmer_seed);
         seed.update_claimed(&claimed);
 
     }
@@ -146,7 +193,8 @@ impl Contract {
             .expect(E100_ACC_NOT_REGISTERED)
     }
 
-    pub fn internal_set_farmer(&mut self, farmer_id: &AccountId, farmer: Farmer) {
+    pub fn internal_set_farmer(&mut self, farmer_id: &AccountId, mut farmer: Farmer) {
+        farmer.upgrade_seeds();
         self.data_mut().farmers.insert(farmer_id, &farmer.into());
     }
 }
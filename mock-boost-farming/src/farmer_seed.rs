@@ -18,7 +18,7 @@ pub struct FarmerSeed {
     /// The duration of current locking in seconds.
     pub duration_sec: u32,
     /// <booster_id, booster-ratio>
-    pub boost_ratios: HashMap<SeedId, f64>,
+    pub boost_ratios: HashMap<SeedId, BigDecimal>,
     #[serde(skip_serializing)]
     pub user_rps: HashMap<FarmId, BigDecimal>,
 }
@@ -26,7 +26,7 @@ pub struct FarmerSeed {
 impl FarmerSeed {
     pub fn get_seed_power(&self) -> Balance {
         let base_power = self.get_basic_seed_power();
-        let extras: Vec<u128> = self.boost_ratios.values().map(|ratio|((base_power as f64) * ratio) as u128).collect();
+        let extras: Vec<u128> = self.boost_ratios.values().map(|ratio| ratio.round_down_mul_u128(base_power)).collect();
         base_power + extras.iter().sum::<u128>()
     }
 
@@ -55,10 +55,10 @@ pub struct FarmerV0 {
     /// Amounts of various reward tokens the farmer claimed.
     pub rewards: HashMap<AccountId, Balance>,
     /// Various seed tokens the farmer staked.
-    pub seeds: UnorderedMap<SeedId, FarmerSeed>,
+    pub seeds: UnorderedMap<SeedId, FarmerSeedV0>,
 }
 
-impl From<FarmerV0> for Farmer {
+impl From<FarmerV0> for FarmerV1 {
     fn from(a: FarmerV0) -> Self {
         let FarmerV0 {
             farmer_id,
@@ -72,4 +72,164 @@ impl From<FarmerV0> for Farmer {
             seeds,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FarmerV1 {
+    /// A copy of an farmer ID. Saves one storage_read when iterating on farmers.
+    pub farmer_id: AccountId,
+    pub sponsor_id: AccountId,
+    /// Amounts of various reward tokens the farmer claimed.
+    pub rewards: HashMap<AccountId, Balance>,
+    /// Various seed tokens the farmer staked.
+    pub seeds: UnorderedMap<SeedId, FarmerSeedV0>,
+}
+
+impl From<FarmerV1> for Farmer {
+    fn from(a: FarmerV1) -> Self {
+        let FarmerV1 {
+            farmer_id,
+            sponsor_id,
+            rewards,
+            seeds,
+        } = a;
+        // the same collection in storage, values are converted on reading
+        let current_seeds = UnorderedMap::try_from_slice(&seeds.try_to_vec().unwrap()).unwrap();
+        Self {
+            farmer_id,
+            sponsor_id,
+            rewards,
+            seeds: current_seeds,
+            legacy_seeds: Some(seeds),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FarmerSeedV0 {
+    pub free_amount: Balance,
+    pub locked_amount: Balance,
+    pub x_locked_amount: Balance,
+    pub unlock_timestamp: u64,
+    pub duration_sec: u32,
+    pub boost_ratios: HashMap<SeedId, f64>,
+    pub user_rps: HashMap<FarmId, BigDecimal>,
+}
+
+impl From<FarmerSeedV0> for FarmerSeed {
+    fn from(a: FarmerSeedV0) -> Self {
+        let FarmerSeedV0 {
+            free_amount,
+            locked_amount,
+            x_locked_amount,
+            unlock_timestamp,
+            duration_sec,
+            boost_ratios,
+            user_rps,
+        } = a;
+        Self {
+            free_amount,
+            locked_amount,
+            x_locked_amount,
+            unlock_timestamp,
+            duration_sec,
+            boost_ratios: boost_ratios
+                .into_iter()
+                .filter(|(_, ratio)| ratio.is_finite() && *ratio > 0f64)
+                .map(|(booster_id, ratio)| (booster_id, BigDecimal::from(ratio)))
+                .collect(),
+            user_rps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    /// VFarmer as stored before boost ratios were BigDecimal.
+    #[derive(BorshSerialize)]
+    #[allow(dead_code)]
+    enum BaselineVFarmer {
+        V0(FarmerV0),
+        Current(BaselineFarmer),
+    }
+
+    #[derive(BorshSerialize)]
+    struct BaselineFarmer {
+        farmer_id: AccountId,
+        sponsor_id: AccountId,
+        rewards: HashMap<AccountId, Balance>,
+        seeds: UnorderedMap<SeedId, FarmerSeedV0>,
+    }
+
+    #[test]
+    fn test_baseline_farmer_decodes_as_v1() {
+        testing_env!(VMContextBuilder::new().build());
+        let farmer_id: AccountId = "alice".to_string();
+        let seed_id: SeedId = "seed".to_string();
+        let mut seeds = UnorderedMap::new(StorageKeys::FarmerSeed {
+            account_id: farmer_id.clone(),
+        });
+        let mut boost_ratios = HashMap::new();
+        boost_ratios.insert("booster".to_string(), 0.5f64);
+        seeds.insert(&seed_id, &FarmerSeedV0 {
+            free_amount: 100,
+            locked_amount: 0,
+            x_locked_amount: 0,
+            unlock_timestamp: 0,
+            duration_sec: 0,
+            boost_ratios,
+            user_rps: HashMap::new(),
+        });
+        let mut rewards = HashMap::new();
+        rewards.insert("reward".to_string(), 10);
+        let content = BaselineVFarmer::Current(BaselineFarmer {
+            farmer_id: farmer_id.clone(),
+            sponsor_id: "bob".to_string(),
+            rewards,
+            seeds,
+        }).try_to_vec().unwrap();
+
+        let v_farmer = VFarmer::try_from_slice(&content).unwrap();
+        assert!(matches!(v_farmer, VFarmer::V1(_)));
+        let mut farmer: Farmer = v_farmer.into();
+        assert_eq!(farmer.farmer_id, farmer_id);
+        assert_eq!(farmer.sponsor_id, "bob".to_string());
+        assert_eq!(farmer.rewards.get("reward"), Some(&10));
+        assert_eq!(farmer.get_seed(&seed_id).unwrap().get_seed_power(), 150);
+
+        // rewritten with BigDecimal ratios once upgraded
+        farmer.upgrade_seeds();
+        assert!(farmer.legacy_seeds.is_none());
+        assert_eq!(farmer.seeds.get(&seed_id).unwrap().get_seed_power(), 150);
+    }
+
+    #[test]
+    fn test_farmer_seed_v0_power() {
+        let ratios = [0.5f64, 1.0, 1.2345678901234567, 3.0102999566398116, 12.000000000000002];
+        let amounts = [1u128, 10u128.pow(18), 123456789012345678901234567, 10u128.pow(30) + 7];
+        for ratio in ratios.iter() {
+            for amount in amounts.iter() {
+                let mut boost_ratios = HashMap::new();
+                boost_ratios.insert("booster".to_string(), *ratio);
+                let v0 = FarmerSeedV0 {
+                    free_amount: *amount,
+                    locked_amount: 0,
+                    x_locked_amount: 0,
+                    unlock_timestamp: 0,
+                    duration_sec: 0,
+                    boost_ratios,
+                    user_rps: HashMap::new(),
+                };
+                let v0_power = *amount + ((*amount as f64) * ratio) as u128;
+                let power = FarmerSeed::from(v0).get_seed_power();
+                let diff = if power > v0_power { power - v0_power } else { v0_power - power };
+                // within f64 precision
+                assert!(diff <= v0_power / 10u128.pow(15) + 1, "{} {} {} {}", ratio, amount, power, v0_power);
+            }
+        }
+    }
+}
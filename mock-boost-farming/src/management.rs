@@ -1,5 +1,4 @@
 use crate::*;
-// use near_contract_standards::fungible_token::core::ext_ft_core;
 
 #[near_bindgen]
 impl Contract {
@@ -121,116 +120,74 @@ impl Contract {
         self.internal_set_seed(&seed_id, seed);
     }
 
-    // /// Owner retrieve those slashed seed
-    // #[payable]
-    // pub fn withdraw_seed_slashed(&mut self, seed_id: SeedId) -> Promise {
-    //     assert_one_yocto();
-    //     assert!(self.is_owner_or_operators(), E002_NOT_ALLOWED);
-    //     assert!(
-    //         self.data().state == RunningState::Running,
-    //         E004_CONTRACT_PAUSED
-    //     );
-
-    //     // update inner state
-    //     let amount = self
-    //         .data_mut()
-    //         .seeds_slashed
-    //         .remove(&seed_id)
-    //         .unwrap_or(0_u128);
-    //     assert!(amount > 0, E101_INSUFFICIENT_BALANCE);
+    /// Owner retrieve those slashed seed
+    #[payable]
+    pub fn withdraw_seed_slashed(&mut self, seed_id: SeedId) -> Promise {
+        assert_one_yocto();
+        assert!(self.is_owner_or_operators(), "{}", E002_NOT_ALLOWED);
+        assert!(
+            self.data().state == RunningState::Running,
+            "{}", E004_CONTRACT_PAUSED
+        );
 
-    //     let (token, token_id) = parse_seed_id(&seed_id);
+        // update inner state
+        let amount = self
+            .data_mut()
+            .seeds_slashed
+            .remove(&seed_id)
+            .unwrap_or(0_u128);
+        assert!(amount > 0, "{}", E101_INSUFFICIENT_BALANCE);
 
-    //     if let Some(token_id) = token_id {
-    //         ext_multi_fungible_token::ext(token.clone())
-    //             .with_attached_deposit(1)
-    //             .with_static_gas(GAS_FOR_SEED_TRANSFER)
-    //             .mft_transfer(
-    //                 wrap_mft_token_id(&token_id),
-    //                 self.data().owner_id.clone(),
-    //                 amount.into(),
-    //                 None,
-    //             )
-    //             .then(
-    //                 Self::ext(env::current_account_id())
-    //                     .with_static_gas(GAS_FOR_RESOLVE_SEED_TRANSFER)
-    //                     .callback_withdraw_seed_slashed(seed_id.clone(), amount.into()),
-    //             )
-    //     } else {
-    //         ext_ft_core::ext(token.clone())
-    //             .with_attached_deposit(1)
-    //             .with_static_gas(GAS_FOR_SEED_TRANSFER)
-    //             .ft_transfer(self.data().owner_id.clone(), amount.into(), None)
-    //             .then(
-    //                 Self::ext(env::current_account_id())
-    //                     .with_static_gas(GAS_FOR_RESOLVE_SEED_TRANSFER)
-    //                     .callback_withdraw_seed_slashed(seed_id.clone(), amount.into()),
-    //             )
-    //     }
-    // }
+        let owner_id = self.data().owner_id.clone();
+        self.internal_transfer_seed(&owner_id, &seed_id, amount)
+            .then(ext_self::callback_withdraw_seed_slashed(
+                seed_id.clone(),
+                amount.into(),
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                GAS_FOR_RESOLVE_SEED_TRANSFER,
+            ))
+    }
 
     /// owner help to return those who lost seed when withdraw,
     /// It's owner's responsibility to verify amount and seed id before calling
-    // #[payable]
-    // pub fn return_seed_lostfound(
-    //     &mut self,
-    //     farmer_id: AccountId,
-    //     seed_id: SeedId,
-    //     amount: U128,
-    // ) -> Promise {
-    //     assert_one_yocto();
-    //     self.assert_owner();
-    //     assert!(
-    //         self.data().state == RunningState::Running,
-    //         E004_CONTRACT_PAUSED
-    //     );
-
-    //     self.internal_unwrap_farmer(&farmer_id);
+    #[payable]
+    pub fn return_seed_lostfound(
+        &mut self,
+        farmer_id: AccountId,
+        seed_id: SeedId,
+        amount: U128,
+    ) -> Promise {
+        assert_one_yocto();
+        self.assert_owner();
+        assert!(
+            self.data().state == RunningState::Running,
+            "{}", E004_CONTRACT_PAUSED
+        );
 
-    //     // update inner state
-    //     let max_amount = self.data().seeds_lostfound.get(&seed_id).unwrap_or(0_u128);
-    //     assert!(amount.0 <= max_amount, E101_INSUFFICIENT_BALANCE);
-    //     self.data_mut()
-    //         .seeds_lostfound
-    //         .insert(&seed_id, &(max_amount - amount.0));
+        self.internal_unwrap_farmer(&farmer_id);
 
-    //     let (token, token_id) = parse_seed_id(&seed_id);
+        // update inner state
+        let max_amount = self.data().seeds_lostfound.get(&seed_id).unwrap_or(0_u128);
+        assert!(amount.0 > 0 && amount.0 <= max_amount, "{}", E101_INSUFFICIENT_BALANCE);
+        if amount.0 == max_amount {
+            self.data_mut().seeds_lostfound.remove(&seed_id);
+        } else {
+            self.data_mut()
+                .seeds_lostfound
+                .insert(&seed_id, &(max_amount - amount.0));
+        }
 
-    //     if let Some(token_id) = token_id {
-    //         ext_multi_fungible_token::ext(token.clone())
-    //             .with_attached_deposit(1)
-    //             .with_static_gas(GAS_FOR_SEED_TRANSFER)
-    //             .mft_transfer(
-    //                 wrap_mft_token_id(&token_id),
-    //                 farmer_id.clone(),
-    //                 amount.into(),
-    //                 None,
-    //             )
-    //             .then(
-    //                 Self::ext(env::current_account_id())
-    //                     .with_static_gas(GAS_FOR_RESOLVE_SEED_TRANSFER)
-    //                     .callback_withdraw_seed_lostfound(
-    //                         seed_id.clone(),
-    //                         farmer_id.clone(),
-    //                         amount.into(),
-    //                     ),
-    //             )
-    //     } else {
-    //         ext_ft_core::ext(token.clone())
-    //             .with_attached_deposit(1)
-    //             .with_static_gas(GAS_FOR_SEED_TRANSFER)
-    //             .ft_transfer(farmer_id.clone(), amount.into(), None)
-    //             .then(
-    //                 Self::ext(env::current_account_id())
-    //                     .with_static_gas(GAS_FOR_RESOLVE_SEED_TRANSFER)
-    //                     .callback_withdraw_seed_lostfound(
-    //                         seed_id.clone(),
-    //                         farmer_id.clone(),
-    //                         amount.into(),
-    //                     ),
-    //             )
-    //     }
-    // }
+        self.internal_transfer_seed(&farmer_id, &seed_id, amount.0)
+            .then(ext_self::callback_withdraw_seed_lostfound(
+                seed_id.clone(),
+                farmer_id.clone(),
+                amount,
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                GAS_FOR_RESOLVE_SEED_TRANSFER,
+            ))
+    }
 
     #[private]
     pub fn callback_withdraw_seed_lostfound(
@@ -165,15 +165,9 @@ impl Contract {
         limit: Option<u64>,
     ) -> HashMap<SeedId, FarmerSeed> {
         if let Some(farmer) = self.internal_get_farmer(&farmer_id) {
-            let keys = farmer.seeds.keys_as_vector();
             let from_index = from_index.unwrap_or(0);
-            let limit = limit.unwrap_or(keys.len() as u64);
-            (from_index..std::cmp::min(keys.len() as u64, from_index + limit))
-                .map(|idx| {
-                    let key = keys.get(idx).unwrap();
-                    (key.clone(), farmer.seeds.get(&key).unwrap())
-                })
-                .collect()
+            let limit = limit.unwrap_or(farmer.seeds.len());
+            farmer.list_seeds(from_index, limit).into_iter().collect()
         } else {
             HashMap::new()
         }
@@ -181,7 +175,7 @@ impl Contract {
 
     pub fn get_farmer_seed(&self, farmer_id: AccountId, seed_id: SeedId) -> Option<FarmerSeed> {
         if let Some(farmer) = self.internal_get_farmer(&farmer_id) {
-            farmer.seeds.get(&seed_id)
+            farmer.get_seed(&seed_id)
         } else {
             None
         }
@@ -10,11 +10,11 @@ use near_sdk::{
     assert_one_yocto, env, near_bindgen, 
     AccountId, Balance, PromiseResult, StorageUsage,
 };
-use crate::legacy::{AccountV1, AccountV2};
+use crate::legacy::{AccountV1, AccountV2, AccountV3};
 use crate::utils::{
     ext_self, ext_wrap_near, ONE_NEAR, MIN_CONTRACT_FREE_BALANCE, 
     GAS_FOR_FT_TRANSFER, GAS_FOR_FT_TRANSFER_CALL, GAS_FOR_NEAR_WITHDRAW, 
-    GAS_FOR_CB_NEAR_TRANSFER, GAS_FOR_CB_FT_TRANSFER
+    GAS_FOR_CB_NEAR_TRANSFER, GAS_FOR_CB_FT_TRANSFER, DEFAULT_STORAGE_TOPUP_CAP
 };
 use crate::*;
 
@@ -43,10 +43,24 @@ const ACC_ID_AS_CLT_KEY_STORAGE: StorageUsage = ACC_ID_AS_KEY_STORAGE + 1;
 pub const INIT_ACCOUNT_STORAGE: StorageUsage =
     ACC_ID_AS_CLT_KEY_STORAGE + 1 + U128_STORAGE + U32_STORAGE + U32_STORAGE + U64_STORAGE;
 
+/// Max tokens withdrawn in one withdraw_batch, each chains a transfer and its callback.
+pub const MAX_WITHDRAW_BATCH_TOKENS: usize = 5;
+
+pub fn read_storage_topup_cap() -> Balance {
+    env::storage_read(STORAGE_TOPUP_CAP.as_bytes())
+        .map(|content| Balance::try_from_slice(&content).expect("deserialize storage topup cap failed."))
+        .unwrap_or(DEFAULT_STORAGE_TOPUP_CAP)
+}
+
+pub fn write_storage_topup_cap(cap: Balance) {
+    env::storage_write(STORAGE_TOPUP_CAP.as_bytes(), &cap.try_to_vec().unwrap());
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub enum VAccount {
     V1(AccountV1),
     V2(AccountV2),
+    V3(AccountV3),
     Current(Account),
 }
 
@@ -57,6 +71,7 @@ impl VAccount {
             VAccount::Current(account) => account,
             VAccount::V1(account) => account.into_current(account_id),
             VAccount::V2(account) => account.into_current(account_id),
+            VAccount::V3(account) => account.into_current(),
         }
     }
 }
@@ -79,7 +94,9 @@ pub struct Account {
     pub tokens: UnorderedMap<AccountId, Balance>,
     pub storage_used: StorageUsage,
     // pool_id -> ShadowRecord
-    pub shadow_records: UnorderedMap<u64, VShadowRecord>
+    pub shadow_records: UnorderedMap<u64, VShadowRecord>,
+    /// Opt-in to cover storage shortage by unwrapping deposited wNEAR.
+    pub auto_storage_topup: bool,
 }
 
 impl Account {
@@ -94,6 +111,7 @@ impl Account {
             shadow_records: UnorderedMap::new(StorageKey::ShadowRecord {
                 account_id: account_id.clone(),
             }),
+            auto_storage_topup: false,
         }
     }
 
@@ -328,6 +346,43 @@ impl Contract {
         self.internal_save_account(&sender_id, account);
     }
 
+    /// Opt in or out of covering storage shortage with the sender's wNEAR deposit.
+    /// When enabled, up to `get_storage_topup_cap` wNEAR is unwrapped each time the account's storage deposit
+    /// or the deposit attached to a call falls short.
+    #[payable]
+    pub fn set_auto_storage_topup(&mut self, enabled: bool) {
+        assert_one_yocto();
+        self.assert_contract_running();
+        let sender_id = env::predecessor_account_id();
+        let mut account = self.internal_unwrap_account(&sender_id);
+        account.auto_storage_topup = enabled;
+        self.internal_save_account(&sender_id, account);
+    }
+
+    pub fn get_auto_storage_topup(&self, account_id: ValidAccountId) -> bool {
+        self.internal_get_account(account_id.as_ref())
+            .map(|account| account.auto_storage_topup)
+            .unwrap_or(false)
+    }
+
+    pub fn get_storage_topup_cap(&self) -> U128 {
+        U128(read_storage_topup_cap())
+    }
+
+    /// Unwrap amount of the sender's wNEAR deposit into his storage deposit.
+    /// The storage is credited only after the unwrapping succeeds,
+    /// otherwise the wNEAR goes back to his deposit.
+    #[payable]
+    pub fn storage_topup_with_wnear(&mut self, amount: U128) {
+        assert_one_yocto();
+        self.assert_contract_running();
+        assert!(amount.0 > 0, "{}", ERR29_ILLEGAL_WITHDRAW_AMOUNT);
+        let sender_id = env::predecessor_account_id();
+        let mut account = self.internal_unwrap_account(&sender_id);
+        self.internal_topup_storage(&sender_id, &mut account, amount.0, true);
+        self.internal_save_account(&sender_id, account);
+    }
+
     /// Unregister given token from user's account deposit.
     /// Panics if the balance of any given token is non 0.
     #[payable]
@@ -382,7 +437,65 @@ impl Contract {
             account.unregister(&token_id);
         }
         self.internal_save_account(&sender_id, account);
-        self.internal_send_tokens(&sender_id, &token_id, amount, skip_unwrap_near)
+        self.internal_send_tokens(&sender_id, &sender_id, &token_id, amount, skip_unwrap_near)
+    }
+
+    /// Withdraws at most MAX_WITHDRAW_BATCH_TOKENS tokens in one call, amount 0 means the full balance of that token.
+    /// Each token is sent the same way as withdraw, wNEAR is unwrapped unless skip_unwrap_near,
+    /// a failed one is reverted to deposit or lostfound by its own callback.
+    #[payable]
+    pub fn withdraw_batch(
+        &mut self,
+        token_amounts: Vec<(ValidAccountId, U128)>,
+        unregister: Option<bool>,
+        skip_unwrap_near: Option<bool>,
+    ) -> Promise {
+        assert_one_yocto();
+        self.assert_contract_running();
+        assert!(
+            !token_amounts.is_empty() && token_amounts.len() <= MAX_WITHDRAW_BATCH_TOKENS,
+            "{}", ERR76_INVALID_PARAMS
+        );
+        assert!(
+            env::prepaid_gas() - env::used_gas()
+                >= (GAS_FOR_NEAR_WITHDRAW + GAS_FOR_CB_NEAR_TRANSFER) * token_amounts.len() as Gas
+                    + GAS_FOR_CB_FT_TRANSFER * (token_amounts.len() as Gas + 1)
+                    + GAS_FOR_BASIC_OP,
+            "{}", ERR37_NOT_ENOUGH_GAS
+        );
+        let token_ids: Vec<ValidAccountId> = token_amounts.iter().map(|(token_id, _)| token_id.clone()).collect();
+        check_token_duplicates(&token_ids);
+        let token_ids: Vec<AccountId> = token_ids.into_iter().map(|t| t.into()).collect();
+        // feature frozenlist
+        self.assert_no_frozen_tokens(&token_ids);
+        let sender_id = env::predecessor_account_id();
+        let mut account = self.internal_unwrap_account(&sender_id);
+
+        let mut amounts = vec![];
+        for (token_id, (_, amount)) in token_ids.iter().zip(token_amounts.into_iter()) {
+            let mut amount: u128 = amount.into();
+            if amount == 0 {
+                amount = account.get_balance(token_id).expect(ERR21_TOKEN_NOT_REG);
+            }
+            assert!(amount > 0, "{}", ERR29_ILLEGAL_WITHDRAW_AMOUNT);
+            account.withdraw(token_id, amount);
+            if unregister == Some(true) {
+                account.unregister(token_id);
+            }
+            amounts.push(amount);
+        }
+        self.internal_save_account(&sender_id, account);
+
+        let mut promise = self.internal_send_tokens(&sender_id, &sender_id, &token_ids[0], amounts[0], skip_unwrap_near);
+        for (token_id, amount) in token_ids.iter().zip(amounts.iter()).skip(1) {
+            promise = promise.and(self.internal_send_tokens(&sender_id, &sender_id, token_id, *amount, skip_unwrap_near));
+        }
+        promise.then(ext_self::exchange_callback_post_withdraw_batch(
+            token_ids.len() as u64,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_CB_FT_TRANSFER * (token_ids.len() as Gas + 1),
+        ))
     }
 
     #[private]
@@ -390,6 +503,7 @@ impl Contract {
         &mut self,
         sender_id: AccountId,
         amount: U128,
+        receiver_id: Option<AccountId>,
     ) -> U128 {
         assert_eq!(
             env::promise_results_count(),
@@ -400,7 +514,7 @@ impl Contract {
         match env::promise_result(0) {
             PromiseResult::NotReady => unreachable!(),
             PromiseResult::Successful(_) => {
-                Promise::new(sender_id).transfer(amount.into());
+                Promise::new(receiver_id.unwrap_or(sender_id)).transfer(amount.into());
                 amount
             },
             PromiseResult::Failed => {
@@ -411,6 +525,71 @@ impl Contract {
         }
     }
         
+    /// If credit_account, the unwrapped NEAR is credited to the account's storage here,
+    /// and a failed unwrapping gives the wNEAR back to the account.
+    /// Otherwise the NEAR has been used before the unwrapping, by the account's storage deposit or the storage of the call,
+    /// a failed unwrapping leaves the wNEAR with the exchange for that storage, recorded in owner's lostfound.
+    #[private]
+    pub fn exchange_callback_post_storage_topup(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        credit_account: bool,
+    ) -> U128 {
+        assert_eq!(
+            env::promise_results_count(),
+            1,
+            "{}",
+            ERR25_CALLBACK_POST_WITHDRAW_INVALID
+        );
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                if credit_account {
+                    if let Some(mut account) = self.internal_get_account(&account_id) {
+                        // more storage deposit never fails the storage check
+                        account.near_amount += amount.0;
+                        self.accounts.insert(&account_id, &account.into());
+                    } else {
+                        Promise::new(account_id).transfer(amount.0);
+                    }
+                }
+                amount
+            },
+            PromiseResult::Failed => {
+                log!("Storage top-up unwrap of {} for {} failed.", amount.0, account_id);
+                let token_id = self.wnear_id.as_ref().unwrap().clone();
+                if credit_account {
+                    self.internal_handle_fail_in_withdraw_callback(&account_id, &token_id, amount.0);
+                } else {
+                    self.internal_lostfound(&token_id, amount.0);
+                }
+                0.into()
+            }
+        }
+    }
+
+    /// Returns the amounts actually sent, in the order of token_ids.
+    /// Failed transfers have been reverted by the callbacks of internal_send_tokens, which return 0 for them.
+    #[private]
+    pub fn exchange_callback_post_withdraw_batch(&mut self, token_count: u64) -> Vec<U128> {
+        assert_eq!(
+            env::promise_results_count(),
+            token_count,
+            "{}",
+            ERR25_CALLBACK_POST_WITHDRAW_INVALID
+        );
+        (0..token_count)
+            .map(|idx| match env::promise_result(idx) {
+                PromiseResult::NotReady => unreachable!(),
+                PromiseResult::Successful(result) => {
+                    near_sdk::serde_json::from_slice::<U128>(&result).unwrap_or(U128(0))
+                }
+                PromiseResult::Failed => 0.into(),
+            })
+            .collect()
+    }
+
     #[private]
     pub fn exchange_callback_post_withdraw(
         &mut self,
@@ -439,7 +618,12 @@ impl Contract {
 
     pub(crate) fn internal_handle_fail_in_withdraw_callback(&mut self, sender_id: &AccountId, token_id: &AccountId, amount: u128) {
         // This reverts the changes from withdraw function.
-        // If account doesn't exit, deposits to the owner's account as lostfound.
+        self.internal_deposit_or_lostfound(sender_id, token_id, amount);
+    }
+
+    /// Credit amount of token_id to the account in a callback, where storage can't be attached.
+    /// If account doesn't exist or can't hold one more token, deposits to user or owner lostfound.
+    pub(crate) fn internal_deposit_or_lostfound(&mut self, sender_id: &AccountId, token_id: &AccountId, amount: u128) {
         let mut failed = false;
         if let Some(mut account) = self.internal_get_account(sender_id) {
             if account.deposit_with_storage_check(token_id, amount) {
@@ -492,9 +676,16 @@ impl Contract {
     }
 
     /// Checks that account has enough storage to be stored and saves it into collection.
+    /// A storage shortage is covered by auto storage top-up from wNEAR if the account opted in.
     /// This should be only place to directly use `self.accounts`.
-    pub(crate) fn internal_save_account(&mut self, account_id: &AccountId, account: Account) {
+    pub(crate) fn internal_save_account(&mut self, account_id: &AccountId, mut account: Account) {
+        let storage_usage = account.storage_usage();
+        if storage_usage > account.near_amount {
+            let shortage = storage_usage - account.near_amount;
+            account.near_amount += self.internal_auto_topup_storage(account_id, &mut account, shortage);
+        }
         account.assert_storage_usage();
+        self.assert_locked_deposits(account_id, &account);
         self.accounts.insert(&account_id, &account.into());
     }
 
@@ -507,10 +698,54 @@ impl Contract {
     }
     
 
+    /// If the account opted in, withdraws `shortage` wNEAR (limited by the cap) from its deposit
+    /// and unwraps it into the exchange's NEAR, returns the amount the caller can use right away,
+    /// either for the account's storage deposit or for the storage of the current call.
+    /// Returns 0 if it can't cover the whole shortage, so the caller still fails on storage.
+    pub(crate) fn internal_auto_topup_storage(&mut self, account_id: &AccountId, account: &mut Account, shortage: Balance) -> Balance {
+        if !account.auto_storage_topup || shortage == 0 || shortage > read_storage_topup_cap() {
+            return 0;
+        }
+        let wnear_id = match self.wnear_id.as_ref() {
+            Some(wnear_id) => wnear_id.clone(),
+            None => return 0,
+        };
+        if account.get_balance(&wnear_id).unwrap_or(0) < shortage {
+            return 0;
+        }
+        self.internal_topup_storage(account_id, account, shortage, false);
+        shortage
+    }
+
+    /// Withdraws amount wNEAR from the account's deposit and unwraps it into the exchange's NEAR,
+    /// see exchange_callback_post_storage_topup for credit_account.
+    pub(crate) fn internal_topup_storage(&mut self, account_id: &AccountId, account: &mut Account, amount: Balance, credit_account: bool) {
+        let wnear_id = self.wnear_id.clone().expect(ERR30_WNEAR_NOT_SET);
+        account.withdraw(&wnear_id, amount);
+        ext_wrap_near::near_withdraw(
+            U128(amount),
+            &wnear_id,
+            1,
+            GAS_FOR_NEAR_WITHDRAW,
+        )
+        .then(ext_self::exchange_callback_post_storage_topup(
+            account_id.clone(),
+            U128(amount),
+            credit_account,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_CB_NEAR_TRANSFER,
+        ));
+        log!("Top up {} storage with {} wNEAR", account_id, amount);
+    }
+
     /// Registers account in deposited amounts with given amount of $NEAR.
     /// If account already exists, adds amount to it.
     /// This should be used when it's known that storage is prepaid.
     pub(crate) fn internal_register_account(&mut self, account_id: &AccountId, amount: Balance) {
+        if !self.accounts.contains_key(account_id) {
+            update_storage_counters(|storage_counters| storage_counters.account_change += 1);
+        }
         let mut account = self.internal_unwrap_or_default_account(&account_id);
         account.near_amount += amount;
         self.internal_save_account(&account_id, account);
@@ -582,6 +817,7 @@ impl Contract {
     pub(crate) fn internal_send_tokens(
         &self,
         sender_id: &AccountId,
+        receiver_id: &AccountId,
         token_id: &AccountId,
         amount: Balance,
         skip_unwrap_near: Option<bool>,
@@ -601,13 +837,14 @@ impl Contract {
             .then(ext_self::exchange_callback_post_withdraw_near(
                 sender_id.clone(),
                 U128(amount),
+                Some(receiver_id.clone()),
                 &env::current_account_id(),
                 0,
                 GAS_FOR_CB_NEAR_TRANSFER,
             ))
         } else {
             ext_fungible_token::ft_transfer(
-                sender_id.clone(),
+                receiver_id.clone(),
                 U128(amount),
                 None,
                 token_id,
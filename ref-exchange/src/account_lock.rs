@@ -0,0 +1,250 @@
+//! Deposit locking lets an integration escrow part of a user's deposit, e.g. while an off-chain order is pending.
+//! The user authorizes a locker contract, which can then lock and unlock the user's deposits.
+//! Locked deposit can't leave the account: every save of the account checks it against the locks.
+//! A lock lasts at most MAX_LOCK_DURATION_SEC, an expired lock no longer counts and is dropped on the next write,
+//! the owner or guardians can release the locks of an account earlier.
+
+use near_sdk::Timestamp;
+use crate::utils::to_nano;
+use crate::*;
+
+/// Longest a lock can last, the locker locks again to extend it.
+pub const MAX_LOCK_DURATION_SEC: u32 = 30 * 24 * 3600;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct DepositLock {
+    pub amount: Balance,
+    pub expires_at: Timestamp,
+}
+
+impl DepositLock {
+    pub fn is_active(&self) -> bool {
+        self.expires_at > env::block_timestamp()
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct AccountLocks {
+    /// Lockers allowed to create new locks, revoking one keeps its existing locks.
+    pub authorized_lockers: Vec<AccountId>,
+    /// token_id -> locker_id -> lock
+    pub locks: HashMap<AccountId, HashMap<AccountId, DepositLock>>,
+}
+
+impl AccountLocks {
+    /// Sum of the unexpired locks on token_id.
+    pub fn get_locked(&self, token_id: &AccountId) -> Balance {
+        self.locks
+            .get(token_id)
+            .map(|lockers| lockers.values().filter(|lock| lock.is_active()).map(|lock| lock.amount).sum())
+            .unwrap_or(0)
+    }
+
+    pub fn remove_expired(&mut self) {
+        for lockers in self.locks.values_mut() {
+            lockers.retain(|_, lock| lock.is_active());
+        }
+        self.locks.retain(|_, lockers| !lockers.is_empty());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.authorized_lockers.is_empty() && self.locks.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct DepositLockInfo {
+    pub token_id: AccountId,
+    pub locker_id: AccountId,
+    pub amount: U128,
+    pub expires_at: U64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct AccountLocksInfo {
+    pub authorized_lockers: Vec<AccountId>,
+    pub locks: Vec<DepositLockInfo>,
+}
+
+/// Same as lostfound, the LookupMap only needs the prefix.
+pub fn read_account_locks() -> LookupMap<AccountId, AccountLocks> {
+    LookupMap::new(StorageKey::AccountLocks)
+}
+
+pub fn write_account_locks(account_id: &AccountId, account_locks: &AccountLocks) {
+    let mut all_locks = read_account_locks();
+    if account_locks.is_empty() {
+        all_locks.remove(account_id);
+    } else {
+        all_locks.insert(account_id, account_locks);
+    }
+}
+
+impl Contract {
+    /// Called whenever the account is saved, so withdraw, actions, mft_transfer
+    /// and liquidity operations can only use the unlocked part of deposits.
+    pub(crate) fn assert_locked_deposits(&self, account_id: &AccountId, account: &Account) {
+        if let Some(account_locks) = read_account_locks().get(account_id) {
+            for token_id in account_locks.locks.keys() {
+                assert!(
+                    account.get_balance(token_id).unwrap_or(0) >= account_locks.get_locked(token_id),
+                    "{}",
+                    ERR161_DEPOSIT_LOCKED
+                );
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Allow locker_id to lock the sender's deposits.
+    /// Attached near should cover the extra storage, the left over is refunded.
+    #[payable]
+    pub fn authorize_locker(&mut self, locker_id: ValidAccountId) {
+        self.assert_contract_running();
+        let sender_id = env::predecessor_account_id();
+        self.internal_unwrap_account(&sender_id);
+        let prev_storage = env::storage_usage();
+        let mut account_locks = read_account_locks().get(&sender_id).unwrap_or_default();
+        let locker_id: AccountId = locker_id.into();
+        if !account_locks.authorized_lockers.contains(&locker_id) {
+            account_locks.authorized_lockers.push(locker_id.clone());
+        }
+        write_account_locks(&sender_id, &account_locks);
+        self.internal_check_storage(prev_storage);
+        log!("{} authorized locker {}", sender_id, locker_id);
+    }
+
+    /// Stop locker_id from creating new locks,
+    /// its existing locks stay until they expire or are unlocked by it.
+    #[payable]
+    pub fn revoke_locker(&mut self, locker_id: ValidAccountId) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let mut account_locks = read_account_locks().get(&sender_id).expect(ERR160_LOCKER_NOT_AUTHORIZED);
+        let index = account_locks.authorized_lockers.iter()
+            .position(|id| id == locker_id.as_ref())
+            .expect(ERR160_LOCKER_NOT_AUTHORIZED);
+        account_locks.authorized_lockers.remove(index);
+        account_locks.remove_expired();
+        write_account_locks(&sender_id, &account_locks);
+        log!("{} revoked locker {}", sender_id, locker_id);
+    }
+
+    /// Called by an authorized locker to lock amount of account's unlocked deposit for duration_sec.
+    /// Locking more on an existing lock of the locker keeps the later expiry for the whole amount.
+    /// Attached near should cover the extra storage, the left over is refunded.
+    #[payable]
+    pub fn lock_deposit(&mut self, account_id: ValidAccountId, token_id: ValidAccountId, amount: U128, duration_sec: u32) {
+        self.assert_contract_running();
+        assert!(amount.0 > 0, "{}", ERR29_ILLEGAL_WITHDRAW_AMOUNT);
+        assert!(duration_sec > 0 && duration_sec <= MAX_LOCK_DURATION_SEC, "{}", ERR163_INVALID_LOCK_DURATION);
+        let locker_id = env::predecessor_account_id();
+        let account_id: AccountId = account_id.into();
+        let token_id: AccountId = token_id.into();
+        let account = self.internal_unwrap_account(&account_id);
+        let prev_storage = env::storage_usage();
+        let mut account_locks = read_account_locks().get(&account_id).unwrap_or_default();
+        assert!(account_locks.authorized_lockers.contains(&locker_id), "{}", ERR160_LOCKER_NOT_AUTHORIZED);
+        account_locks.remove_expired();
+        let locked = account_locks.get_locked(&token_id);
+        assert!(
+            account.get_balance(&token_id).unwrap_or(0) >= locked + amount.0,
+            "{}",
+            ERR161_DEPOSIT_LOCKED
+        );
+        let expires_at = env::block_timestamp() + to_nano(duration_sec);
+        let lock = account_locks.locks
+            .entry(token_id.clone())
+            .or_default()
+            .entry(locker_id.clone())
+            .or_insert(DepositLock { amount: 0, expires_at });
+        lock.amount += amount.0;
+        lock.expires_at = std::cmp::max(lock.expires_at, expires_at);
+        write_account_locks(&account_id, &account_locks);
+        self.internal_check_storage(prev_storage);
+        log!("Locker {} locked {} {} of {}", locker_id, amount.0, token_id, account_id);
+    }
+
+    /// Called by the locker to release amount locked by itself.
+    #[payable]
+    pub fn unlock_deposit(&mut self, account_id: ValidAccountId, token_id: ValidAccountId, amount: U128) {
+        assert_one_yocto();
+        let locker_id = env::predecessor_account_id();
+        let account_id: AccountId = account_id.into();
+        let token_id: AccountId = token_id.into();
+        let mut account_locks = read_account_locks().get(&account_id).expect(ERR162_NOT_ENOUGH_LOCKED);
+        account_locks.remove_expired();
+        let token_locks = account_locks.locks.get_mut(&token_id).expect(ERR162_NOT_ENOUGH_LOCKED);
+        let locked = token_locks.get(&locker_id).map(|lock| lock.amount).unwrap_or(0);
+        assert!(amount.0 > 0 && locked >= amount.0, "{}", ERR162_NOT_ENOUGH_LOCKED);
+        if locked == amount.0 {
+            token_locks.remove(&locker_id);
+        } else {
+            token_locks.get_mut(&locker_id).unwrap().amount -= amount.0;
+        }
+        if token_locks.is_empty() {
+            account_locks.locks.remove(&token_id);
+        }
+        write_account_locks(&account_id, &account_locks);
+        log!("Locker {} unlocked {} {} of {}", locker_id, amount.0, token_id, account_id);
+    }
+
+    /// Release all locks of the account, e.g. those of a misbehaving locker, by owner or guardians.
+    #[payable]
+    pub fn release_deposit_locks(&mut self, account_id: ValidAccountId) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        let account_id: AccountId = account_id.into();
+        let mut account_locks = read_account_locks().get(&account_id).expect(ERR162_NOT_ENOUGH_LOCKED);
+        account_locks.locks.clear();
+        write_account_locks(&account_id, &account_locks);
+        log!("{} released deposit locks of {}", env::predecessor_account_id(), account_id);
+    }
+
+    /// Unexpired locks of the account.
+    pub fn get_account_locks(&self, account_id: ValidAccountId) -> Option<AccountLocksInfo> {
+        read_account_locks().get(account_id.as_ref()).map(|mut account_locks| {
+            account_locks.remove_expired();
+            AccountLocksInfo {
+                authorized_lockers: account_locks.authorized_lockers,
+                locks: account_locks.locks.into_iter()
+                    .flat_map(|(token_id, lockers)| {
+                        lockers.into_iter().map(move |(locker_id, lock)| DepositLockInfo {
+                            token_id: token_id.clone(),
+                            locker_id,
+                            amount: U128(lock.amount),
+                            expires_at: U64(lock.expires_at),
+                        })
+                    })
+                    .collect(),
+            }
+        })
+    }
+
+    pub fn get_locked_deposit(&self, account_id: ValidAccountId, token_id: ValidAccountId) -> U128 {
+        U128(
+            read_account_locks()
+                .get(account_id.as_ref())
+                .map(|account_locks| account_locks.get_locked(token_id.as_ref()))
+                .unwrap_or(0)
+        )
+    }
+
+    /// Deposits of the account minus the locked part.
+    pub fn get_available_deposits(&self, account_id: ValidAccountId) -> HashMap<AccountId, U128> {
+        let account_locks = read_account_locks().get(account_id.as_ref()).unwrap_or_default();
+        self.get_deposits(account_id)
+            .into_iter()
+            .map(|(token_id, amount)| {
+                let locked = account_locks.get_locked(&token_id);
+                (token_id, U128(amount.0.saturating_sub(locked)))
+            })
+            .collect()
+    }
+}
@@ -1,8 +1,11 @@
-use near_sdk::collections::{UnorderedMap, LookupMap};
+use near_sdk::collections::{UnorderedMap, UnorderedSet, LookupMap};
 use near_sdk::json_types::{ValidAccountId, U128};
 use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, Balance};
 use crate::*;
 
+/// Max tokens claimed in one claim_lostfound_batch, each chains a transfer and its callback.
+pub const MAX_LOSTFOUND_BATCH_TOKENS: usize = 5;
+
 
 pub type LostfoundAccount = UnorderedMap<AccountId, Balance>;
 
@@ -21,6 +24,22 @@ pub fn read_lostfound() -> LookupMap<AccountId, LostfoundAccount> {
     LookupMap::new(StorageKey::LostfoundAccounts)
 }
 
+/// As lostfound itself can't be iterated, accounts holding lostfound tokens are indexed here.
+pub fn read_lostfound_account_index() -> UnorderedSet<AccountId> {
+    if let Some(content) = env::storage_read(LOSTFOUND_ACCOUNT_INDEX.as_bytes()) {
+        UnorderedSet::try_from_slice(&content).expect("deserialize lostfound account index failed.")
+    } else {
+        UnorderedSet::new(StorageKey::LostfoundAccountIndex)
+    }
+}
+
+pub fn write_lostfound_account_index(lostfound_account_index: UnorderedSet<AccountId>) {
+    env::storage_write(
+        LOSTFOUND_ACCOUNT_INDEX.as_bytes(),
+        &lostfound_account_index.try_to_vec().unwrap(),
+    );
+}
+
 
 #[near_bindgen]
 impl Contract {
@@ -55,14 +74,98 @@ impl Contract {
         let token_id: AccountId = token_id.into();
         self.assert_no_frozen_tokens(&[token_id.clone()]);
         let sender_id = env::predecessor_account_id();
+        self.internal_claim_lostfound(&sender_id, &token_id, &sender_id, None)
+    }
 
-        let amount = self.remove_lostfound_token(&sender_id, &token_id);
-        assert!(amount > 0, "{}", ERR29_ILLEGAL_WITHDRAW_AMOUNT);
-        self.internal_send_tokens(&sender_id, &token_id, amount, None)
+    /// Claim lostfound token to another receiver,
+    /// if the transfer fails, the token goes back to sender as a failed withdraw does.
+    #[payable]
+    pub fn claim_lostfound_to(&mut self, token_id: ValidAccountId, receiver_id: ValidAccountId, skip_unwrap_near: Option<bool>) -> Promise {
+        assert_one_yocto();
+        self.assert_contract_running();
+        let token_id: AccountId = token_id.into();
+        self.assert_no_frozen_tokens(&[token_id.clone()]);
+        let sender_id = env::predecessor_account_id();
+        self.internal_claim_lostfound(&sender_id, &token_id, receiver_id.as_ref(), skip_unwrap_near)
+    }
+
+    /// Claim at most MAX_LOSTFOUND_BATCH_TOKENS lostfound tokens in one call,
+    /// to sender itself or to receiver_id if given.
+    #[payable]
+    pub fn claim_lostfound_batch(&mut self, token_ids: Vec<ValidAccountId>, receiver_id: Option<ValidAccountId>, skip_unwrap_near: Option<bool>) -> Promise {
+        assert_one_yocto();
+        self.assert_contract_running();
+        assert!(!token_ids.is_empty() && token_ids.len() <= MAX_LOSTFOUND_BATCH_TOKENS, "{}", ERR76_INVALID_PARAMS);
+        check_token_duplicates(&token_ids);
+        let token_ids: Vec<AccountId> = token_ids.into_iter().map(|t| t.into()).collect();
+        self.assert_no_frozen_tokens(&token_ids);
+        let sender_id = env::predecessor_account_id();
+        let receiver_id: AccountId = receiver_id.map(|r| r.into()).unwrap_or(sender_id.clone());
+        let mut promise = self.internal_claim_lostfound(&sender_id, &token_ids[0], &receiver_id, skip_unwrap_near);
+        for token_id in token_ids.iter().skip(1) {
+            promise = promise.and(self.internal_claim_lostfound(&sender_id, token_id, &receiver_id, skip_unwrap_near));
+        }
+        promise
+    }
+
+    /// Guardians can retry delivery of lostfound token to its owner,
+    /// usually after the owner registered storage on the token.
+    #[payable]
+    pub fn redeliver_lostfound(&mut self, account_id: ValidAccountId, token_id: ValidAccountId) -> Promise {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        self.assert_contract_running();
+        let token_id: AccountId = token_id.into();
+        self.assert_no_frozen_tokens(&[token_id.clone()]);
+        self.internal_claim_lostfound(account_id.as_ref(), &token_id, account_id.as_ref(), None)
+    }
+
+    /// Index accounts holding lostfound tokens before the index was introduced.
+    #[payable]
+    pub fn sync_lostfound_account_index(&mut self, account_ids: Vec<ValidAccountId>) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        let lostfound = read_lostfound();
+        let mut lostfound_account_index = read_lostfound_account_index();
+        for account_id in account_ids {
+            if lostfound.contains_key(account_id.as_ref()) {
+                lostfound_account_index.insert(account_id.as_ref());
+            }
+        }
+        write_lostfound_account_index(lostfound_account_index);
+    }
+
+    pub fn get_lostfound_account_count(&self) -> u64 {
+        read_lostfound_account_index().len()
+    }
+
+    /// Paged view of accounts holding lostfound tokens with all their lostfound tokens.
+    pub fn list_lostfound_accounts(&self, from_index: Option<u64>, limit: Option<u64>) -> HashMap<AccountId, HashMap<AccountId, U128>> {
+        let lostfound_account_index = read_lostfound_account_index();
+        let keys = lostfound_account_index.as_vector();
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(keys.len());
+        (from_index..std::cmp::min(keys.len(), from_index + limit))
+            .filter_map(|idx| {
+                let account_id = keys.get(idx).unwrap();
+                self.get_lostfound_account(&account_id).map(|account| {
+                    let tokens = account.iter().map(|(token_id, amount)| (token_id, U128(amount))).collect();
+                    (account_id, tokens)
+                })
+            })
+            .collect()
     }
 }
 
 impl Contract {
+    /// Take out all of the token in account's lostfound and send to receiver_id,
+    /// a failed transfer is handled as a failed withdraw of the account.
+    fn internal_claim_lostfound(&mut self, account_id: &AccountId, token_id: &AccountId, receiver_id: &AccountId, skip_unwrap_near: Option<bool>) -> Promise {
+        let amount = self.remove_lostfound_token(account_id, token_id);
+        assert!(amount > 0, "{}", ERR29_ILLEGAL_WITHDRAW_AMOUNT);
+        self.internal_send_tokens(account_id, receiver_id, token_id, amount, skip_unwrap_near)
+    }
+
     pub fn get_lostfound_account(&self, account_id: &AccountId) -> Option<LostfoundAccount> {
         let lostfound = read_lostfound();
         lostfound.get(account_id)
@@ -86,8 +189,14 @@ impl Contract {
                 account_id: account_id.clone(),
             }));
         let old_value = lostfound_account.get(token_id).unwrap_or(0_u128);
-        lostfound_account.insert(token_id, &(old_value+amount));
+        if lostfound_account.insert(token_id, &(old_value+amount)).is_none() {
+            update_storage_counters(|storage_counters| storage_counters.lostfound_entry_change += 1);
+        }
         lostfound.insert(account_id, &lostfound_account);
+        let mut lostfound_account_index = read_lostfound_account_index();
+        if lostfound_account_index.insert(account_id) {
+            write_lostfound_account_index(lostfound_account_index);
+        }
         old_value
     }
 
@@ -98,11 +207,22 @@ impl Contract {
             .unwrap_or_else(|| UnorderedMap::new(StorageKey::LostfoundAccountTokens {
                 account_id: account_id.clone(),
             }));
-        let value = lostfound_account.remove(token_id).unwrap_or(0_u128);
+        let value = if let Some(value) = lostfound_account.remove(token_id) {
+            update_storage_counters(|storage_counters| {
+                storage_counters.lostfound_entry_change -= 1;
+            });
+            value
+        } else {
+            0
+        };
         if lostfound_account.len() > 0 {
             lostfound.insert(account_id, &lostfound_account);
         } else {
             lostfound.remove(account_id);
+            let mut lostfound_account_index = read_lostfound_account_index();
+            if lostfound_account_index.remove(account_id) {
+                write_lostfound_account_index(lostfound_account_index);
+            }
         }
         value
     }
@@ -19,6 +19,8 @@ pub struct SwapAction {
     pub token_out: AccountId,
     /// Required minimum amount of token_out.
     pub min_amount_out: U128,
+    /// Max allowed price impact in bps, measured by the pool price before and after this swap.
+    pub max_price_impact_bps: Option<u32>,
 }
 
 /// Single swap by output action.
@@ -37,6 +39,8 @@ pub struct SwapByOutputAction {
     pub token_out: AccountId,
     /// The maximum amount of the input token that can be used for the swap.
     pub max_amount_in: Option<U128>,
+    /// Max allowed price impact in bps, measured by the pool price before and after this swap.
+    pub max_price_impact_bps: Option<u32>,
 }
 
 /// Single action. Allows to execute sequence of various actions initiated by an account.
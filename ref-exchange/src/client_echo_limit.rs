@@ -64,7 +64,75 @@ fn matches_wildcard_pattern(whitelist: &UnorderedSet<String>, account_id: &Accou
     false
 }
 
-pub fn assert_client_echo_valid(token_id: &AccountId, sender_id: &AccountId) {
+/// Client echo permissions of senders matching a pattern.
+/// A pattern is either an exact account id or a wildcard `*.suffix` matching all sub-accounts of suffix.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct ClientEchoPolicy {
+    /// Token patterns the sender can swap in with client echo, None means any token.
+    pub allowed_tokens: Option<Vec<String>>,
+    /// Prefixes the client_echo message must start with, None means any message.
+    pub allowed_msg_prefixes: Option<Vec<String>>,
+    /// Upper bound of extra_tgas_for_client_echo, None means no limit.
+    pub max_extra_tgas: Option<u32>,
+}
+
+pub fn read_ce_policies_from_storage() -> UnorderedMap<String, ClientEchoPolicy> {
+    if let Some(content) = env::storage_read(CLIENT_ECHO_SENDER_POLICY.as_bytes()) {
+        UnorderedMap::try_from_slice(&content).expect("deserialize client echo sender policy failed.")
+    } else {
+        UnorderedMap::new(StorageKey::ClientEchoSenderPolicy)
+    }
+}
+
+pub fn write_ce_policies_to_storage(client_echo_policies: UnorderedMap<String, ClientEchoPolicy>) {
+    env::storage_write(
+        CLIENT_ECHO_SENDER_POLICY.as_bytes(),
+        &client_echo_policies.try_to_vec().unwrap(),
+    );
+}
+
+/// Whether the pattern can be looked up in the policy table,
+/// legacy patterns such as `*near` can't and stay in the legacy whitelists.
+fn is_indexable_pattern(pattern: &str) -> bool {
+    !pattern.starts_with('*') || (pattern.starts_with("*.") && pattern.len() > 2)
+}
+
+fn assert_valid_pattern(pattern: &str) {
+    assert!(is_indexable_pattern(pattern), "Wildcard pattern must start with '*.'");
+}
+
+/// Patterns that could match account_id, from the most specific one:
+/// `a.b.near` gives `a.b.near`, `*.b.near`, `*.near`.
+fn candidate_patterns(account_id: &str) -> Vec<String> {
+    let mut patterns = vec![account_id.to_string()];
+    for (idx, c) in account_id.char_indices() {
+        if c == '.' {
+            patterns.push(format!("*{}", &account_id[idx..]));
+        }
+    }
+    patterns
+}
+
+/// Sorted patterns are looked up by binary search instead of scanning them one by one.
+fn matches_sorted_patterns(sorted_patterns: &[String], account_id: &str) -> bool {
+    candidate_patterns(account_id)
+        .iter()
+        .any(|pattern| sorted_patterns.binary_search(pattern).is_ok())
+}
+
+/// The policy of the most specific pattern matching sender_id.
+pub fn find_client_echo_policy(sender_id: &AccountId) -> Option<ClientEchoPolicy> {
+    let client_echo_policies = read_ce_policies_from_storage();
+    candidate_patterns(sender_id)
+        .iter()
+        .find_map(|pattern| client_echo_policies.get(pattern))
+}
+
+/// Senders without a policy fall back to the legacy whitelists,
+/// which stay in use until they are migrated.
+fn assert_legacy_client_echo_valid(token_id: &AccountId, sender_id: &AccountId) {
     let secure_sender_whitelist = read_ssw_from_storage();
 
     // If sender is in secure sender whitelist (including wildcard matches), skip token validation
@@ -79,8 +147,136 @@ pub fn assert_client_echo_valid(token_id: &AccountId, sender_id: &AccountId) {
     assert!(matches_wildcard_pattern(&client_echo_sender_id_whitelist, sender_id), "Invalid client echo sender id");
 }
 
+pub fn assert_client_echo_valid(token_id: &AccountId, sender_id: &AccountId, message: &str, extra_tgas: u32) {
+    let policy = match find_client_echo_policy(sender_id) {
+        Some(policy) => policy,
+        None => return assert_legacy_client_echo_valid(token_id, sender_id),
+    };
+    if let Some(allowed_tokens) = policy.allowed_tokens {
+        assert!(matches_sorted_patterns(&allowed_tokens, token_id), "Invalid client echo token id");
+    }
+    if let Some(allowed_msg_prefixes) = policy.allowed_msg_prefixes {
+        assert!(
+            allowed_msg_prefixes.iter().any(|prefix| message.starts_with(prefix.as_str())),
+            "Invalid client echo message"
+        );
+    }
+    if let Some(max_extra_tgas) = policy.max_extra_tgas {
+        assert!(extra_tgas <= max_extra_tgas, "Extra tgas for client echo exceeds limit");
+    }
+}
+
+/// Move the legacy whitelists into the policy table:
+/// secure senders can use any token, other senders are limited to the token whitelist.
+/// Patterns that can't be indexed are left in the legacy whitelists,
+/// so the fallback keeps matching them as before.
+pub(crate) fn internal_migrate_client_echo_whitelists() {
+    let mut client_echo_token_id_whitelist = read_ce_tw_from_storage();
+    let mut client_echo_sender_id_whitelist = read_ce_sw_from_storage();
+    let mut secure_sender_whitelist = read_ssw_from_storage();
+    if client_echo_sender_id_whitelist.is_empty() && secure_sender_whitelist.is_empty() {
+        return;
+    }
+    let mut allowed_tokens = client_echo_token_id_whitelist.to_vec();
+    allowed_tokens.sort();
+    let tokens_indexable = allowed_tokens.iter().all(|token_pattern| is_indexable_pattern(token_pattern));
+    let mut client_echo_policies = read_ce_policies_from_storage();
+
+    for sender_pattern in client_echo_sender_id_whitelist.to_vec() {
+        // a sender also covered by a secure pattern could use any token before
+        let is_secure = matches_wildcard_pattern(&secure_sender_whitelist, &sender_pattern);
+        if is_indexable_pattern(&sender_pattern) && (tokens_indexable || is_secure) {
+            client_echo_policies.insert(&sender_pattern, &ClientEchoPolicy {
+                allowed_tokens: if is_secure { None } else { Some(allowed_tokens.clone()) },
+                allowed_msg_prefixes: None,
+                max_extra_tgas: None,
+            });
+            client_echo_sender_id_whitelist.remove(&sender_pattern);
+        }
+    }
+    for sender_pattern in secure_sender_whitelist.to_vec() {
+        if is_indexable_pattern(&sender_pattern) {
+            client_echo_policies.insert(&sender_pattern, &ClientEchoPolicy {
+                allowed_tokens: None,
+                allowed_msg_prefixes: None,
+                max_extra_tgas: None,
+            });
+            secure_sender_whitelist.remove(&sender_pattern);
+        }
+    }
+    // the token whitelist is only used by senders left in the legacy sender whitelist
+    if client_echo_sender_id_whitelist.is_empty() {
+        client_echo_token_id_whitelist.clear();
+    }
+    write_ce_policies_to_storage(client_echo_policies);
+    write_ce_tw_to_storage(client_echo_token_id_whitelist);
+    write_ce_sw_to_storage(client_echo_sender_id_whitelist);
+    write_ssw_to_storage(secure_sender_whitelist);
+}
+
 #[near_bindgen]
 impl Contract {
+    /// Insert or replace the client echo policy of senders matching sender_pattern.
+    #[payable]
+    pub fn set_client_echo_policy(&mut self, sender_pattern: String, policy: ClientEchoPolicy) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        assert_valid_pattern(&sender_pattern);
+        let mut policy = policy;
+        if let Some(allowed_tokens) = policy.allowed_tokens.as_mut() {
+            for token_pattern in allowed_tokens.iter() {
+                assert_valid_pattern(token_pattern);
+            }
+            allowed_tokens.sort();
+            allowed_tokens.dedup();
+        }
+        let mut client_echo_policies = read_ce_policies_from_storage();
+        client_echo_policies.insert(&sender_pattern, &policy);
+        write_ce_policies_to_storage(client_echo_policies);
+        log!("Set client echo policy of {}", sender_pattern);
+    }
+
+    #[payable]
+    pub fn remove_client_echo_policy(&mut self, sender_patterns: Vec<String>) {
+        assert_one_yocto();
+        self.assert_owner();
+        let mut client_echo_policies = read_ce_policies_from_storage();
+        for sender_pattern in sender_patterns {
+            assert!(client_echo_policies.remove(&sender_pattern).is_some(), "Invalid sender pattern");
+        }
+        write_ce_policies_to_storage(client_echo_policies);
+    }
+
+    /// Run the legacy whitelist migration again,
+    /// e.g. after the deprecated methods below changed the legacy whitelists.
+    #[payable]
+    pub fn migrate_client_echo_whitelists(&mut self) {
+        assert_one_yocto();
+        self.assert_owner();
+        internal_migrate_client_echo_whitelists();
+    }
+
+    pub fn get_client_echo_policy(&self, sender_pattern: String) -> Option<ClientEchoPolicy> {
+        read_ce_policies_from_storage().get(&sender_pattern)
+    }
+
+    /// The policy applied to sender_id, resolved from the most specific matching pattern.
+    pub fn get_effective_client_echo_policy(&self, sender_id: ValidAccountId) -> Option<ClientEchoPolicy> {
+        find_client_echo_policy(sender_id.as_ref())
+    }
+
+    pub fn list_client_echo_policies(&self, from_index: Option<u64>, limit: Option<u64>) -> HashMap<String, ClientEchoPolicy> {
+        let client_echo_policies = read_ce_policies_from_storage();
+        let keys = client_echo_policies.keys_as_vector();
+        let values = client_echo_policies.values_as_vector();
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(keys.len());
+        (from_index..std::cmp::min(keys.len(), from_index + limit))
+            .map(|idx| (keys.get(idx).unwrap(), values.get(idx).unwrap()))
+            .collect()
+    }
+
+    /// Deprecated, use set_client_echo_policy instead.
     #[payable]
     pub fn extend_client_echo_token_id_whitelist(&mut self, token_ids: Vec<String>) {
         assert_one_yocto();
@@ -96,6 +292,7 @@ impl Contract {
         write_ce_tw_to_storage(client_echo_token_id_whitelist);
     }
 
+    /// Deprecated, use remove_client_echo_policy instead.
     #[payable]
     pub fn remove_client_echo_token_id_whitelist(&mut self, token_ids: Vec<String>) {
         assert_one_yocto();
@@ -108,11 +305,13 @@ impl Contract {
         write_ce_tw_to_storage(client_echo_token_id_whitelist);
     }
 
+    /// Deprecated, use list_client_echo_policies instead.
     pub fn get_client_echo_token_id_whitelist(&self) -> Vec<String> {
         let client_echo_token_id_whitelist = read_ce_tw_from_storage();
         client_echo_token_id_whitelist.to_vec()
     }
 
+    /// Deprecated, use set_client_echo_policy instead.
     #[payable]
     pub fn extend_client_echo_sender_id_whitelist(&mut self, sender_ids: Vec<String>) {
         assert_one_yocto();
@@ -125,6 +324,7 @@ impl Contract {
         write_ce_sw_to_storage(client_echo_sender_id_whitelist);
     }
 
+    /// Deprecated, use remove_client_echo_policy instead.
     #[payable]
     pub fn remove_client_echo_sender_id_whitelist(&mut self, sender_ids: Vec<String>) {
         assert_one_yocto();
@@ -137,11 +337,13 @@ impl Contract {
         write_ce_sw_to_storage(client_echo_sender_id_whitelist);
     }
 
+    /// Deprecated, use list_client_echo_policies instead.
     pub fn get_client_echo_sender_id_whitelist(&self) -> Vec<String> {
         let client_echo_sender_id_whitelist = read_ce_sw_from_storage();
         client_echo_sender_id_whitelist.to_vec()
     }
 
+    /// Deprecated, use set_client_echo_policy instead.
     #[payable]
     pub fn extend_secure_sender_whitelist(&mut self, sender_ids: Vec<String>) {
         assert_one_yocto();
@@ -157,6 +359,7 @@ impl Contract {
         write_ssw_to_storage(secure_sender_whitelist);
     }
 
+    /// Deprecated, use remove_client_echo_policy instead.
     #[payable]
     pub fn remove_secure_sender_whitelist(&mut self, sender_ids: Vec<String>) {
         assert_one_yocto();
@@ -169,6 +372,7 @@ impl Contract {
         write_ssw_to_storage(secure_sender_whitelist);
     }
 
+    /// Deprecated, use list_client_echo_policies instead.
     pub fn get_secure_sender_whitelist(&self) -> Vec<String> {
         let secure_sender_whitelist = read_ssw_from_storage();
         secure_sender_whitelist.to_vec()
@@ -181,7 +385,7 @@ mod tests {
     use near_sdk::{testing_env, MockedBlockchain, AccountId};
     use near_sdk::collections::UnorderedSet;
     use crate::{Contract, StorageKey};
-    use super::matches_wildcard_pattern;
+    use super::*;
 
     fn get_context(predecessor_account_id: near_sdk::json_types::ValidAccountId, is_view: bool) -> VMContextBuilder {
         let mut builder = VMContextBuilder::new();
@@ -505,4 +709,254 @@ mod tests {
         let account_id2: AccountId = "something.near".parse().unwrap();
         assert!(!matches_wildcard_pattern(&whitelist, &account_id2));
     }
-}
\ No newline at end of file
+
+    fn policy(allowed_tokens: Option<Vec<&str>>, allowed_msg_prefixes: Option<Vec<&str>>, max_extra_tgas: Option<u32>) -> ClientEchoPolicy {
+        ClientEchoPolicy {
+            allowed_tokens: allowed_tokens.map(|v| v.into_iter().map(|s| s.to_string()).collect()),
+            allowed_msg_prefixes: allowed_msg_prefixes.map(|v| v.into_iter().map(|s| s.to_string()).collect()),
+            max_extra_tgas,
+        }
+    }
+
+    #[test]
+    fn test_set_client_echo_policy_success() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("farm.near".to_string(), policy(Some(vec!["token2.near", "*.testnet", "token2.near"]), None, Some(20)));
+
+        let stored = contract.get_client_echo_policy("farm.near".to_string()).unwrap();
+        // tokens are kept sorted and deduplicated for binary search
+        assert_eq!(stored.allowed_tokens.unwrap(), vec!["*.testnet".to_string(), "token2.near".to_string()]);
+        assert_eq!(stored.max_extra_tgas, Some(20));
+        assert_eq!(contract.list_client_echo_policies(None, None).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wildcard pattern must start with '*.'")]
+    fn test_set_client_echo_policy_invalid_sender_wildcard() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("*near".to_string(), policy(None, None, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Wildcard pattern must start with '*.'")]
+    fn test_set_client_echo_policy_invalid_token_wildcard() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("farm.near".to_string(), policy(Some(vec!["*testnet"]), None, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "E100: no permission to invoke this")]
+    fn test_set_client_echo_policy_not_authorized() {
+        let context = get_context(accounts(1), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("farm.near".to_string(), policy(None, None, None));
+    }
+
+    #[test]
+    fn test_remove_client_echo_policy() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("farm.near".to_string(), policy(None, None, None));
+        contract.remove_client_echo_policy(vec!["farm.near".to_string()]);
+        assert!(contract.get_client_echo_policy("farm.near".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid sender pattern")]
+    fn test_remove_client_echo_policy_not_exist() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.remove_client_echo_policy(vec!["farm.near".to_string()]);
+    }
+
+    #[test]
+    fn test_candidate_patterns() {
+        assert_eq!(
+            candidate_patterns("ref.swap.near"),
+            vec!["ref.swap.near".to_string(), "*.swap.near".to_string(), "*.near".to_string()]
+        );
+        assert_eq!(candidate_patterns("near"), vec!["near".to_string()]);
+    }
+
+    #[test]
+    fn test_matches_sorted_patterns() {
+        let mut patterns = vec!["specific.near".to_string(), "*.swap.near".to_string(), "*.testnet".to_string()];
+        patterns.sort();
+
+        assert!(matches_sorted_patterns(&patterns, "specific.near"));
+        assert!(matches_sorted_patterns(&patterns, "ref.swap.near"));
+        assert!(matches_sorted_patterns(&patterns, "my-token.testnet"));
+        // wildcard doesn't match the suffix itself
+        assert!(!matches_sorted_patterns(&patterns, "swap.near"));
+        assert!(!matches_sorted_patterns(&patterns, "other.near"));
+        assert!(!matches_sorted_patterns(&[], "any.near"));
+    }
+
+    #[test]
+    fn test_find_client_echo_policy_most_specific() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("*.near".to_string(), policy(Some(vec!["token1.near"]), None, None));
+        contract.set_client_echo_policy("boost.farm.near".to_string(), policy(None, None, Some(30)));
+
+        let sender: AccountId = "boost.farm.near".to_string();
+        assert_eq!(find_client_echo_policy(&sender).unwrap().max_extra_tgas, Some(30));
+        let sender: AccountId = "other.farm.near".to_string();
+        assert_eq!(find_client_echo_policy(&sender).unwrap().allowed_tokens.unwrap(), vec!["token1.near".to_string()]);
+        let sender: AccountId = "farm.testnet".to_string();
+        assert!(find_client_echo_policy(&sender).is_none());
+    }
+
+    #[test]
+    fn test_assert_client_echo_valid() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("*.farm.near".to_string(), policy(Some(vec!["*.token.near"]), Some(vec!["{\"Free\"", "\"Free\""]), Some(20)));
+        let sender: AccountId = "boost.farm.near".to_string();
+        let token: AccountId = "usdt.token.near".to_string();
+        assert_client_echo_valid(&token, &sender, "\"Free\"", 15);
+    }
+
+    #[test]
+    fn test_assert_client_echo_valid_legacy_fallback() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.extend_client_echo_token_id_whitelist(vec!["*.token.near".to_string()]);
+        contract.extend_client_echo_sender_id_whitelist(vec!["*near".to_string()]);
+        contract.extend_secure_sender_whitelist(vec!["*.burrow.near".to_string()]);
+        let sender: AccountId = "boost.farmnear".to_string();
+        let token: AccountId = "usdt.token.near".to_string();
+        assert_client_echo_valid(&token, &sender, "\"Free\"", 15);
+        let sender: AccountId = "a.burrow.near".to_string();
+        let token: AccountId = "usdt.near".to_string();
+        assert_client_echo_valid(&token, &sender, "\"Free\"", 15);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid client echo sender id")]
+    fn test_assert_client_echo_valid_no_policy() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.extend_client_echo_token_id_whitelist(vec!["*.token.near".to_string()]);
+        let sender: AccountId = "boost.farm.near".to_string();
+        let token: AccountId = "usdt.token.near".to_string();
+        assert_client_echo_valid(&token, &sender, "\"Free\"", 15);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid client echo token id")]
+    fn test_assert_client_echo_valid_token_not_allowed() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("boost.farm.near".to_string(), policy(Some(vec!["*.token.near"]), None, None));
+        let sender: AccountId = "boost.farm.near".to_string();
+        let token: AccountId = "usdt.near".to_string();
+        assert_client_echo_valid(&token, &sender, "\"Free\"", 15);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid client echo message")]
+    fn test_assert_client_echo_valid_message_not_allowed() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("boost.farm.near".to_string(), policy(None, Some(vec!["\"Free\""]), None));
+        let sender: AccountId = "boost.farm.near".to_string();
+        let token: AccountId = "usdt.near".to_string();
+        assert_client_echo_valid(&token, &sender, "{\"Lock\":{}}", 15);
+    }
+
+    #[test]
+    #[should_panic(expected = "Extra tgas for client echo exceeds limit")]
+    fn test_assert_client_echo_valid_extra_tgas_too_much() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.set_client_echo_policy("boost.farm.near".to_string(), policy(None, None, Some(10)));
+        let sender: AccountId = "boost.farm.near".to_string();
+        let token: AccountId = "usdt.near".to_string();
+        assert_client_echo_valid(&token, &sender, "\"Free\"", 15);
+    }
+
+    #[test]
+    fn test_migrate_client_echo_whitelists() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        let mut token_whitelist = read_ce_tw_from_storage();
+        token_whitelist.insert(&"token1.near".to_string());
+        write_ce_tw_to_storage(token_whitelist);
+        let mut sender_whitelist = read_ce_sw_from_storage();
+        sender_whitelist.insert(&"farm.near".to_string());
+        write_ce_sw_to_storage(sender_whitelist);
+        let mut secure_sender_whitelist = read_ssw_from_storage();
+        secure_sender_whitelist.insert(&"*.burrow.near".to_string());
+        write_ssw_to_storage(secure_sender_whitelist);
+
+        contract.migrate_client_echo_whitelists();
+
+        assert_eq!(
+            contract.get_client_echo_policy("farm.near".to_string()).unwrap().allowed_tokens.unwrap(),
+            vec!["token1.near".to_string()]
+        );
+        assert!(contract.get_client_echo_policy("*.burrow.near".to_string()).unwrap().allowed_tokens.is_none());
+        assert_eq!(read_ce_tw_from_storage().len(), 0);
+        assert_eq!(read_ce_sw_from_storage().len(), 0);
+        assert_eq!(read_ssw_from_storage().len(), 0);
+    }
+
+    #[test]
+    fn test_migrate_client_echo_whitelists_keeps_legacy_patterns() {
+        let context = get_context(accounts(0), false);
+        testing_env!(context.build());
+
+        let mut contract = init_contract();
+        contract.extend_client_echo_token_id_whitelist(vec!["token1.near".to_string()]);
+        contract.extend_client_echo_sender_id_whitelist(vec!["*farm.near".to_string(), "x.burrow.near".to_string()]);
+        contract.extend_secure_sender_whitelist(vec!["*.burrow.near".to_string()]);
+
+        internal_migrate_client_echo_whitelists();
+
+        // covered by a secure pattern, so any token as before
+        assert!(contract.get_client_echo_policy("x.burrow.near".to_string()).unwrap().allowed_tokens.is_none());
+        // can't be indexed, stays in the legacy whitelists with its tokens
+        assert_eq!(contract.get_client_echo_sender_id_whitelist(), vec!["*farm.near".to_string()]);
+        assert_eq!(contract.get_client_echo_token_id_whitelist(), vec!["token1.near".to_string()]);
+        assert_eq!(contract.get_secure_sender_whitelist().len(), 0);
+
+        let sender: AccountId = "boostfarm.near".to_string();
+        let token: AccountId = "token1.near".to_string();
+        assert_client_echo_valid(&token, &sender, "\"Free\"", 15);
+        let sender: AccountId = "x.burrow.near".to_string();
+        let token: AccountId = "usdt.near".to_string();
+        assert_client_echo_valid(&token, &sender, "\"Free\"", 15);
+    }
+}
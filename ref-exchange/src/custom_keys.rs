@@ -1,5 +1,6 @@
 // Key for rated token info
 pub const RATE_STORAGE_KEY: &str = "custom_rate_key";
+pub const RATE_GUARD_STORAGE_KEY: &str = "custom_rate_guard_key";
 
 // Key for degen token info
 pub const DEGEN_STORAGE_KEY: &str = "custom_degen_key";
@@ -12,6 +13,28 @@ pub const POOL_LIMIT: &str = "pl";
 pub const CLIENT_ECHO_TOKEN_ID_WHITELIST: &str = "ce_tw";
 pub const CLIENT_ECHO_SENDER_ID_WHITELIST: &str = "ce_sw";
 pub const SECURE_SENDER_WHITELIST: &str = "ce_ssw";
+pub const CLIENT_ECHO_SENDER_POLICY: &str = "ce_sp";
 
 // Key for swap volume
-pub const SWAP_VOLUME_KEY: &str = "sv";
\ No newline at end of file
+pub const SWAP_VOLUME_KEY: &str = "sv";
+// Key for stable pool imbalance penalty
+pub const IMBALANCE_PENALTY: &str = "ip";
+
+// Key for stable pool virtual price records
+pub const VIRTUAL_PRICE_KEY: &str = "vp";
+
+// Key for deprecated tokens in stable-like pools
+pub const DEPRECATED_POOL_TOKENS: &str = "dpt";
+
+// Key for index of accounts holding lostfound tokens
+pub const LOSTFOUND_ACCOUNT_INDEX: &str = "lfi";
+
+// Key for max wNEAR unwrapped in a storage top-up
+pub const STORAGE_TOPUP_CAP: &str = "stc";
+
+// Key for storage sponsors and the accounts they registered
+pub const STORAGE_SPONSORS: &str = "ss";
+pub const SPONSORED_ACCOUNTS: &str = "ssa";
+
+// Key for counters of the contract storage report
+pub const STORAGE_REPORT_COUNTERS: &str = "src";
@@ -14,6 +14,7 @@ use crate::pyth_oracle::PriceIdentifier;
 use crate::utils::{GAS_FOR_BASIC_OP, NO_DEPOSIT};
 use crate::{ext_self, DEGEN_STORAGE_KEY};
 use crate::DEGEN_ORACLE_CONFIG_STORAGE_KEY;
+use crate::update_storage_bytes;
 
 pub static DEGENS: Lazy<Mutex<HashMap<AccountId, Degen>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 pub static DEGEN_ORACLE_CONFIGS: Lazy<Mutex<HashMap<String, DegenOracleConfig>>> = Lazy::new(|| Mutex::new(HashMap::new()));
@@ -159,10 +160,12 @@ pub fn read_degens_from_storage() -> HashMap<String, Degen> {
 }
 
 pub fn write_degens_to_storage(degens: HashMap<String, Degen>) {
+    let prev_storage = env::storage_usage();
     env::storage_write(
         DEGEN_STORAGE_KEY.as_bytes(), 
         &degens.try_to_vec().unwrap(),
     );
+    update_storage_bytes(prev_storage, |storage_counters| &mut storage_counters.degen_registry_bytes);
 }
 
 pub fn read_degen_oracle_configs_from_storage() -> HashMap<String, DegenOracleConfig> {
@@ -281,10 +284,12 @@ pub fn global_get_degen_pyth_oracle_config() -> PythOracleConfig {
 pub fn global_set_degen(token_id: &AccountId, degen: &Degen) {
     init_degens_cache();
     DEGENS.lock().unwrap().insert(token_id.clone(), degen.clone());
+    let prev_storage = env::storage_usage();
     env::storage_write(
         DEGEN_STORAGE_KEY.as_bytes(), 
         &DEGENS.lock().unwrap().try_to_vec().unwrap(),
     );
+    update_storage_bytes(prev_storage, |storage_counters| &mut storage_counters.degen_registry_bytes);
 }
 
 pub fn is_global_degen_price_valid(token_id: &AccountId) -> bool {
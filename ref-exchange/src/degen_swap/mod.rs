@@ -5,6 +5,7 @@ use near_sdk::{env, AccountId, Balance, Timestamp};
 
 use crate::admin_fee::AdminFees;
 use crate::errors::*;
+use crate::pool_token_manager::StableLikePool;
 use crate::degen_swap::math::{
     Fees, DegenSwap, SwapResult, MAX_AMP, MAX_AMP_CHANGE, MIN_AMP, MIN_RAMP_DURATION,
 };
@@ -802,6 +803,44 @@ impl DegenSwapPool {
     }
 }
 
+impl StableLikePool for DegenSwapPool {
+    const MIN_DECIMAL: u8 = MIN_DECIMAL;
+    const MAX_DECIMAL: u8 = MAX_DECIMAL;
+    const MIN_RESERVE: Balance = MIN_RESERVE;
+
+    fn token_vectors_mut(&mut self) -> (&mut Vec<AccountId>, &mut Vec<u8>, &mut Vec<Balance>, &mut Vec<SwapVolume>) {
+        (&mut self.token_account_ids, &mut self.token_decimals, &mut self.c_amounts, &mut self.volumes)
+    }
+
+    fn get_shares_total_supply(&self) -> Balance {
+        self.shares_total_supply
+    }
+
+    fn get_total_fee(&self) -> u32 {
+        self.total_fee
+    }
+
+    fn to_c_amount(&self, amount: Balance, index: usize) -> Balance {
+        self.amount_to_c_amount(amount, index)
+    }
+
+    fn from_c_amount(&self, c_amount: Balance, index: usize) -> Balance {
+        self.c_amount_to_amount(c_amount, index)
+    }
+
+    fn compute_current_d(&self) -> Balance {
+        self.assert_degens_valid();
+        self.get_invariant_with_degens(&self.get_degens())
+            .compute_d_with_degens(&self.c_amounts)
+            .expect(ERR66_INVARIANT_CALC_ERR)
+            .as_u128()
+    }
+
+    fn mint_shares_to(&mut self, account_id: &AccountId, shares: Balance) {
+        self.mint_shares(account_id, shares, false);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use near_sdk::test_utils::{accounts, VMContextBuilder};
@@ -839,17 +878,7 @@ mod tests {
         )
     }
 
-    #[test]
-    fn test_mix() {
-        let mut context = VMContextBuilder::new();
-        testing_env!(context.block_timestamp(25).predecessor_account_id(accounts(0)).build());
-        let fees = AdminFees::zero();
-        let mut pool = new_degen_pool(TARGET_DECIMAL, 1000, 0);
-        assert_eq!(
-            pool.tokens(),
-            vec![accounts(1).to_string(), accounts(2).to_string()]
-        );
-
+    fn register_test_degens() {
         let cross_call_result1 = near_sdk::serde_json::to_vec(&price_oracle::PriceData{
             timestamp: 20,
             recency_duration_sec: 90,
@@ -888,6 +917,20 @@ mod tests {
         degen2.set_price(&cross_call_result2);
         println!("degen2: {:?}", degen2.get_price_info().stored_degen);
         global_set_degen(accounts(2).as_ref(), &degen2);
+    }
+
+    #[test]
+    fn test_mix() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_timestamp(25).predecessor_account_id(accounts(0)).build());
+        let fees = AdminFees::zero();
+        let mut pool = new_degen_pool(TARGET_DECIMAL, 1000, 0);
+        assert_eq!(
+            pool.tokens(),
+            vec![accounts(1).to_string(), accounts(2).to_string()]
+        );
+
+        register_test_degens();
 
         println!("degens: {:?}", pool.get_degens());
 
@@ -899,4 +942,107 @@ mod tests {
         assert_eq!(pool.c_amounts, vec![100001 * PRECISION, 199998_000000009995002449799089]);
     }
 
+    #[test]
+    fn test_degen_ramp_amp() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_timestamp(25).predecessor_account_id(accounts(0)).build());
+        let mut pool = new_degen_pool(TARGET_DECIMAL, 10000, 0);
+        register_test_degens();
+
+        let start_ts = MIN_RAMP_DURATION + 1_000_000_000;
+        testing_env!(context.block_timestamp(start_ts).build());
+        pool.ramp_amplification(50000, start_ts + MIN_RAMP_DURATION * 10);
+        assert_eq!(pool.get_amp(), 10000);
+        assert_eq!(pool.target_amp_factor, 50000);
+        testing_env!(context
+            .block_timestamp(start_ts + MIN_RAMP_DURATION * 5)
+            .build());
+        assert_eq!(pool.get_amp(), 30000);
+        pool.ramp_amplification(150000, start_ts + MIN_RAMP_DURATION * 20);
+        assert_eq!(pool.init_amp_factor, 30000);
+        testing_env!(context
+            .block_timestamp(start_ts + MIN_RAMP_DURATION * 7)
+            .build());
+        pool.stop_ramp_amplification();
+        let amp = pool.get_amp();
+        assert_eq!(pool.target_amp_factor, amp as u128);
+        testing_env!(context
+            .block_timestamp(start_ts + MIN_RAMP_DURATION * 30)
+            .build());
+        assert_eq!(pool.get_amp(), amp);
+    }
+
+    #[test]
+    #[should_panic(expected = "E84: amp factor change is too large")]
+    fn test_degen_ramp_amp_large_change() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_timestamp(25).predecessor_account_id(accounts(0)).build());
+        let mut pool = new_degen_pool(TARGET_DECIMAL, 10000, 0);
+        register_test_degens();
+
+        let start_ts = MIN_RAMP_DURATION + 1_000_000_000;
+        testing_env!(context.block_timestamp(start_ts).build());
+        pool.ramp_amplification(10000 * MAX_AMP_CHANGE + 1, start_ts + MIN_RAMP_DURATION * 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "E82: insufficient ramp time")]
+    fn test_degen_ramp_amp_insufficient_time() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_timestamp(25).predecessor_account_id(accounts(0)).build());
+        let mut pool = new_degen_pool(TARGET_DECIMAL, 10000, 0);
+        register_test_degens();
+
+        let start_ts = MIN_RAMP_DURATION + 1_000_000_000;
+        testing_env!(context.block_timestamp(start_ts).build());
+        pool.ramp_amplification(20000, start_ts + MIN_RAMP_DURATION - 1);
+    }
+
+    #[test]
+    fn test_degen_append_and_remove_token() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.block_timestamp(25).predecessor_account_id(accounts(0)).build());
+        let mut pool = new_degen_pool(TARGET_DECIMAL, 1000, 25);
+        register_test_degens();
+        for token_id in vec![accounts(3), accounts(4)] {
+            global_register_degen(token_id.as_ref(), DegenType::PriceOracle { decimals: TARGET_DECIMAL });
+            let cross_call_result = near_sdk::serde_json::to_vec(&price_oracle::PriceData{
+                timestamp: 20,
+                recency_duration_sec: 90,
+                prices: vec![price_oracle::AssetOptionalPrice{
+                    asset_id: token_id.to_string(),
+                    price: Some(price_oracle::Price {
+                        multiplier: 10000,
+                        decimals: TARGET_DECIMAL + 4,
+                    }),
+                }],
+            }).unwrap();
+            let mut degen = global_get_degen(token_id.as_ref());
+            degen.set_price(&cross_call_result);
+            global_set_degen(token_id.as_ref(), &degen);
+        }
+        let mut amounts = vec![100000 * PRECISION, 200000 * PRECISION];
+        let shares = pool.add_liquidity(accounts(0).as_ref(), &mut amounts, 1, &AdminFees::zero(), false);
+
+        // seeding shares are charged with the pool fee
+        let new_shares = pool.append_token(accounts(0).as_ref(), accounts(3).as_ref(), TARGET_DECIMAL, 200000 * PRECISION);
+        assert!(new_shares < shares / 2);
+        assert!(new_shares > shares / 2 * 9970 / 10000);
+        assert_eq!(pool.share_total_balance(), shares + new_shares);
+        assert_eq!(pool.get_degens().len(), 3);
+        assert_eq!(pool.c_amounts.len(), 3);
+        assert_eq!(pool.volumes.len(), 3);
+
+        // dust token can be removed
+        pool.append_token(accounts(0).as_ref(), accounts(4).as_ref(), TARGET_DECIMAL, MIN_RESERVE);
+        assert_eq!(pool.remove_token(accounts(4).as_ref()), MIN_RESERVE);
+        assert_eq!(
+            pool.tokens(),
+            vec![accounts(1).to_string(), accounts(2).to_string(), accounts(3).to_string()]
+        );
+        assert_eq!(pool.token_decimals.len(), 3);
+        assert_eq!(pool.c_amounts.len(), 3);
+        assert_eq!(pool.volumes.len(), 3);
+    }
+
 }
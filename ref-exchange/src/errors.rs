@@ -25,6 +25,7 @@ pub const ERR27_DEPOSIT_NEEDED: &str =
     "E27: attach 1yN to swap tokens not in whitelist";
 pub const ERR28_WRONG_MSG_FORMAT: &str = "E28: Illegal msg in ft_transfer_call";
 pub const ERR29_ILLEGAL_WITHDRAW_AMOUNT: &str = "E29: Illegal withdraw amount";
+pub const ERR30_WNEAR_NOT_SET: &str = "E30: wnear id not set";
 
 // Liquidity operations.
 
@@ -35,6 +36,7 @@ pub const ERR33_TRANSFER_TO_SELF: &str = "E33: transfer to self";
 pub const ERR34_INSUFFICIENT_LP_SHARES: &str = "E34: insufficient lp shares";
 pub const ERR35_AT_LEAST_ONE_YOCTO: &str = "E35: requires attached deposit of at least 1 yoctoNEAR";
 pub const ERR36_SHARES_TOTAL_SUPPLY_OVERFLOW: &str = "E36: shares_total_supply overflow";
+pub const ERR37_NOT_ENOUGH_GAS: &str = "E37: not enough prepaid gas";
 
 // Action result.
 
@@ -63,6 +65,8 @@ pub const ERR73_SAME_TOKEN: &str = "E73: same token swap";
 pub const ERR75_INVARIANT_REDUCE: &str = "E75: invariant can not reduce ";
 pub const ERR76_INVALID_PARAMS: &str = "E76: invalid params";
 pub const ERR77_INVALID_ACTION_TYPE: &str = "E77: all action types must be the same";
+pub const ERR78_PRICE_IMPACT_TOO_HIGH: &str = "E78: price impact exceeds limit";
+pub const ERR79_DEADLINE_EXCEEDED: &str = "E79: deadline exceeded";
 
 // pool manage
 pub const ERR81_AMP_IN_LOCK: &str = "E81: amp is currently in lock";
@@ -78,6 +82,11 @@ pub const ERR90_FEE_TOO_LARGE: &str = "E90: fee too large";
 pub const ERR91_NOT_ENOUGH_SHARES: &str = "E91: not enough shares";
 pub const ERR92_TOKEN_DUPLICATES: &str = "E92: token duplicated";
 pub const ERR89_WRONG_AMOUNT_COUNT: &str = "E89: wrong amount count";
+pub const ERR93_TOKEN_DEPRECATED: &str = "E93: token deprecated in pool";
+pub const ERR94_POOL_NO_LIQUIDITY: &str = "E94: pool has no liquidity";
+pub const ERR95_MIGRATION_TOKEN_MISMATCH: &str = "E95: target pool token not in source pool";
+pub const ERR96_TOO_MANY_POOL_TOKENS: &str = "E96: too many tokens in pool";
+pub const ERR97_TOKEN_NOT_DRAINED: &str = "E97: token not drained from pool";
 
 
 // owner
@@ -87,6 +96,7 @@ pub const ERR102_INVALID_TOKEN_ID: &str = "E102: invalid token id";
 pub const ERR103_NOT_INITIALIZED: &str = "E103: contract is not initialized";
 pub const ERR104_GUARDIAN_NOT_IN_LIST: &str = "E104: guardian not in list";
 pub const ERR105_WHITELISTED_POSTFIX_NOT_IN_LIST: &str = "E105: whitelisted postfix not in list";
+pub const ERR106_STORAGE_REPORT_SEEDED: &str = "E106: storage report already seeded";
 
 
 //mft
@@ -95,6 +105,7 @@ pub const ERR111_INVALID_UNREGISTER: &str = "E111: Invalid unregister";
 
 // rated pool
 pub const ERR120_RATES_EXPIRED: &str = "E120: Rates expired";
+pub const ERR121_RATE_PAUSED: &str = "E121: Rate paused by rate guard";
 // pub const ERR122_FAILED_TO_UPDATE_RATES: &str = "E122: Failed to update rates";
 pub const ERR123_TWO_PROMISE_RESULT: &str = "E123: Cross-contract call should have exactly two promise result";
 pub const ERR124_CROSS_CALL_FAILED: &str = "E124: Cross-contract call failed";
@@ -107,4 +118,23 @@ pub const ERR129_DEGENS_EXPIRED: &str = "E129: Degens expired";
 // referrals
 pub const ERR130_REFERRAL_EXIST: &str = "E130: Referral already exist";
 pub const ERR131_REFERRAL_NOT_EXIST: &str = "E131: Referral not exist";
-pub const ERR132_ILLEGAL_REFERRAL_FEE: &str = "E132: Illegal referral fee";
\ No newline at end of file
+pub const ERR132_ILLEGAL_REFERRAL_FEE: &str = "E132: Illegal referral fee";
+// signed intents
+pub const ERR140_INTENT_KEY_NOT_REGISTERED: &str = "E140: intent key not registered";
+pub const ERR141_INVALID_PUBLIC_KEY: &str = "E141: invalid ed25519 public key";
+pub const ERR142_INVALID_SIGNATURE: &str = "E142: invalid intent signature";
+pub const ERR143_INTENT_EXPIRED: &str = "E143: intent expired";
+pub const ERR144_INTENT_NONCE_USED: &str = "E144: intent nonce already used";
+pub const ERR145_WRONG_INTENT_CONTRACT: &str = "E145: intent not for this contract";
+pub const ERR146_ILLEGAL_INTENT_MSG: &str = "E146: illegal intent message";
+
+// storage sponsor
+pub const ERR150_SPONSOR_NOT_FOUND: &str = "E150: storage sponsor not found";
+pub const ERR151_SPONSOR_BALANCE_NOT_ENOUGH: &str = "E151: storage sponsor balance not enough";
+pub const ERR152_ACC_ALREADY_REGISTERED: &str = "E152: account already registered";
+
+// deposit lock
+pub const ERR160_LOCKER_NOT_AUTHORIZED: &str = "E160: locker not authorized";
+pub const ERR161_DEPOSIT_LOCKED: &str = "E161: not enough unlocked deposit";
+pub const ERR162_NOT_ENOUGH_LOCKED: &str = "E162: not enough locked deposit";
+pub const ERR163_INVALID_LOCK_DURATION: &str = "E163: invalid lock duration";
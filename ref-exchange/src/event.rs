@@ -1,6 +1,7 @@
 
 use crate::*;
 use near_sdk::serde_json::json;
+use near_sdk::json_types::U64;
 
 const EVENT_STANDARD: &str = "exchange.ref";
 const EVENT_STANDARD_VERSION: &str = "1.0.0";
@@ -20,6 +21,16 @@ pub enum Event<'a> {
         account_id: &'a AccountId,
         token_id: &'a AccountId,
         amount: U128,
+    },
+    RateGuardTriggered {
+        token_id: &'a AccountId,
+        prev_rate: U128,
+        reported_rate: U128,
+    },
+    SignedIntentExecuted {
+        signer_id: &'a AccountId,
+        relayer_id: &'a AccountId,
+        nonce: U64,
     }
 }
 
@@ -0,0 +1,96 @@
+use crate::*;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use crate::utils::{u128_ratio, FEE_DIVISOR};
+
+/// Scales up the fee of a stable pool liquidity op,
+/// if the op pushes any token's portion out of the band around its ideal portion.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct ImbalancePenalty {
+    /// Max allowed deviation in bps of any token's portion from 1/n.
+    pub band_bps: u32,
+    /// Fee multiplier in bps applied when out of band, should be no less than FEE_DIVISOR.
+    pub multiplier_bps: u32,
+}
+
+impl ImbalancePenalty {
+    pub fn assert_valid(&self) {
+        assert!(self.band_bps < FEE_DIVISOR, "{}", ERR76_INVALID_PARAMS);
+        assert!(self.multiplier_bps >= FEE_DIVISOR, "{}", ERR76_INVALID_PARAMS);
+    }
+
+    /// The max deviation in bps of token portions from the ideal one.
+    pub fn max_deviation_bps(c_amounts: &Vec<Balance>) -> u32 {
+        let sum: u128 = c_amounts.iter().sum();
+        if sum == 0 {
+            return 0;
+        }
+        let ideal_bps = FEE_DIVISOR as u128 / c_amounts.len() as u128;
+        c_amounts
+            .iter()
+            .map(|c_amount| {
+                let portion_bps = u128_ratio(*c_amount, FEE_DIVISOR as u128, sum);
+                if portion_bps > ideal_bps { portion_bps - ideal_bps } else { ideal_bps - portion_bps }
+            })
+            .max()
+            .unwrap_or(0) as u32
+    }
+
+    /// Returns the fee to charge for a liquidity op moving pool balances from prev_c_amounts to new_c_amounts.
+    /// Only an op ending out of band while making the imbalance worse gets penalized.
+    pub fn apply(&self, total_fee: u32, prev_c_amounts: &Vec<Balance>, new_c_amounts: &Vec<Balance>) -> u32 {
+        let new_deviation = Self::max_deviation_bps(new_c_amounts);
+        if new_deviation > self.band_bps && new_deviation > Self::max_deviation_bps(prev_c_amounts) {
+            std::cmp::min(
+                u128_ratio(total_fee as u128, self.multiplier_bps as u128, FEE_DIVISOR as u128),
+                (FEE_DIVISOR - 1) as u128,
+            ) as u32
+        } else {
+            total_fee
+        }
+    }
+}
+
+pub fn read_imbalance_penalty_from_storage() -> UnorderedMap<u64, ImbalancePenalty> {
+    if let Some(content) = env::storage_read(IMBALANCE_PENALTY.as_bytes()) {
+        UnorderedMap::try_from_slice(&content).expect("deserialize imbalance penalty failed.")
+    } else {
+        UnorderedMap::new(StorageKey::ImbalancePenalty)
+    }
+}
+
+pub fn write_imbalance_penalty_to_storage(imbalance_penalty: UnorderedMap<u64, ImbalancePenalty>) {
+    env::storage_write(
+        IMBALANCE_PENALTY.as_bytes(),
+        &imbalance_penalty.try_to_vec().unwrap(),
+    );
+}
+
+impl Contract {
+    /// Imbalance penalty of the pool if any, only applied by liquidity ops of stable swap pool.
+    pub fn internal_get_imbalance_penalty(&self, pool_id: u64) -> Option<ImbalancePenalty> {
+        read_imbalance_penalty_from_storage().get(&pool_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_imbalance_penalty() {
+        let penalty = ImbalancePenalty { band_bps: 1000, multiplier_bps: 30000 };
+        assert_eq!(ImbalancePenalty::max_deviation_bps(&vec![0, 0]), 0);
+        assert_eq!(ImbalancePenalty::max_deviation_bps(&vec![100, 100, 100]), 0);
+        assert_eq!(ImbalancePenalty::max_deviation_bps(&vec![70, 30]), 2000);
+        // inside the band
+        assert_eq!(penalty.apply(5, &vec![100, 100], &vec![110, 100]), 5);
+        // pushed out of the band
+        assert_eq!(penalty.apply(5, &vec![100, 100], &vec![200, 100]), 15);
+        // out of the band, but getting better
+        assert_eq!(penalty.apply(5, &vec![300, 100], &vec![300, 150]), 5);
+        // capped below FEE_DIVISOR
+        assert_eq!(penalty.apply(9000, &vec![100, 100], &vec![200, 100]), FEE_DIVISOR - 1);
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use near_sdk::collections::{UnorderedMap, Vector, LookupMap, UnorderedSet};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{AccountId, Balance, StorageUsage};
-use crate::account_deposit::{Account, VAccount};
+use crate::account_deposit::{Account, VAccount, VShadowRecord};
 use crate::{StorageKey, VUnitShareCumulativeInfo};
 use crate::pool::Pool;
 use crate::RunningState;
@@ -30,7 +30,8 @@ impl AccountV1 {
             storage_used: self.storage_used,
             shadow_records: UnorderedMap::new(StorageKey::ShadowRecord {
                 account_id: account_id.clone(),
-            })
+            }),
+            auto_storage_topup: false,
         }
     }
 }
@@ -61,7 +62,41 @@ impl AccountV2 {
             storage_used,
             shadow_records: UnorderedMap::new(StorageKey::ShadowRecord {
                 account_id: account_id.clone(),
-            })
+            }),
+            auto_storage_topup: false,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct AccountV3 {
+    /// Native NEAR amount sent to the exchange.
+    /// Used for storage right now, but in future can be used for trading as well.
+    pub near_amount: Balance,
+    /// Amounts of various tokens deposited to this account.
+    pub legacy_tokens: HashMap<AccountId, Balance>,
+    pub tokens: UnorderedMap<AccountId, Balance>,
+    pub storage_used: StorageUsage,
+    // pool_id -> ShadowRecord
+    pub shadow_records: UnorderedMap<u64, VShadowRecord>
+}
+
+impl AccountV3 {
+    pub fn into_current(self) -> Account {
+        let AccountV3 {
+            near_amount,
+            legacy_tokens,
+            tokens,
+            storage_used,
+            shadow_records,
+        } = self;
+        Account {
+            near_amount,
+            legacy_tokens,
+            tokens,
+            storage_used,
+            shadow_records,
+            auto_storage_topup: false,
         }
     }
 }
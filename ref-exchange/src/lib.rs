@@ -10,12 +10,12 @@ use near_contract_standards::storage_management::{
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, UnorderedSet, Vector, UnorderedMap};
-use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::json_types::{ValidAccountId, U128, U64};
 use near_sdk::{
     assert_one_yocto, env, log, near_bindgen, AccountId, Balance, PanicOnDefault, Promise,
     PromiseResult, StorageUsage, BorshStorageKey, PromiseOrValue, ext_contract, Gas
 };
-use utils::{NO_DEPOSIT, GAS_FOR_BASIC_OP};
+use utils::{NO_DEPOSIT, GAS_FOR_BASIC_OP, PRICE_IMPACT_PROBE_DIVISOR, FEE_DIVISOR, u128_ratio, assert_deadline};
 
 use crate::account_deposit::*;
 pub use crate::action::{SwapAction, SwapByOutputAction, Action, ActionResult, get_tokens_in_actions, assert_all_same_action_type};
@@ -24,10 +24,11 @@ use crate::admin_fee::AdminFees;
 use crate::pool::Pool;
 use crate::simple_pool::SimplePool;
 use crate::stable_swap::StableSwapPool;
-use crate::rated_swap::{RatedSwapPool, rate::{RateTrait, global_get_rate, global_set_rate}};
+use crate::rated_swap::{RatedSwapPool, rate::{RateTrait, global_get_rate, global_set_rate}, rate_guard::{global_get_rate_guard, global_set_rate_guard}};
 pub use crate::utils::{check_token_duplicates, pair_rated_price_to_vec_u8, TokenCache, SwapVolume};
 pub use crate::custom_keys::*;
-pub use crate::views::{PoolInfo, ShadowRecordInfo, RatedPoolInfo, StablePoolInfo, ContractMetadata, RatedTokenInfo, DegenTokenInfo, AddLiquidityPrediction, RefStorageState};
+pub use crate::rated_swap::rate_guard::RateGuardInfo;
+pub use crate::views::{PoolInfo, ShadowRecordInfo, RatedPoolInfo, StablePoolInfo, DegenPoolInfo, ContractMetadata, RatedTokenInfo, DegenTokenInfo, AddLiquidityPrediction, MigrateLiquidityPrediction, RefStorageState};
 pub use crate::token_receiver::{AddLiquidityInfo, VIRTUAL_ACC};
 pub use crate::shadow_actions::*;
 pub use crate::unit_lpt_cumulative_infos::*;
@@ -36,6 +37,13 @@ pub use crate::degen_swap::*;
 pub use crate::pool_limit_info::*;
 pub use crate::client_echo_limit::*;
 pub use crate::swap_volume::*;
+pub use crate::imbalance_penalty::*;
+pub use crate::virtual_price::*;
+pub use crate::pool_token_manager::*;
+pub use crate::signed_intent::*;
+pub use crate::storage_sponsor::*;
+pub use crate::account_lock::*;
+pub use crate::storage_report::*;
 
 mod account_deposit;
 mod account_lostfound;
@@ -63,6 +71,14 @@ mod client_echo_limit;
 mod donation;
 mod event;
 mod swap_volume;
+mod imbalance_penalty;
+mod virtual_price;
+mod pool_token_manager;
+mod signed_intent;
+mod storage_sponsor;
+mod native_near;
+mod account_lock;
+mod storage_report;
 
 near_sdk::setup_alloc!();
 
@@ -84,6 +100,15 @@ pub(crate) enum StorageKey {
     SecureSenderWhitelistItem,
     LostfoundAccounts,
     LostfoundAccountTokens {account_id: AccountId},
+    ImbalancePenalty,
+    VirtualPriceInfo,
+    DeprecatedPoolTokens,
+    LostfoundAccountIndex,
+    IntentSigners,
+    StorageSponsors,
+    SponsoredAccounts,
+    ClientEchoSenderPolicy,
+    AccountLocks,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -312,8 +337,10 @@ impl Contract {
         actions: Vec<Action>,
         referral_id: Option<ValidAccountId>,
         skip_degen_price_sync: Option<bool>,
+        deadline_ns: Option<U64>,
     ) -> ActionResult {
         self.assert_contract_running();
+        assert_deadline(deadline_ns);
         assert_ne!(actions.len(), 0, "{}", ERR72_AT_LEAST_ONE_SWAP);
         let sender_id = env::predecessor_account_id();
         let mut account = self.internal_unwrap_account(&sender_id);
@@ -346,7 +373,7 @@ impl Contract {
     /// If referrer provided, pays referral_fee to it.
     /// If no attached deposit, outgoing tokens used in swaps must be whitelisted.
     #[payable]
-    pub fn swap(&mut self, actions: Vec<SwapAction>, referral_id: Option<ValidAccountId>, skip_degen_price_sync: Option<bool>) -> U128 {
+    pub fn swap(&mut self, actions: Vec<SwapAction>, referral_id: Option<ValidAccountId>, skip_degen_price_sync: Option<bool>, deadline_ns: Option<U64>) -> U128 {
         U128(
             self.execute_actions(
                 actions
@@ -354,7 +381,8 @@ impl Contract {
                     .map(|swap_action| Action::Swap(swap_action))
                     .collect(),
                 referral_id,
-                skip_degen_price_sync
+                skip_degen_price_sync,
+                deadline_ns
             )
             .to_amount(),
         )
@@ -364,7 +392,7 @@ impl Contract {
     /// If referrer provided, pays referral_fee to it.
     /// If no attached deposit, outgoing tokens used in swaps must be whitelisted.
     #[payable]
-    pub fn swap_by_output(&mut self, actions: Vec<SwapByOutputAction>, referral_id: Option<ValidAccountId>, skip_degen_price_sync: Option<bool>) -> U128 {
+    pub fn swap_by_output(&mut self, actions: Vec<SwapByOutputAction>, referral_id: Option<ValidAccountId>, skip_degen_price_sync: Option<bool>, deadline_ns: Option<U64>) -> U128 {
         U128(
             self.execute_actions(
                 actions
@@ -373,6 +401,7 @@ impl Contract {
                     .collect(),
                 referral_id,
                 skip_degen_price_sync,
+                deadline_ns,
             )
             .to_amount(),
         )
@@ -447,12 +476,14 @@ impl Contract {
         let mut pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
         // feature frozenlist
         self.assert_no_frozen_tokens(pool.tokens());
+        self.assert_no_deprecated_token_deposit(pool_id, pool.tokens(), &amounts);
         // Add amounts given to liquidity first. It will return the balanced amounts.
         let mint_shares = pool.add_stable_liquidity(
             &sender_id,
             &amounts,
             min_shares.into(),
             AdminFees::new(self.admin_fee_bps),
+            self.internal_get_imbalance_penalty(pool_id),
             false
         );
         pool.assert_tvl_not_exceed_limit(pool_id);
@@ -465,6 +496,7 @@ impl Contract {
         }
         self.internal_save_account(&sender_id, deposits);
         self.pools.replace(pool_id, &pool);
+        self.internal_update_virtual_price_record(pool_id, &pool);
         self.internal_check_storage(prev_storage);
         mint_shares.into()
     }
@@ -503,6 +535,7 @@ impl Contract {
             false
         );
         self.pools.replace(pool_id, &pool);
+        self.internal_update_virtual_price_record(pool_id, &pool);
         let tokens = pool.tokens();
         for i in 0..tokens.len() {
             deposits.deposit(&tokens[i], amounts[i]);
@@ -547,10 +580,12 @@ impl Contract {
                 .collect(),
             max_burn_shares.into(),
             AdminFees::new(self.admin_fee_bps),
+            self.internal_get_imbalance_penalty(pool_id),
             false
         );
         assert!(burn_shares <= free_shares, "Not enough free shares");
         self.pools.replace(pool_id, &pool);
+        self.internal_update_virtual_price_record(pool_id, &pool);
         let tokens = pool.tokens();
         for i in 0..tokens.len() {
             deposits.deposit(&tokens[i], amounts[i].into());
@@ -559,6 +594,65 @@ impl Contract {
         burn_shares.into()
     }
 
+    /// Move liquidity of sender from one pool to another pool of the same assets in one call.
+    /// shares: LP shares in from_pool to be migrated, must be free from shadow records.
+    /// min_shares: Slippage, if shares minted in to_pool is less than it, panic with ERR68_SLIPPAGE.
+    /// Tokens removed but not used by to_pool stay in sender's deposits.
+    /// No swap is made, every token of to_pool must be in from_pool, otherwise panic with ERR95_MIGRATION_TOKEN_MISMATCH.
+    #[payable]
+    pub fn migrate_liquidity(
+        &mut self,
+        from_pool_id: u64,
+        shares: U128,
+        to_pool_id: u64,
+        min_shares: U128,
+    ) -> U128 {
+        self.assert_contract_running();
+        assert!(
+            env::attached_deposit() > 0,
+            "{}", ERR35_AT_LEAST_ONE_YOCTO
+        );
+        assert_ne!(from_pool_id, to_pool_id, "{}", ERR76_INVALID_PARAMS);
+        self.internal_update_unit_share_cumulative_info(from_pool_id);
+        self.internal_update_unit_share_cumulative_info(to_pool_id);
+        let prev_storage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let mut from_pool = self.pools.get(from_pool_id).expect(ERR85_NO_POOL);
+        let mut to_pool = self.pools.get(to_pool_id).expect(ERR85_NO_POOL);
+        let mut deposits = self.internal_unwrap_account(&sender_id);
+        if let Some(record) = deposits.get_shadow_record(from_pool_id) {
+            assert!(shares.0 <= record.free_shares(from_pool.share_balances(&sender_id)), "Not enough free shares");
+        }
+        // feature frozenlist
+        self.assert_no_frozen_tokens(from_pool.tokens());
+        self.assert_no_frozen_tokens(to_pool.tokens());
+        let (remove_amounts, add_amounts, mint_shares) = self.internal_migrate_liquidity(
+            &sender_id,
+            &mut from_pool,
+            shares.into(),
+            to_pool_id,
+            &mut to_pool,
+            min_shares.into(),
+            false,
+        );
+        to_pool.assert_tvl_not_exceed_limit(to_pool_id);
+        let from_tokens = from_pool.tokens();
+        for i in 0..from_tokens.len() {
+            deposits.deposit(&from_tokens[i], remove_amounts[i]);
+        }
+        let to_tokens = to_pool.tokens();
+        for i in 0..to_tokens.len() {
+            deposits.withdraw(&to_tokens[i], add_amounts[i]);
+        }
+        self.internal_save_account(&sender_id, deposits);
+        self.pools.replace(from_pool_id, &from_pool);
+        self.pools.replace(to_pool_id, &to_pool);
+        self.internal_update_virtual_price_record(from_pool_id, &from_pool);
+        self.internal_update_virtual_price_record(to_pool_id, &to_pool);
+        self.internal_check_storage(prev_storage);
+        mint_shares.into()
+    }
+
     /// anyone can trigger an update for some rated token
     pub fn update_token_rate(& self, token_id: ValidAccountId) -> PromiseOrValue<bool> {
         let caller = env::predecessor_account_id();
@@ -600,7 +694,30 @@ impl Contract {
             pair_rated_price_to_vec_u8(cross_call_result1, cross_call_result2)
         };
         if let Some(mut rate) = global_get_rate(&token_id) {
+            let prev_rate = rate.get();
+            let prev_ts = rate.last_update_ts();
             let new_rate = rate.set(&cross_call_result);
+            if let Some(mut rate_guard) = global_get_rate_guard(&token_id) {
+                rate_guard.last_reported_rate = new_rate;
+                rate_guard.last_reported_at = env::block_timestamp();
+                if rate_guard.is_paused {
+                    global_set_rate_guard(&token_id, rate_guard);
+                    log!("Token {} rate is paused, reported rate {} ignored.", token_id, new_rate);
+                    return;
+                }
+                if !rate_guard.is_within_bounds(prev_rate, prev_ts, new_rate, env::block_timestamp()) {
+                    rate_guard.is_paused = true;
+                    global_set_rate_guard(&token_id, rate_guard);
+                    event::Event::RateGuardTriggered {
+                        token_id: &token_id,
+                        prev_rate: U128(prev_rate),
+                        reported_rate: U128(new_rate),
+                    }
+                    .emit();
+                    return;
+                }
+                global_set_rate_guard(&token_id, rate_guard);
+            }
             global_set_rate(&token_id, &rate);
             log!(
                 "Token {} got new rate {} from cross-contract call.",
@@ -665,20 +782,81 @@ impl Contract {
         self.whitelisted_tokens.contains(token_id) || self.auto_whitelisted_postfix.iter().any(|postfix| token_id.ends_with(postfix))
     }
 
+    /// Remove shares from from_pool and add the removed tokens into to_pool.
+    /// Returns amounts removed (in from_pool tokens sequence), amounts used (in to_pool tokens sequence) and shares minted.
+    /// Every token of to_pool must be in from_pool, checked before any liquidity is removed.
+    fn internal_migrate_liquidity(
+        &self,
+        sender_id: &AccountId,
+        from_pool: &mut Pool,
+        shares: Balance,
+        to_pool_id: u64,
+        to_pool: &mut Pool,
+        min_shares: Balance,
+        is_view: bool,
+    ) -> (Vec<Balance>, Vec<Balance>, Balance) {
+        let from_tokens = from_pool.tokens();
+        let token_indexes: Vec<usize> = to_pool
+            .tokens()
+            .iter()
+            .map(|token_id| from_tokens.iter().position(|id| id == token_id).expect(ERR95_MIGRATION_TOKEN_MISMATCH))
+            .collect();
+        let remove_amounts = from_pool.remove_liquidity(
+            sender_id,
+            shares,
+            vec![0; from_pool.tokens().len()],
+            is_view
+        );
+        let mut add_amounts: Vec<Balance> = token_indexes.into_iter().map(|idx| remove_amounts[idx]).collect();
+        self.assert_no_deprecated_token_deposit(to_pool_id, to_pool.tokens(), &add_amounts);
+        let mint_shares = match to_pool {
+            Pool::SimplePool(_) => {
+                let mint_shares = to_pool.add_liquidity(sender_id, &mut add_amounts, is_view);
+                assert!(mint_shares >= min_shares, "{}", ERR68_SLIPPAGE);
+                mint_shares
+            }
+            Pool::StableSwapPool(_) | Pool::RatedSwapPool(_) | Pool::DegenSwapPool(_) => {
+                to_pool.add_stable_liquidity(
+                    sender_id,
+                    &add_amounts,
+                    min_shares,
+                    AdminFees::new(self.admin_fee_bps),
+                    self.internal_get_imbalance_penalty(to_pool_id),
+                    is_view
+                )
+            }
+        };
+        (remove_amounts, add_amounts, mint_shares)
+    }
+
     /// Check how much storage taken costs and refund the left over back.
+    /// If the attached deposit is short, the caller may cover it by auto storage top-up from wNEAR,
+    /// the exchange pays the storage until the wNEAR is unwrapped.
     /// Return the storage costs due to this call by far.
-    fn internal_check_storage(&self, prev_storage: StorageUsage) -> u128 {
+    fn internal_check_storage(&mut self, prev_storage: StorageUsage) -> u128 {
         let storage_cost = env::storage_usage()
             .checked_sub(prev_storage)
             .unwrap_or_default() as Balance
             * env::storage_byte_cost();
 
-        let refund = env::attached_deposit()
+        let mut attached = env::attached_deposit();
+        if storage_cost > attached {
+            let sender_id = env::predecessor_account_id();
+            if let Some(mut account) = self.internal_get_account(&sender_id) {
+                let topup = self.internal_auto_topup_storage(&sender_id, &mut account, storage_cost - attached);
+                if topup > 0 {
+                    attached += topup;
+                    self.internal_save_account(&sender_id, account);
+                }
+            }
+        }
+
+        let refund = attached
             .checked_sub(storage_cost)
             .expect(
                 format!(
                     "ERR_STORAGE_DEPOSIT need {}, attatched {}", 
-                    storage_cost, env::attached_deposit()
+                    storage_cost, attached
                 ).as_str()
             );
         if refund > 0 {
@@ -696,6 +874,7 @@ impl Contract {
         // exchange share was registered at creation time
         pool.share_register(&env::current_account_id());
         self.pools.push(&pool);
+        self.internal_count_new_pool(id, pool.kind());
         internal_set_swap_volume_u256_vec(id, vec![SwapVolumeU256::default(); pool.tokens().len()]);
         self.internal_check_storage(prev_storage);
         id
@@ -788,6 +967,7 @@ impl Contract {
                     &swap_action.token_out,
                     swap_action.min_amount_out.0,
                     referral_info,
+                    swap_action.max_price_impact_bps,
                 );
                 account.deposit(&swap_action.token_out, amount_out);
                 // [AUDIT_02]
@@ -805,6 +985,7 @@ impl Contract {
                     &swap_by_output_action.token_out,
                     swap_by_output_action.max_amount_in.map(|v| v.0),
                     referral_info,
+                    swap_by_output_action.max_price_impact_bps,
                 );
                 ActionResult::Amount(U128(amount_in))
             }
@@ -821,9 +1002,12 @@ impl Contract {
         token_out: &AccountId,
         min_amount_out: u128,
         referral_info: &Option<(AccountId, u32)>,
+        max_price_impact_bps: Option<u32>,
     ) -> u128 {
         self.internal_update_unit_share_cumulative_info(pool_id);
+        self.assert_no_deprecated_token_in(pool_id, token_in);
         let mut pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
+        let pool_before = max_price_impact_bps.map(|_| self.pools.get(pool_id).unwrap());
         // Replace pool.volumes for recording.
         let sv_u256s = internal_get_swap_volume_u256_vec_or_default(pool_id, &pool.get_volumes());
         let amount_out = pool.swap(
@@ -854,6 +1038,10 @@ impl Contract {
             sv_u256s,
         );
         self.pools.replace(pool_id, &pool);
+        self.internal_update_virtual_price_record(pool_id, &pool);
+        if let (Some(max_price_impact_bps), Some(pool_before)) = (max_price_impact_bps, pool_before) {
+            self.assert_price_impact(&pool_before, &pool, token_in, amount_in, token_out, max_price_impact_bps);
+        }
         amount_out
     }
 
@@ -867,9 +1055,12 @@ impl Contract {
         token_out: &AccountId,
         max_amount_in: Option<u128>,
         referral_info: &Option<(AccountId, u32)>,
+        max_price_impact_bps: Option<u32>,
     ) -> u128 {
         self.internal_update_unit_share_cumulative_info(pool_id);
+        self.assert_no_deprecated_token_in(pool_id, token_in);
         let mut pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
+        let pool_before = max_price_impact_bps.map(|_| self.pools.get(pool_id).unwrap());
         // Replace pool.volumes for recording.
         let sv_u256s = internal_get_swap_volume_u256_vec_or_default(pool_id, &pool.get_volumes());
         let amount_in = pool.swap_by_output(
@@ -900,8 +1091,47 @@ impl Contract {
             sv_u256s,
         );
         self.pools.replace(pool_id, &pool);
+        self.internal_update_virtual_price_record(pool_id, &pool);
+        if let (Some(max_price_impact_bps), Some(pool_before)) = (max_price_impact_bps, pool_before) {
+            self.assert_price_impact(&pool_before, &pool, token_in, amount_in, token_out, max_price_impact_bps);
+        }
         amount_in
     }
+
+    /// Compare the return of a probe swap on the pool before and after the swap,
+    /// the drop of it in bps is taken as the price impact.
+    /// The probe runs on copies, so neither of the given pools is touched.
+    /// A probe too small to get any output is redone with the whole amount_in,
+    /// if that gets nothing either the swap is too small to measure and the check is skipped.
+    fn assert_price_impact(
+        &self,
+        pool_before: &Pool,
+        pool_after: &Pool,
+        token_in: &AccountId,
+        amount_in: u128,
+        token_out: &AccountId,
+        max_price_impact_bps: u32,
+    ) {
+        let probe_out = |pool: &Pool, probe_amount: u128| {
+            pool.snapshot().swap(token_in, probe_amount, token_out, 0, AdminFees::new(self.admin_fee_bps), true)
+        };
+        let mut probe_amount = std::cmp::max(amount_in / PRICE_IMPACT_PROBE_DIVISOR, 1);
+        let mut probe_out_before = probe_out(pool_before, probe_amount);
+        if probe_out_before == 0 && probe_amount < amount_in {
+            probe_amount = amount_in;
+            probe_out_before = probe_out(pool_before, probe_amount);
+        }
+        if probe_out_before == 0 {
+            return;
+        }
+        let probe_out_after = probe_out(pool_after, probe_amount);
+        let price_impact_bps = if probe_out_after >= probe_out_before {
+            0
+        } else {
+            u128_ratio(probe_out_before - probe_out_after, FEE_DIVISOR as u128, probe_out_before)
+        };
+        assert!(price_impact_bps <= max_price_impact_bps as u128, "{}", ERR78_PRICE_IMPACT_TOO_HIGH);
+    }
 }
 
 
@@ -975,6 +1205,7 @@ impl Contract {
                     &swap_action.token_out,
                     swap_action.min_amount_out.0,
                     referral_info,
+                    swap_action.max_price_impact_bps,
                 );
                 token_cache.add(&swap_action.token_out, amount_out);
                 ActionResult::Amount(U128(amount_out))
@@ -992,6 +1223,7 @@ impl Contract {
                     &swap_by_output_action.token_out,
                     swap_by_output_action.max_amount_in.map(|v| v.0),
                     referral_info,
+                    swap_by_output_action.max_price_impact_bps,
                 );
                 ActionResult::Amount(U128(amount_in))
             }
@@ -1007,8 +1239,11 @@ impl Contract {
         token_out: &AccountId,
         min_amount_out: u128,
         referral_info: &Option<(AccountId, u32)>,
+        max_price_impact_bps: Option<u32>,
     ) -> u128 {
+        self.assert_no_deprecated_token_in(pool_id, token_in);
         let mut pool = pool_cache.remove(&pool_id).unwrap_or(self.pools.get(pool_id).expect(ERR85_NO_POOL));
+        let pool_before = max_price_impact_bps.map(|_| pool.snapshot());
         let amount_out = pool.swap(
             token_in,
             amount_in,
@@ -1021,6 +1256,9 @@ impl Contract {
             },
             true
         );
+        if let (Some(max_price_impact_bps), Some(pool_before)) = (max_price_impact_bps, pool_before) {
+            self.assert_price_impact(&pool_before, &pool, token_in, amount_in, token_out, max_price_impact_bps);
+        }
         pool_cache.insert(pool_id, pool);
         amount_out
     }
@@ -1034,8 +1272,11 @@ impl Contract {
         token_out: &AccountId,
         max_amount_in: Option<u128>,
         referral_info: &Option<(AccountId, u32)>,
+        max_price_impact_bps: Option<u32>,
     ) -> u128 {
+        self.assert_no_deprecated_token_in(pool_id, token_in);
         let mut pool = pool_cache.remove(&pool_id).unwrap_or(self.pools.get(pool_id).expect(ERR85_NO_POOL));
+        let pool_before = max_price_impact_bps.map(|_| pool.snapshot());
         let amount_in = pool.swap_by_output(
             token_in,
             amount_out,
@@ -1048,6 +1289,9 @@ impl Contract {
             },
             true
         );
+        if let (Some(max_price_impact_bps), Some(pool_before)) = (max_price_impact_bps, pool_before) {
+            self.assert_price_impact(&pool_before, &pool, token_in, amount_in, token_out, max_price_impact_bps);
+        }
         pool_cache.insert(pool_id, pool);
         amount_in
     }
@@ -1154,9 +1398,11 @@ mod tests {
                     amount_in: Some(U128(amount_in)),
                     token_out: token_out.into(),
                     min_amount_out: U128(1),
+                    max_price_impact_bps: None,
                 }],
                 None,
                 None,
+                None,
             )
             .0
     }
@@ -1410,9 +1656,11 @@ mod tests {
                 amount_in: Some(U128(12446461932933863316530306u128)),
                 token_out: accounts(2).into(),
                 min_amount_out: U128(0),
+                max_price_impact_bps: None,
             }],
             None,
             None,
+            None,
         );
     }
 
@@ -1526,7 +1774,7 @@ mod tests {
         testing_env!(context
             .predecessor_account_id(acc.clone())
             .build());
-        contract.execute_actions(actions, None, None);
+        contract.execute_actions(actions, None, None, None);
     }
 
     #[test]
@@ -1584,9 +1832,11 @@ mod tests {
                 amount_in: Some(U128(1_000_000)),
                 token_out: accounts(2).into(),
                 min_amount_out: U128(1_000_000),
+                max_price_impact_bps: None,
             }],
             None,
             None,
+            None,
         );
     }
 
@@ -1606,7 +1856,7 @@ mod tests {
         testing_env!(context.attached_deposit(to_yocto("1")).build());
         contract.storage_deposit(None, None);
         testing_env!(context.attached_deposit(1).build());
-        contract.swap(vec![], None, None);
+        contract.swap(vec![], None, None, None);
     }
 
     /// Check that can not swap non whitelisted tokens when attaching 0 deposit (access key).
@@ -1662,6 +1912,7 @@ mod tests {
                     amount_in: Some(U128(1_000)),
                     token_out: accounts(2).into(),
                     min_amount_out: U128(1),
+                    max_price_impact_bps: None,
                 },
                 SwapAction {
                     pool_id: 0,
@@ -1669,10 +1920,12 @@ mod tests {
                     amount_in: None,
                     token_out: accounts(1).into(),
                     min_amount_out: U128(1),
+                    max_price_impact_bps: None,
                 },
             ],
             None,
             None,
+            None,
         );
         // Roundtrip returns almost everything except 0.25% fee.
         assert_eq!(contract.get_deposit(acc, accounts(1)).0, 1_000_000 - 6);
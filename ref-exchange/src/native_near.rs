@@ -0,0 +1,130 @@
+//! Native NEAR in and out of the exchange, wrapping and unwrapping through the wNEAR contract.
+
+use near_sdk::{PromiseOrValue, PromiseResult};
+
+use crate::*;
+use crate::utils::{ext_self, ext_wrap_near, GAS_FOR_NEAR_DEPOSIT, MIN_GAS_FOR_NEAR_DEPOSIT_CALLBACK};
+
+#[near_bindgen]
+impl Contract {
+    /// Wrap the attached NEAR into wNEAR, credit it to the sender's deposit and then execute actions.
+    /// The exchange must have been registered in the wNEAR contract, which is always the case once it holds wNEAR.
+    /// If actions fail (e.g. slippage), the wNEAR stays in the sender's deposit.
+    #[payable]
+    pub fn near_deposit_and_execute(
+        &mut self,
+        actions: Vec<Action>,
+        referral_id: Option<ValidAccountId>,
+        skip_degen_price_sync: Option<bool>,
+        deadline_ns: Option<U64>,
+    ) -> Promise {
+        self.assert_contract_running();
+        assert_deadline(deadline_ns);
+        assert_ne!(actions.len(), 0, "{}", ERR72_AT_LEAST_ONE_SWAP);
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "{}", ERR35_AT_LEAST_ONE_YOCTO);
+        let wnear_id = self.wnear_id.clone().expect(ERR30_WNEAR_NOT_SET);
+        self.assert_no_frozen_tokens(&[wnear_id.clone()]);
+        let sender_id = env::predecessor_account_id();
+        // register wnear now, so that crediting it in callback needs no more storage.
+        let mut account = self.internal_unwrap_account(&sender_id);
+        account.register(&vec![wnear_id.clone().try_into().unwrap()]);
+        self.internal_save_account(&sender_id, account);
+
+        let remaining_gas = env::prepaid_gas() - env::used_gas();
+        assert!(
+            remaining_gas >= GAS_FOR_NEAR_DEPOSIT + GAS_FOR_BASIC_OP + MIN_GAS_FOR_NEAR_DEPOSIT_CALLBACK,
+            "{}", ERR37_NOT_ENOUGH_GAS
+        );
+        let callback_gas = remaining_gas - GAS_FOR_NEAR_DEPOSIT - GAS_FOR_BASIC_OP;
+        ext_wrap_near::near_deposit(
+            &wnear_id,
+            amount,
+            GAS_FOR_NEAR_DEPOSIT,
+        )
+        .then(ext_self::exchange_callback_post_near_deposit(
+            sender_id,
+            U128(amount),
+            actions,
+            referral_id,
+            skip_degen_price_sync,
+            deadline_ns,
+            &env::current_account_id(),
+            0,
+            callback_gas,
+        ))
+    }
+
+    /// Withdraw deposited wNEAR as native NEAR, amount 0 means all.
+    #[payable]
+    pub fn withdraw_near(&mut self, amount: U128, unregister: Option<bool>) -> Promise {
+        let wnear_id = self.wnear_id.clone().expect(ERR30_WNEAR_NOT_SET);
+        self.withdraw(wnear_id.try_into().unwrap(), amount, unregister, Some(false))
+    }
+
+    /// Credit the wrapped NEAR to sender, then run actions in a separate receipt,
+    /// so that a failed action won't revert the deposit.
+    #[private]
+    pub fn exchange_callback_post_near_deposit(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        actions: Vec<Action>,
+        referral_id: Option<ValidAccountId>,
+        skip_degen_price_sync: Option<bool>,
+        deadline_ns: Option<U64>,
+    ) -> PromiseOrValue<ActionResult> {
+        assert_eq!(
+            env::promise_results_count(),
+            1,
+            "{}",
+            ERR25_CALLBACK_POST_WITHDRAW_INVALID
+        );
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                let wnear_id = self.wnear_id.clone().unwrap();
+                // goes to lostfound if the account can't take it.
+                self.internal_deposit_or_lostfound(&sender_id, &wnear_id, amount.0);
+                // short of gas the actions fail in their own receipt, the deposit stays.
+                let execute_gas = (env::prepaid_gas() - env::used_gas()).saturating_sub(GAS_FOR_BASIC_OP);
+                PromiseOrValue::Promise(ext_self::exchange_callback_execute_actions(
+                    sender_id,
+                    actions,
+                    referral_id,
+                    skip_degen_price_sync,
+                    deadline_ns,
+                    &env::current_account_id(),
+                    0,
+                    execute_gas,
+                ))
+            }
+            PromiseResult::Failed => {
+                log!("Wrap {} NEAR for {} failed, refund it.", amount.0, sender_id);
+                Promise::new(sender_id).transfer(amount.0);
+                PromiseOrValue::Value(ActionResult::None)
+            }
+        }
+    }
+
+    #[private]
+    pub fn exchange_callback_execute_actions(
+        &mut self,
+        sender_id: AccountId,
+        actions: Vec<Action>,
+        referral_id: Option<ValidAccountId>,
+        skip_degen_price_sync: Option<bool>,
+        deadline_ns: Option<U64>,
+    ) -> ActionResult {
+        self.assert_contract_running();
+        assert_deadline(deadline_ns);
+        let mut account = self.internal_unwrap_account(&sender_id);
+        let referral_info :Option<(AccountId, u32)> = referral_id
+            .as_ref().and_then(|rid| self.referrals.get(rid.as_ref()))
+            .map(|fee| (referral_id.unwrap().into(), fee));
+        let result =
+            self.internal_execute_actions(&mut account, &referral_info, &actions, ActionResult::None, skip_degen_price_sync.unwrap_or(false));
+        self.internal_save_account(&sender_id, account);
+        result
+    }
+}
@@ -6,6 +6,7 @@ use near_contract_standards::fungible_token::core_impl::ext_fungible_token;
 
 use crate::*;
 use crate::rated_swap::rate::{global_register_rate, global_unregister_rate, global_update_rated_token_extra_info};
+use crate::rated_swap::rate_guard::{global_remove_rate_guard, RateGuard};
 use crate::utils::{FEE_DIVISOR, MAX_ADMIN_FEE_BPS, GAS_FOR_BASIC_OP};
 
 #[near_bindgen]
@@ -115,6 +116,15 @@ impl Contract {
         self.wnear_id = Some(wnear_id);
     }
 
+    /// Set max amount of wNEAR that can be unwrapped in a single auto storage top-up.
+    #[payable]
+    pub fn modify_storage_topup_cap(&mut self, cap: U128) {
+        assert_one_yocto();
+        self.assert_owner();
+        log!("Modify storage_topup_cap from {} to {}", read_storage_topup_cap(), cap.0);
+        write_storage_topup_cap(cap.0);
+    }
+
     /// Change state of contract, Only can be called by owner or guardians.
     #[payable]
     pub fn change_state(&mut self, state: RunningState) {
@@ -302,7 +312,7 @@ impl Contract {
         let mut account = self.internal_unwrap_account(&owner_id);
         account.withdraw(&token_id, amount);
         self.accounts.insert(&owner_id, &account.into());
-        self.internal_send_tokens(&owner_id, &token_id, amount, skip_unwrap_near)
+        self.internal_send_tokens(&owner_id, &owner_id, &token_id, amount, skip_unwrap_near)
     }
 
     /// to eventually change a stable pool's amp factor
@@ -383,6 +393,59 @@ impl Contract {
         log!("Update rated token {} extra info: {}", token_id, extra_info);
     }
 
+    /// Set the max rate increase/decrease per interval for a rated token.
+    /// An update out of these bounds would be rejected and the token paused.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_id` - The rated token id.
+    /// * `max_increase_bps` - Max rate increase in bps per interval, at most FEE_DIVISOR.
+    /// * `max_decrease_bps` - Max rate decrease in bps per interval, at most FEE_DIVISOR.
+    /// * `interval_sec` - The interval in seconds.
+    #[payable]
+    pub fn set_rated_token_guard(&mut self, token_id: ValidAccountId, max_increase_bps: u32, max_decrease_bps: u32, interval_sec: u32) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        let token_id: AccountId = token_id.into();
+        assert!(global_get_rate(&token_id).is_some(), "{}", ERR102_INVALID_TOKEN_ID);
+        let rate_guard = if let Some(mut rate_guard) = global_get_rate_guard(&token_id) {
+            rate_guard.update_bounds(max_increase_bps, max_decrease_bps, interval_sec);
+            rate_guard
+        } else {
+            RateGuard::new(max_increase_bps, max_decrease_bps, interval_sec)
+        };
+        global_set_rate_guard(&token_id, rate_guard);
+        log!("Rated token {} guard set to +{}/-{} bps per {} sec", token_id, max_increase_bps, max_decrease_bps, interval_sec);
+    }
+
+    /// Remove the rate guard of a rated token. Only owner can call.
+    #[payable]
+    pub fn remove_rated_token_guard(&mut self, token_id: ValidAccountId) {
+        assert_one_yocto();
+        self.assert_owner();
+        let token_id: AccountId = token_id.into();
+        if global_remove_rate_guard(&token_id) {
+            log!("Rated token {} guard removed.", token_id);
+        } else {
+            log!("Rated token {} has no guard.", token_id);
+        }
+    }
+
+    /// Resume a rated token paused by its rate guard. Only owner can call.
+    /// The rate stays at the last accepted value, loosen the bounds first if the reported one is legit.
+    #[payable]
+    pub fn resume_rated_token(&mut self, token_id: ValidAccountId) {
+        assert_one_yocto();
+        self.assert_owner();
+        let token_id: AccountId = token_id.into();
+        let mut rate_guard = global_get_rate_guard(&token_id).expect(ERR102_INVALID_TOKEN_ID);
+        if rate_guard.is_paused {
+            rate_guard.is_paused = false;
+            global_set_rate_guard(&token_id, rate_guard);
+            log!("Rated token {} resumed.", token_id);
+        }
+    }
+
     /// Register new degen token.
     #[payable]
     pub fn register_degen_token(&mut self, token_id: ValidAccountId, degen_type: DegenType) {
@@ -489,6 +552,29 @@ impl Contract {
         write_pool_limit_to_storage(pool_limit);
     }
 
+    /// Set the imbalance penalty of a stable pool,
+    /// liquidity ops leaving the pool out of band would pay multiplier_bps / 10000 times the fee.
+    #[payable]
+    pub fn set_imbalance_penalty(&mut self, pool_id: u64, imbalance_penalty: ImbalancePenalty) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        assert!(self.get_pool(pool_id).pool_kind == "STABLE_SWAP", "{}", ERR88_NOT_STABLE_POOL);
+        imbalance_penalty.assert_valid();
+        let mut imbalance_penalties = read_imbalance_penalty_from_storage();
+        imbalance_penalties.insert(&pool_id, &imbalance_penalty);
+        write_imbalance_penalty_to_storage(imbalance_penalties);
+        log!("Set pool {} imbalance penalty: band_bps {}, multiplier_bps {}", pool_id, imbalance_penalty.band_bps, imbalance_penalty.multiplier_bps);
+    }
+
+    #[payable]
+    pub fn remove_imbalance_penalty(&mut self, pool_id: u64) {
+        assert_one_yocto();
+        self.assert_owner();
+        let mut imbalance_penalties = read_imbalance_penalty_from_storage();
+        assert!(imbalance_penalties.remove(&pool_id).is_some(), "Invalid pool_id");
+        write_imbalance_penalty_to_storage(imbalance_penalties);
+    }
+
     pub(crate) fn assert_owner(&self) {
         assert_eq!(
             env::predecessor_account_id(),
@@ -508,7 +594,9 @@ impl Contract {
     // [AUDIT_09]
     #[private]
     pub fn migrate() -> Self {
-        env::state_read().expect(ERR103_NOT_INITIALIZED)
+        let contract: Contract = env::state_read().expect(ERR103_NOT_INITIALIZED);
+        internal_migrate_client_echo_whitelists();
+        contract
     }
 }
 
@@ -1,7 +1,10 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::{AccountId, Balance};
+use near_sdk::{env, AccountId, Balance};
 
 use crate::admin_fee::AdminFees;
+use crate::errors::ERR88_NOT_STABLE_POOL;
+use crate::imbalance_penalty::ImbalancePenalty;
+use crate::pool_token_manager::StableLikePool;
 use crate::degen_swap::DegenSwapPool;
 use crate::simple_pool::SimplePool;
 use crate::stable_swap::StableSwapPool;
@@ -29,6 +32,12 @@ impl Pool {
         }
     }
 
+    /// Returns an in-memory copy of the pool, for probing swaps without touching the original.
+    /// Share balances live in storage and are shared with the original, so the copy must stay read-only there.
+    pub fn snapshot(&self) -> Pool {
+        Pool::try_from_slice(&self.try_to_vec().unwrap()).unwrap()
+    }
+
     /// Returns which tokens are in the underlying pool.
     pub fn tokens(&self) -> &[AccountId] {
         match self {
@@ -48,6 +57,34 @@ impl Pool {
         }
     }
 
+    /// Appends a new token into stable/rated/degen pool with a seeding amount.
+    /// Returns the shares minted to sender.
+    pub fn add_token(
+        &mut self,
+        sender_id: &AccountId,
+        token_id: &AccountId,
+        decimal: u8,
+        amount: Balance,
+    ) -> Balance {
+        match self {
+            Pool::SimplePool(_) => env::panic(ERR88_NOT_STABLE_POOL.as_bytes()),
+            Pool::StableSwapPool(pool) => pool.append_token(sender_id, token_id, decimal, amount),
+            Pool::RatedSwapPool(pool) => pool.append_token(sender_id, token_id, decimal, amount),
+            Pool::DegenSwapPool(pool) => pool.append_token(sender_id, token_id, decimal, amount),
+        }
+    }
+
+    /// Removes a drained token from stable/rated/degen pool.
+    /// Returns the dust amount of it left in the pool.
+    pub fn remove_token(&mut self, token_id: &AccountId) -> Balance {
+        match self {
+            Pool::SimplePool(_) => env::panic(ERR88_NOT_STABLE_POOL.as_bytes()),
+            Pool::StableSwapPool(pool) => pool.remove_token(token_id),
+            Pool::RatedSwapPool(pool) => pool.remove_token(token_id),
+            Pool::DegenSwapPool(pool) => pool.remove_token(token_id),
+        }
+    }
+
     /// Adds liquidity into underlying pool.
     /// Updates amounts to amount kept in the pool.
     pub fn add_liquidity(
@@ -70,11 +107,12 @@ impl Pool {
         amounts: &Vec<Balance>,
         min_shares: Balance,
         admin_fee: AdminFees,
+        imbalance_penalty: Option<ImbalancePenalty>,
         is_view: bool
     ) -> Balance {
         match self {
             Pool::SimplePool(_) => unimplemented!(),
-            Pool::StableSwapPool(pool) => pool.add_liquidity(sender_id, amounts, min_shares, &admin_fee, is_view),
+            Pool::StableSwapPool(pool) => pool.add_liquidity_with_penalty(sender_id, amounts, min_shares, &admin_fee, imbalance_penalty.as_ref(), is_view),
             Pool::RatedSwapPool(pool) => pool.add_liquidity(sender_id, amounts, min_shares, &admin_fee, is_view),
             Pool::DegenSwapPool(pool) => pool.add_liquidity(sender_id, amounts, min_shares, &admin_fee, is_view),
         }
@@ -109,12 +147,13 @@ impl Pool {
         amounts: Vec<Balance>,
         max_burn_shares: Balance,
         admin_fee: AdminFees,
+        imbalance_penalty: Option<ImbalancePenalty>,
         is_view: bool
     ) -> Balance {
         match self {
             Pool::SimplePool(_) => unimplemented!(),
             Pool::StableSwapPool(pool) => {
-                pool.remove_liquidity_by_tokens(sender_id, amounts, max_burn_shares, &admin_fee, is_view)
+                pool.remove_liquidity_by_tokens_with_penalty(sender_id, amounts, max_burn_shares, &admin_fee, imbalance_penalty.as_ref(), is_view)
             },
             Pool::RatedSwapPool(pool) => {
                 pool.remove_liquidity_by_tokens(sender_id, amounts, max_burn_shares, &admin_fee, is_view)
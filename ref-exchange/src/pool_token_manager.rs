@@ -0,0 +1,236 @@
+use crate::*;
+use crate::utils::{nano_to_sec, u128_ratio, SwapVolume, FEE_DIVISOR, U256};
+
+/// Max number of tokens a stable/rated/degen pool can hold by appending.
+pub const MAX_POOL_TOKENS: usize = 8;
+
+/// Stable, rated and degen pools keep their per-token vectors in the same layout,
+/// appending and removing a token goes through this trait for all of them.
+pub trait StableLikePool {
+    const MIN_DECIMAL: u8;
+    const MAX_DECIMAL: u8;
+    const MIN_RESERVE: Balance;
+
+    /// (token_account_ids, token_decimals, c_amounts, volumes)
+    fn token_vectors_mut(&mut self) -> (&mut Vec<AccountId>, &mut Vec<u8>, &mut Vec<Balance>, &mut Vec<SwapVolume>);
+    fn get_shares_total_supply(&self) -> Balance;
+    fn get_total_fee(&self) -> u32;
+    fn to_c_amount(&self, amount: Balance, index: usize) -> Balance;
+    fn from_c_amount(&self, c_amount: Balance, index: usize) -> Balance;
+    /// Invariant D of current c_amounts, panics if rates or degens of the pool are not valid.
+    fn compute_current_d(&self) -> Balance;
+    fn mint_shares_to(&mut self, account_id: &AccountId, shares: Balance);
+
+    /// Append a new token into the pool, seeded with amount from sender.
+    /// Sender gets shares in proportion to the growth of invariant D,
+    /// less the pool fee which stays with the existing LPs.
+    fn append_token(
+        &mut self,
+        sender_id: &AccountId,
+        token_id: &AccountId,
+        decimal: u8,
+        amount: Balance,
+    ) -> Balance {
+        assert!(decimal <= Self::MAX_DECIMAL, "{}", ERR60_DECIMAL_ILLEGAL);
+        assert!(decimal >= Self::MIN_DECIMAL, "{}", ERR60_DECIMAL_ILLEGAL);
+        let shares_total_supply = self.get_shares_total_supply();
+        assert!(shares_total_supply > 0, "{}", ERR94_POOL_NO_LIQUIDITY);
+        let d0 = self.compute_current_d();
+
+        let (token_account_ids, token_decimals, _, volumes) = self.token_vectors_mut();
+        assert!(!token_account_ids.contains(token_id), "{}", ERR92_TOKEN_DUPLICATES);
+        assert!(token_account_ids.len() < MAX_POOL_TOKENS, "{}", ERR96_TOO_MANY_POOL_TOKENS);
+        token_account_ids.push(token_id.clone());
+        token_decimals.push(decimal);
+        volumes.push(SwapVolume::default());
+        let index = token_account_ids.len() - 1;
+        let c_amount = self.to_c_amount(amount, index);
+        assert!(c_amount >= Self::MIN_RESERVE, "{}", ERR69_MIN_RESERVE);
+        self.token_vectors_mut().2.push(c_amount);
+
+        let d1 = self.compute_current_d();
+        assert!(d1 > d0, "{}", ERR67_LPSHARE_CALC_ERR);
+        let new_shares = (U256::from(d1 - d0) * U256::from(shares_total_supply) / U256::from(d0)).as_u128();
+        let fee_part = u128_ratio(new_shares, self.get_total_fee() as u128, FEE_DIVISOR as u128);
+        let mint_shares = new_shares - fee_part;
+        assert!(mint_shares > 0, "{}", ERR32_ZERO_SHARES);
+        self.mint_shares_to(sender_id, mint_shares);
+        env::log(
+            format!(
+                "Token {} appended with {} seeded by {}, mint {} shares, fee is {} shares",
+                token_id, amount, sender_id, mint_shares, fee_part
+            )
+            .as_bytes(),
+        );
+        mint_shares
+    }
+
+    /// Remove a drained token from the pool,
+    /// returns the dust amount of it left in the pool.
+    fn remove_token(&mut self, token_id: &AccountId) -> Balance {
+        let (token_account_ids, _, c_amounts, _) = self.token_vectors_mut();
+        assert!(token_account_ids.len() > 2, "{}", ERR89_WRONG_TOKEN_COUNT);
+        let index = token_account_ids.iter().position(|id| id == token_id).expect(ERR63_MISSING_TOKEN);
+        let c_amount = c_amounts[index];
+        assert!(c_amount < 2 * Self::MIN_RESERVE, "{}", ERR97_TOKEN_NOT_DRAINED);
+        let amount = self.from_c_amount(c_amount, index);
+
+        let (token_account_ids, token_decimals, c_amounts, volumes) = self.token_vectors_mut();
+        token_account_ids.remove(index);
+        token_decimals.remove(index);
+        c_amounts.remove(index);
+        volumes.remove(index);
+        env::log(
+            format!(
+                "Token {} removed, {} left in pool",
+                token_id, amount
+            )
+            .as_bytes(),
+        );
+        amount
+    }
+}
+
+pub fn read_deprecated_tokens_from_storage() -> UnorderedMap<u64, Vec<AccountId>> {
+    if let Some(content) = env::storage_read(DEPRECATED_POOL_TOKENS.as_bytes()) {
+        UnorderedMap::try_from_slice(&content).expect("deserialize deprecated pool tokens failed.")
+    } else {
+        UnorderedMap::new(StorageKey::DeprecatedPoolTokens)
+    }
+}
+
+pub fn write_deprecated_tokens_to_storage(deprecated_tokens: UnorderedMap<u64, Vec<AccountId>>) {
+    env::storage_write(
+        DEPRECATED_POOL_TOKENS.as_bytes(),
+        &deprecated_tokens.try_to_vec().unwrap(),
+    );
+}
+
+impl Contract {
+    /// A deprecated token is withdraw-only in its pool:
+    /// it can neither be swapped in, nor be added as liquidity.
+    pub fn assert_no_deprecated_token_in(&self, pool_id: u64, token_in: &AccountId) {
+        if let Some(tokens) = read_deprecated_tokens_from_storage().get(&pool_id) {
+            assert!(!tokens.contains(token_in), "{}", ERR93_TOKEN_DEPRECATED);
+        }
+    }
+
+    pub fn assert_no_deprecated_token_deposit(&self, pool_id: u64, pool_tokens: &[AccountId], amounts: &Vec<Balance>) {
+        if let Some(tokens) = read_deprecated_tokens_from_storage().get(&pool_id) {
+            for (token_id, amount) in pool_tokens.iter().zip(amounts.iter()) {
+                assert!(*amount == 0 || !tokens.contains(token_id), "{}", ERR93_TOKEN_DEPRECATED);
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Append a new token into an existing stable/rated/degen pool.
+    /// The caller seeds it with amount from its deposit and gets the minted shares less the pool fee.
+    #[payable]
+    pub fn add_pool_token(&mut self, pool_id: u64, token_id: ValidAccountId, decimals: u8, amount: U128) -> U128 {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        self.assert_contract_running();
+        let token_id: AccountId = token_id.into();
+        self.assert_no_frozen_tokens(&[token_id.clone()]);
+        self.internal_update_unit_share_cumulative_info(pool_id);
+        let sender_id = env::predecessor_account_id();
+        let mut pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
+        let mut sv_u256s = internal_get_swap_volume_u256_vec_or_default(pool_id, &pool.get_volumes());
+        let mut deposits = self.internal_unwrap_account(&sender_id);
+        deposits.withdraw(&token_id, amount.0);
+        self.internal_save_account(&sender_id, deposits);
+        let shares = pool.add_token(&sender_id, &token_id, decimals, amount.0);
+        pool.assert_tvl_not_exceed_limit(pool_id);
+        self.pools.replace(pool_id, &pool);
+        sv_u256s.push(SwapVolumeU256::default());
+        internal_set_swap_volume_u256_vec(pool_id, sv_u256s);
+        // twap records are based on the old token set, restart them.
+        if self.unit_share_cumulative_infos.get(&pool_id).is_some() {
+            let amounts = self.internal_unit_share_token_amounts(pool_id).expect("Too few shares in the pool");
+            self.internal_set_unit_share_cumulative_infos(pool_id, UnitShareCumulativeInfo::new(nano_to_sec(env::block_timestamp()), amounts));
+        }
+        U128(shares)
+    }
+
+    /// Put a token of the pool into withdraw-only mode,
+    /// LPs can still exit from it through remove_liquidity(_by_tokens) and swapping it out.
+    #[payable]
+    pub fn deprecate_pool_token(&mut self, pool_id: u64, token_id: ValidAccountId) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        let token_id: AccountId = token_id.into();
+        let pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
+        assert!(!matches!(pool, Pool::SimplePool(_)), "{}", ERR88_NOT_STABLE_POOL);
+        assert!(pool.tokens().contains(&token_id), "{}", ERR63_MISSING_TOKEN);
+        let mut deprecated_tokens = read_deprecated_tokens_from_storage();
+        let mut tokens = deprecated_tokens.get(&pool_id).unwrap_or_default();
+        assert!(!tokens.contains(&token_id), "{}", ERR93_TOKEN_DEPRECATED);
+        tokens.push(token_id.clone());
+        deprecated_tokens.insert(&pool_id, &tokens);
+        write_deprecated_tokens_to_storage(deprecated_tokens);
+        log!("Token {} deprecated in pool {}", token_id, pool_id);
+    }
+
+    #[payable]
+    pub fn undeprecate_pool_token(&mut self, pool_id: u64, token_id: ValidAccountId) {
+        assert_one_yocto();
+        self.assert_owner();
+        let token_id: AccountId = token_id.into();
+        let mut deprecated_tokens = read_deprecated_tokens_from_storage();
+        let mut tokens = deprecated_tokens.get(&pool_id).expect(ERR63_MISSING_TOKEN);
+        let index = tokens.iter().position(|id| id == &token_id).expect(ERR63_MISSING_TOKEN);
+        tokens.remove(index);
+        if tokens.is_empty() {
+            deprecated_tokens.remove(&pool_id);
+        } else {
+            deprecated_tokens.insert(&pool_id, &tokens);
+        }
+        write_deprecated_tokens_to_storage(deprecated_tokens);
+        log!("Token {} undeprecated in pool {}", token_id, pool_id);
+    }
+
+    /// Remove a deprecated token from its pool once LPs have drained it to dust,
+    /// the dust goes to owner's lostfound.
+    #[payable]
+    pub fn remove_pool_token(&mut self, pool_id: u64, token_id: ValidAccountId) -> U128 {
+        assert_one_yocto();
+        self.assert_owner();
+        let token_id: AccountId = token_id.into();
+        self.internal_update_unit_share_cumulative_info(pool_id);
+        let mut deprecated_tokens = read_deprecated_tokens_from_storage();
+        let mut tokens = deprecated_tokens.get(&pool_id).expect(ERR63_MISSING_TOKEN);
+        let index = tokens.iter().position(|id| id == &token_id).expect(ERR63_MISSING_TOKEN);
+        tokens.remove(index);
+        if tokens.is_empty() {
+            deprecated_tokens.remove(&pool_id);
+        } else {
+            deprecated_tokens.insert(&pool_id, &tokens);
+        }
+        write_deprecated_tokens_to_storage(deprecated_tokens);
+
+        let mut pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
+        let token_index = pool.tokens().iter().position(|id| id == &token_id).expect(ERR63_MISSING_TOKEN);
+        let mut sv_u256s = internal_get_swap_volume_u256_vec_or_default(pool_id, &pool.get_volumes());
+        let amount = pool.remove_token(&token_id);
+        self.pools.replace(pool_id, &pool);
+        sv_u256s.remove(token_index);
+        internal_set_swap_volume_u256_vec(pool_id, sv_u256s);
+        // twap records are based on the old token set, restart them.
+        if self.unit_share_cumulative_infos.get(&pool_id).is_some() {
+            let amounts = self.internal_unit_share_token_amounts(pool_id).expect("Too few shares in the pool");
+            self.internal_set_unit_share_cumulative_infos(pool_id, UnitShareCumulativeInfo::new(nano_to_sec(env::block_timestamp()), amounts));
+        }
+        if amount > 0 {
+            let owner_id = self.owner_id.clone();
+            self.insert_lostfound_token(&owner_id, &token_id, amount);
+        }
+        U128(amount)
+    }
+
+    pub fn get_pool_deprecated_tokens(&self, pool_id: u64) -> Vec<AccountId> {
+        read_deprecated_tokens_from_storage().get(&pool_id).unwrap_or_default()
+    }
+}
@@ -5,6 +5,7 @@ use near_sdk::{env, AccountId, Balance, Timestamp};
 
 use crate::admin_fee::AdminFees;
 use crate::errors::*;
+use crate::pool_token_manager::StableLikePool;
 use crate::rated_swap::math::{
     Fees, RatedSwap, SwapResult, MAX_AMP, MAX_AMP_CHANGE, MIN_AMP, MIN_RAMP_DURATION,
 };
@@ -12,9 +13,11 @@ use crate::utils::{add_to_collection, SwapVolume, FEE_DIVISOR, U256, u128_ratio}
 use crate::StorageKey;
 
 use self::rate::*;
+use self::rate_guard::is_global_rate_paused;
 
 mod math;
 pub mod rate;
+pub mod rate_guard;
 mod stnear_rate;
 mod linear_rate;
 mod nearx_rate;
@@ -107,6 +110,7 @@ impl RatedSwapPool {
     fn assert_rates_valid(&self) {
         for token_id in &self.token_account_ids {
             assert!(is_global_rate_valid(token_id) == true, "{}", ERR120_RATES_EXPIRED);
+            assert!(!is_global_rate_paused(token_id), "{}", ERR121_RATE_PAUSED);
         }
     }
 
@@ -800,6 +804,44 @@ impl RatedSwapPool {
     }
 }
 
+impl StableLikePool for RatedSwapPool {
+    const MIN_DECIMAL: u8 = MIN_DECIMAL;
+    const MAX_DECIMAL: u8 = MAX_DECIMAL;
+    const MIN_RESERVE: Balance = MIN_RESERVE;
+
+    fn token_vectors_mut(&mut self) -> (&mut Vec<AccountId>, &mut Vec<u8>, &mut Vec<Balance>, &mut Vec<SwapVolume>) {
+        (&mut self.token_account_ids, &mut self.token_decimals, &mut self.c_amounts, &mut self.volumes)
+    }
+
+    fn get_shares_total_supply(&self) -> Balance {
+        self.shares_total_supply
+    }
+
+    fn get_total_fee(&self) -> u32 {
+        self.total_fee
+    }
+
+    fn to_c_amount(&self, amount: Balance, index: usize) -> Balance {
+        self.amount_to_c_amount(amount, index)
+    }
+
+    fn from_c_amount(&self, c_amount: Balance, index: usize) -> Balance {
+        self.c_amount_to_amount(c_amount, index)
+    }
+
+    fn compute_current_d(&self) -> Balance {
+        self.assert_rates_valid();
+        self.get_invariant_with_rates(&self.get_rates())
+            .compute_d_with_rates(&self.c_amounts)
+            .expect(ERR66_INVARIANT_CALC_ERR)
+            .as_u128()
+    }
+
+    fn mint_shares_to(&mut self, account_id: &AccountId, shares: Balance) {
+        self.mint_shares(account_id, shares, false);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use near_sdk::test_utils::{accounts, VMContextBuilder};
@@ -862,4 +904,38 @@ mod tests {
         assert_eq!(pool.c_amounts, vec![100001 * PRECISION, 199998_000000009995002449799089]);
     }
 
+    #[test]
+    fn test_rated_append_and_remove_token() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        let mut pool = new_rated_stnear_pool(TARGET_DECIMAL, 1000, 25);
+        let cross_call_result = near_sdk::serde_json::to_vec(&U128(2_000000000000000000000000)).unwrap();
+        if let Some(mut rate) = global_get_rate(accounts(1).as_ref()) {
+            rate.set(&cross_call_result);
+            global_set_rate(accounts(1).as_ref(), &rate);
+        }
+        let mut amounts = vec![100000 * PRECISION, 200000 * PRECISION];
+        let shares = pool.add_liquidity(accounts(0).as_ref(), &mut amounts, 1, &AdminFees::zero(), false);
+
+        // seeding shares are charged with the pool fee
+        let new_shares = pool.append_token(accounts(0).as_ref(), accounts(3).as_ref(), TARGET_DECIMAL, 200000 * PRECISION);
+        assert!(new_shares < shares / 2);
+        assert!(new_shares > shares / 2 * 9970 / 10000);
+        assert_eq!(pool.share_total_balance(), shares + new_shares);
+        assert_eq!(pool.get_rates(), vec![2 * PRECISION, PRECISION, PRECISION]);
+        assert_eq!(pool.c_amounts.len(), 3);
+        assert_eq!(pool.volumes.len(), 3);
+
+        // dust token can be removed
+        pool.append_token(accounts(0).as_ref(), accounts(4).as_ref(), TARGET_DECIMAL, MIN_RESERVE);
+        assert_eq!(pool.remove_token(accounts(4).as_ref()), MIN_RESERVE);
+        assert_eq!(
+            pool.tokens(),
+            vec![accounts(1).to_string(), accounts(2).to_string(), accounts(3).to_string()]
+        );
+        assert_eq!(pool.token_decimals.len(), 3);
+        assert_eq!(pool.c_amounts.len(), 3);
+        assert_eq!(pool.volumes.len(), 3);
+    }
+
 }
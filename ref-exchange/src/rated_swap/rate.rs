@@ -10,7 +10,7 @@ use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Mutex;
 
-use crate::RATE_STORAGE_KEY;
+use crate::{RATE_STORAGE_KEY, update_storage_bytes};
 
 pub static RATES: Lazy<Mutex<HashMap<AccountId, Rate>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
@@ -121,10 +121,12 @@ pub fn global_register_rate(rate_type: &String, token_id: &AccountId, extra_info
     if !rates.contains_key(token_id) {
         rates.insert(token_id.clone(), Rate::new(rate_type.clone(), token_id.clone(), extra_info));
         // save back to storage
+        let prev_storage = env::storage_usage();
         env::storage_write(
             RATE_STORAGE_KEY.as_bytes(), 
             &rates.try_to_vec().unwrap(),
         );
+        update_storage_bytes(prev_storage, |storage_counters| &mut storage_counters.rated_registry_bytes);
         true
     } else {
         false
@@ -144,10 +146,12 @@ pub fn global_unregister_rate(token_id: &AccountId) -> bool {
     if rates.contains_key(token_id) {
         rates.remove(token_id);
         // save back to storage
+        let prev_storage = env::storage_usage();
         env::storage_write(
             RATE_STORAGE_KEY.as_bytes(), 
             &rates.try_to_vec().unwrap(),
         );
+        update_storage_bytes(prev_storage, |storage_counters| &mut storage_counters.rated_registry_bytes);
         true
     } else {
         false
@@ -164,10 +168,12 @@ pub fn global_update_rated_token_extra_info(token_id: &AccountId, extra_info: St
         _ => unimplemented!()
     }
     // save back to storage
+    let prev_storage = env::storage_usage();
     env::storage_write(
         RATE_STORAGE_KEY.as_bytes(), 
         &rates.try_to_vec().unwrap(),
     );
+    update_storage_bytes(prev_storage, |storage_counters| &mut storage_counters.rated_registry_bytes);
 }
 
 pub fn global_get_rate(token_id: &AccountId) -> Option<Rate> {
@@ -205,10 +211,12 @@ pub fn global_set_rate(token_id: &AccountId, rate: &Rate) {
     RATES.lock().unwrap().insert(token_id.clone(), rate.clone());
 
     // save back to storage
+    let prev_storage = env::storage_usage();
     env::storage_write(
         RATE_STORAGE_KEY.as_bytes(), 
         &RATES.lock().unwrap().try_to_vec().unwrap(),
     );
+    update_storage_bytes(prev_storage, |storage_counters| &mut storage_counters.rated_registry_bytes);
 }
 
 pub fn is_global_rate_valid(token_id: &AccountId) -> bool {
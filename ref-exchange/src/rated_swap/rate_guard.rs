@@ -0,0 +1,189 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, AccountId, Balance};
+
+use std::collections::HashMap;
+
+use crate::errors::ERR76_INVALID_PARAMS;
+use crate::utils::{nano_to_sec, u128_ratio, FEE_DIVISOR};
+use crate::RATE_GUARD_STORAGE_KEY;
+
+/// Bounds on how far a rated token's rate may move between two accepted updates.
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct RateGuard {
+    /// Max rate increase in bps allowed per interval.
+    pub max_increase_bps: u32,
+    /// Max rate decrease in bps allowed per interval.
+    pub max_decrease_bps: u32,
+    /// Length of one interval in seconds.
+    pub interval_sec: u32,
+    /// Set when an out-of-bounds rate was reported, cleared by owner.
+    pub is_paused: bool,
+    /// The latest rate reported by the rate contract, accepted or not.
+    pub last_reported_rate: Balance,
+    /// Timestamp of the latest reported rate.
+    pub last_reported_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct RateGuardInfo {
+    pub max_increase_bps: u32,
+    pub max_decrease_bps: u32,
+    pub interval_sec: u32,
+    pub is_paused: bool,
+    pub last_reported_rate: U128,
+    pub last_reported_at: U64,
+}
+
+impl From<&RateGuard> for RateGuardInfo {
+    fn from(v: &RateGuard) -> Self {
+        RateGuardInfo {
+            max_increase_bps: v.max_increase_bps,
+            max_decrease_bps: v.max_decrease_bps,
+            interval_sec: v.interval_sec,
+            is_paused: v.is_paused,
+            last_reported_rate: v.last_reported_rate.into(),
+            last_reported_at: v.last_reported_at.into(),
+        }
+    }
+}
+
+impl RateGuard {
+    pub fn new(max_increase_bps: u32, max_decrease_bps: u32, interval_sec: u32) -> Self {
+        Self::assert_bounds(max_increase_bps, max_decrease_bps, interval_sec);
+        Self {
+            max_increase_bps,
+            max_decrease_bps,
+            interval_sec,
+            is_paused: false,
+            last_reported_rate: 0,
+            last_reported_at: 0,
+        }
+    }
+
+    pub fn update_bounds(&mut self, max_increase_bps: u32, max_decrease_bps: u32, interval_sec: u32) {
+        Self::assert_bounds(max_increase_bps, max_decrease_bps, interval_sec);
+        self.max_increase_bps = max_increase_bps;
+        self.max_decrease_bps = max_decrease_bps;
+        self.interval_sec = interval_sec;
+    }
+
+    /// Either bound is capped at 100% per interval, a larger increase bound would disable the guard.
+    fn assert_bounds(max_increase_bps: u32, max_decrease_bps: u32, interval_sec: u32) {
+        assert!(interval_sec > 0, "{}", ERR76_INVALID_PARAMS);
+        assert!(max_increase_bps <= FEE_DIVISOR, "{}", ERR76_INVALID_PARAMS);
+        assert!(max_decrease_bps <= FEE_DIVISOR, "{}", ERR76_INVALID_PARAMS);
+    }
+
+    /// Check new_rate against the previously accepted one.
+    /// The allowed change grows linearly with the number of whole intervals elapsed, at least one.
+    /// A prev rate that has never been updated (prev_ts == 0) is the placeholder one and always passes.
+    pub fn is_within_bounds(&self, prev_rate: Balance, prev_ts: u64, new_rate: Balance, current_ts: u64) -> bool {
+        if prev_ts == 0 || prev_rate == 0 {
+            return true;
+        }
+        let elapsed_sec = nano_to_sec(current_ts.saturating_sub(prev_ts));
+        let intervals = std::cmp::max(1, elapsed_sec / self.interval_sec) as u128;
+        if new_rate >= prev_rate {
+            let max_increase = u128_ratio(prev_rate, self.max_increase_bps as u128 * intervals, FEE_DIVISOR as u128);
+            new_rate - prev_rate <= max_increase
+        } else {
+            let decrease_bps = std::cmp::min(self.max_decrease_bps as u128 * intervals, FEE_DIVISOR as u128);
+            let max_decrease = u128_ratio(prev_rate, decrease_bps, FEE_DIVISOR as u128);
+            prev_rate - new_rate <= max_decrease
+        }
+    }
+}
+
+pub fn read_rate_guards_from_storage() -> HashMap<AccountId, RateGuard> {
+    if let Some(content) = env::storage_read(RATE_GUARD_STORAGE_KEY.as_bytes()) {
+        HashMap::try_from_slice(&content).expect("deserialize failed.")
+    } else {
+        HashMap::new()
+    }
+}
+
+pub fn write_rate_guards_to_storage(rate_guards: HashMap<AccountId, RateGuard>) {
+    env::storage_write(
+        RATE_GUARD_STORAGE_KEY.as_bytes(),
+        &rate_guards.try_to_vec().unwrap(),
+    );
+}
+
+pub fn global_get_rate_guard(token_id: &AccountId) -> Option<RateGuard> {
+    read_rate_guards_from_storage().remove(token_id)
+}
+
+pub fn global_set_rate_guard(token_id: &AccountId, rate_guard: RateGuard) {
+    let mut rate_guards = read_rate_guards_from_storage();
+    rate_guards.insert(token_id.clone(), rate_guard);
+    write_rate_guards_to_storage(rate_guards);
+}
+
+pub fn global_remove_rate_guard(token_id: &AccountId) -> bool {
+    let mut rate_guards = read_rate_guards_from_storage();
+    if rate_guards.remove(token_id).is_some() {
+        write_rate_guards_to_storage(rate_guards);
+        true
+    } else {
+        false
+    }
+}
+
+pub fn is_global_rate_paused(token_id: &AccountId) -> bool {
+    global_get_rate_guard(token_id).map(|g| g.is_paused).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: u128 = 10u128.pow(24);
+    const HOUR: u64 = 3600 * 10u64.pow(9);
+
+    #[test]
+    fn test_rate_guard_bounds() {
+        // 1% up, 0.5% down per hour
+        let guard = RateGuard::new(100, 50, 3600);
+        // never updated
+        assert!(guard.is_within_bounds(ONE, 0, 100 * ONE, HOUR));
+        // within one interval
+        assert!(guard.is_within_bounds(ONE, HOUR, ONE + ONE / 100, HOUR + 1));
+        assert!(!guard.is_within_bounds(ONE, HOUR, ONE + ONE / 100 + 1, HOUR + 1));
+        assert!(guard.is_within_bounds(ONE, HOUR, ONE - ONE / 200, HOUR + 1));
+        assert!(!guard.is_within_bounds(ONE, HOUR, ONE - ONE / 200 - 1, HOUR + 1));
+        // bounds grow with elapsed intervals
+        assert!(guard.is_within_bounds(ONE, HOUR, ONE + ONE * 3 / 100, 4 * HOUR));
+        assert!(!guard.is_within_bounds(ONE, HOUR, ONE + ONE * 4 / 100, 4 * HOUR));
+        assert!(guard.is_within_bounds(ONE, HOUR, ONE - ONE * 15 / 1000, 4 * HOUR));
+    }
+
+    #[test]
+    #[should_panic(expected = "E76: invalid params")]
+    fn test_rate_guard_zero_interval() {
+        RateGuard::new(100, 50, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "E76: invalid params")]
+    fn test_rate_guard_decrease_overflow() {
+        RateGuard::new(100, FEE_DIVISOR + 1, 3600);
+    }
+
+    #[test]
+    #[should_panic(expected = "E76: invalid params")]
+    fn test_rate_guard_increase_overflow() {
+        RateGuard::new(FEE_DIVISOR + 1, 50, 3600);
+    }
+
+    #[test]
+    #[should_panic(expected = "E76: invalid params")]
+    fn test_rate_guard_update_increase_overflow() {
+        let mut guard = RateGuard::new(100, 50, 3600);
+        guard.update_bounds(FEE_DIVISOR + 1, 50, 3600);
+    }
+}
@@ -0,0 +1,161 @@
+//! Signed intents allow a relayer to submit actions on behalf of the account holding deposits.
+//! The account registers an ed25519 public key, then signs the json message of `SignedIntent`
+//! off-chain, anyone can submit the message with the signature to `execute_signed_actions`.
+//!
+//! near-sdk 3.x has no ed25519 host function, so the signature is verified in wasm by ed25519-dalek.
+//! The verification gas is paid by the submitter, who can be compensated by relayer_fee.
+//! A relayer that needs neither relayer_fee nor an off-chain deadline can instead be given
+//! a function call access key of the account restricted to this contract, and call `swap` directly.
+//!
+//! The key is stored per account outside of `Account`, its storage is paid by the near attached to
+//! `register_intent_key` and freed by `storage_unregister`, which drops the key together with its nonce.
+//! A key registered again after unregistering should be a new one.
+
+use std::convert::TryFrom;
+
+use crate::*;
+use ed25519_dalek::{PublicKey, Signature};
+use near_sdk::json_types::{Base58PublicKey, Base64VecU8, U64};
+
+/// The message signed by the account, serialized as json.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignedIntent {
+    /// The account whose deposits are used.
+    pub signer_id: AccountId,
+    /// Must be this exchange, prevents the intent from being replayed on other deployments.
+    pub contract_id: AccountId,
+    pub actions: Vec<Action>,
+    pub referral_id: Option<ValidAccountId>,
+    /// The intent can't be executed after this block timestamp.
+    pub deadline_ns: U64,
+    /// Must be greater than the last used nonce of signer.
+    pub nonce: U64,
+    /// Paid from signer's deposits to the submitter.
+    pub relayer_fee: Option<RelayerFee>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RelayerFee {
+    pub token_id: AccountId,
+    pub amount: U128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct IntentSigner {
+    /// Removed key is kept as None, so that the nonce survives a re-registration.
+    pub public_key: Option<Vec<u8>>,
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct IntentSignerInfo {
+    pub public_key: Option<Base58PublicKey>,
+    pub nonce: U64,
+}
+
+/// Same as lostfound, the LookupMap only needs the prefix.
+pub fn read_intent_signers() -> LookupMap<AccountId, IntentSigner> {
+    LookupMap::new(StorageKey::IntentSigners)
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Register or replace the ed25519 key used to sign intents for the sender's deposits.
+    /// Attached near should cover the extra storage, the left over is refunded.
+    #[payable]
+    pub fn register_intent_key(&mut self, public_key: Base58PublicKey) {
+        self.assert_contract_running();
+        let sender_id = env::predecessor_account_id();
+        self.internal_unwrap_account(&sender_id);
+        // the first byte is curve type, 0 for ed25519.
+        assert!(public_key.0.len() == 33 && public_key.0[0] == 0, "{}", ERR141_INVALID_PUBLIC_KEY);
+        let key_bytes = public_key.0[1..].to_vec();
+        PublicKey::from_bytes(&key_bytes).expect(ERR141_INVALID_PUBLIC_KEY);
+        let prev_storage = env::storage_usage();
+        let mut intent_signers = read_intent_signers();
+        let nonce = intent_signers.get(&sender_id).map(|s| s.nonce).unwrap_or(0);
+        intent_signers.insert(&sender_id, &IntentSigner { public_key: Some(key_bytes), nonce });
+        self.internal_check_storage(prev_storage);
+    }
+
+    /// Disable signed intents of the sender, the used nonce is kept.
+    #[payable]
+    pub fn remove_intent_key(&mut self) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let mut intent_signers = read_intent_signers();
+        let mut intent_signer = intent_signers.get(&sender_id).expect(ERR140_INTENT_KEY_NOT_REGISTERED);
+        intent_signer.public_key = None;
+        intent_signers.insert(&sender_id, &intent_signer);
+    }
+
+    pub fn get_intent_signer(&self, account_id: ValidAccountId) -> Option<IntentSignerInfo> {
+        read_intent_signers().get(account_id.as_ref()).map(|s| IntentSignerInfo {
+            public_key: s.public_key.map(|key| Base58PublicKey([vec![0], key].concat())),
+            nonce: s.nonce.into(),
+        })
+    }
+
+    /// Execute actions signed by intent.signer_id, can be submitted by anyone.
+    /// message: json string of SignedIntent.
+    /// signature: ed25519 signature of message bytes by the registered key of signer.
+    /// If relayer_fee is given, it is paid to the submitter, who should have been registered.
+    pub fn execute_signed_actions(&mut self, message: String, signature: Base64VecU8) -> ActionResult {
+        self.assert_contract_running();
+        let intent: SignedIntent = near_sdk::serde_json::from_str(&message).expect(ERR146_ILLEGAL_INTENT_MSG);
+        assert_eq!(intent.contract_id, env::current_account_id(), "{}", ERR145_WRONG_INTENT_CONTRACT);
+        assert!(env::block_timestamp() <= intent.deadline_ns.0, "{}", ERR143_INTENT_EXPIRED);
+        assert_ne!(intent.actions.len(), 0, "{}", ERR72_AT_LEAST_ONE_SWAP);
+
+        let mut intent_signers = read_intent_signers();
+        let mut intent_signer = intent_signers.get(&intent.signer_id).expect(ERR140_INTENT_KEY_NOT_REGISTERED);
+        let key_bytes = intent_signer.public_key.as_ref().expect(ERR140_INTENT_KEY_NOT_REGISTERED);
+        let public_key = PublicKey::from_bytes(key_bytes).expect(ERR141_INVALID_PUBLIC_KEY);
+        let signature = Signature::try_from(signature.0.as_slice()).expect(ERR142_INVALID_SIGNATURE);
+        assert!(public_key.verify_strict(message.as_bytes(), &signature).is_ok(), "{}", ERR142_INVALID_SIGNATURE);
+        assert!(intent.nonce.0 > intent_signer.nonce, "{}", ERR144_INTENT_NONCE_USED);
+        intent_signer.nonce = intent.nonce.0;
+        intent_signers.insert(&intent.signer_id, &intent_signer);
+
+        let signer_id = intent.signer_id;
+        let relayer_id = env::predecessor_account_id();
+        let mut account = self.internal_unwrap_account(&signer_id);
+        // Same as trade with access key, tokens must be whitelisted or already in the account.
+        for action in &intent.actions {
+            for token in action.tokens() {
+                assert!(
+                    account.get_balance(&token).is_some()
+                        || self.is_whitelisted_token(&token),
+                    "{}",
+                    ERR27_DEPOSIT_NEEDED
+                );
+            }
+        }
+        if let Some(relayer_fee) = intent.relayer_fee {
+            if relayer_fee.amount.0 > 0 && relayer_id != signer_id {
+                account.withdraw(&relayer_fee.token_id, relayer_fee.amount.0);
+                let mut relayer = self.internal_unwrap_account(&relayer_id);
+                relayer.deposit(&relayer_fee.token_id, relayer_fee.amount.0);
+                self.internal_save_account(&relayer_id, relayer);
+            }
+        }
+
+        let referral_info :Option<(AccountId, u32)> = intent.referral_id
+            .as_ref().and_then(|rid| self.referrals.get(rid.as_ref()))
+            .map(|fee| (intent.referral_id.unwrap().into(), fee));
+        let result =
+            self.internal_execute_actions(&mut account, &referral_info, &intent.actions, ActionResult::None, false);
+        self.internal_save_account(&signer_id, account);
+        event::Event::SignedIntentExecuted {
+            signer_id: &signer_id,
+            relayer_id: &relayer_id,
+            nonce: intent.nonce,
+        }
+        .emit();
+        result
+    }
+}
@@ -5,6 +5,8 @@ use near_sdk::{env, AccountId, Balance, Timestamp};
 
 use crate::admin_fee::AdminFees;
 use crate::errors::*;
+use crate::pool_token_manager::StableLikePool;
+use crate::imbalance_penalty::ImbalancePenalty;
 use crate::stable_swap::math::{
     Fees, StableSwap, SwapResult, MAX_AMP, MAX_AMP_CHANGE, MIN_AMP, MIN_RAMP_DURATION,
 };
@@ -204,12 +206,34 @@ impl StableSwapPool {
             .as_u128()
     }
 
+    /// Virtual price of the share, D / shares_total_supply in 1e18 precision.
+    pub fn get_virtual_price(&self) -> u128 {
+        if self.shares_total_supply == 0 {
+            return 10u128.pow(TARGET_DECIMAL as u32);
+        }
+        let d = self.get_invariant()
+            .compute_d(&self.c_amounts)
+            .expect(ERR66_INVARIANT_CALC_ERR);
+        (d * U256::from(10u128.pow(TARGET_DECIMAL as u32)) / U256::from(self.shares_total_supply)).as_u128()
+    }
+
+    /// Fee for liquidity ops which would move pool balances to new_c_amounts,
+    /// scaled up if the op hits the imbalance penalty.
+    fn liquidity_total_fee(&self, new_c_amounts: &Vec<Balance>, imbalance_penalty: Option<&ImbalancePenalty>) -> u32 {
+        if let Some(imbalance_penalty) = imbalance_penalty {
+            imbalance_penalty.apply(self.total_fee, &self.c_amounts, new_c_amounts)
+        } else {
+            self.total_fee
+        }
+    }
+
     /// caculate mint share and related fee for adding liquidity
     /// return (share, fee_part)
     fn calc_add_liquidity(
         &self, 
         amounts: &Vec<Balance>, 
         fees: &AdminFees,
+        imbalance_penalty: Option<&ImbalancePenalty>,
     ) -> (Balance, Balance) {
         let invariant = self.get_invariant();
 
@@ -231,12 +255,13 @@ impl StableSwapPool {
             )
         } else {
             // Subsequent add liquidity will charge fee according to difference with ideal balance portions
+            let new_c_amounts: Vec<Balance> = self.c_amounts.iter().zip(c_amounts.iter()).map(|(x, y)| x + y).collect();
             invariant
                 .compute_lp_amount_for_deposit(
                     &c_amounts,
                     &self.c_amounts,
                     self.shares_total_supply,
-                    &Fees::new(self.total_fee, &fees),
+                    &Fees::new(self.liquidity_total_fee(&new_c_amounts, imbalance_penalty), &fees),
                 )
                 .expect(ERR67_LPSHARE_CALC_ERR)
         }
@@ -252,11 +277,24 @@ impl StableSwapPool {
         min_shares: Balance,
         fees: &AdminFees,
         is_view: bool
+    ) -> Balance {
+        self.add_liquidity_with_penalty(sender_id, amounts, min_shares, fees, None, is_view)
+    }
+
+    /// Add liquidity into the pool, fee scaled up by the imbalance penalty of the pool if any.
+    pub fn add_liquidity_with_penalty(
+        &mut self,
+        sender_id: &AccountId,
+        amounts: &Vec<Balance>,
+        min_shares: Balance,
+        fees: &AdminFees,
+        imbalance_penalty: Option<&ImbalancePenalty>,
+        is_view: bool
     ) -> Balance {
         let n_coins = self.token_account_ids.len();
         assert_eq!(amounts.len(), n_coins, "{}", ERR64_TOKENS_COUNT_ILLEGAL);
 
-        let (new_shares, fee_part) = self.calc_add_liquidity(amounts, fees);
+        let (new_shares, fee_part) = self.calc_add_liquidity(amounts, fees, imbalance_penalty);
         //slippage check on the LP tokens.
         assert!(new_shares >= min_shares, "{}", ERR68_SLIPPAGE);
 
@@ -365,6 +403,19 @@ impl StableSwapPool {
         max_burn_shares: Balance,
         fees: &AdminFees,
         is_view: bool
+    ) -> Balance {
+        self.remove_liquidity_by_tokens_with_penalty(sender_id, amounts, max_burn_shares, fees, None, is_view)
+    }
+
+    /// Remove liquidity from the pool by fixed tokens-out, fee scaled up by the imbalance penalty of the pool if any.
+    pub fn remove_liquidity_by_tokens_with_penalty(
+        &mut self,
+        sender_id: &AccountId,
+        amounts: Vec<Balance>,
+        max_burn_shares: Balance,
+        fees: &AdminFees,
+        imbalance_penalty: Option<&ImbalancePenalty>,
+        is_view: bool
     ) -> Balance {
         let n_coins = self.token_account_ids.len();
         assert_eq!(amounts.len(), n_coins, "{}", ERR64_TOKENS_COUNT_ILLEGAL);
@@ -376,7 +427,8 @@ impl StableSwapPool {
         }
 
         let invariant = self.get_invariant();
-        let trade_fee = Fees::new(self.total_fee, &fees);
+        let new_c_amounts: Vec<Balance> = self.c_amounts.iter().zip(c_amounts.iter()).map(|(x, y)| x.saturating_sub(*y)).collect();
+        let trade_fee = Fees::new(self.liquidity_total_fee(&new_c_amounts, imbalance_penalty), &fees);
 
         let (burn_shares, fee_part) = invariant
             .compute_lp_amount_for_withdraw(
@@ -724,12 +776,50 @@ impl StableSwapPool {
     }
 }
 
+impl StableLikePool for StableSwapPool {
+    const MIN_DECIMAL: u8 = MIN_DECIMAL;
+    const MAX_DECIMAL: u8 = MAX_DECIMAL;
+    const MIN_RESERVE: Balance = MIN_RESERVE;
+
+    fn token_vectors_mut(&mut self) -> (&mut Vec<AccountId>, &mut Vec<u8>, &mut Vec<Balance>, &mut Vec<SwapVolume>) {
+        (&mut self.token_account_ids, &mut self.token_decimals, &mut self.c_amounts, &mut self.volumes)
+    }
+
+    fn get_shares_total_supply(&self) -> Balance {
+        self.shares_total_supply
+    }
+
+    fn get_total_fee(&self) -> u32 {
+        self.total_fee
+    }
+
+    fn to_c_amount(&self, amount: Balance, index: usize) -> Balance {
+        self.amount_to_c_amount(amount, index)
+    }
+
+    fn from_c_amount(&self, c_amount: Balance, index: usize) -> Balance {
+        self.c_amount_to_amount(c_amount, index)
+    }
+
+    fn compute_current_d(&self) -> Balance {
+        self.get_invariant()
+            .compute_d(&self.c_amounts)
+            .expect(ERR66_INVARIANT_CALC_ERR)
+            .as_u128()
+    }
+
+    fn mint_shares_to(&mut self, account_id: &AccountId, shares: Balance) {
+        self.mint_shares(account_id, shares, false);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use near_sdk::test_utils::{accounts, VMContextBuilder};
     use near_sdk::{testing_env, MockedBlockchain};
     use std::convert::TryInto;
 
+    use crate::pool_token_manager::MAX_POOL_TOKENS;
     use super::*;
 
     fn swap(
@@ -1172,4 +1262,58 @@ mod tests {
             .build());
         pool.stop_ramp_amplification();
     }
+
+    #[test]
+    fn test_stable_append_and_remove_token() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        let mut pool = StableSwapPool::new(0, vec![accounts(1), accounts(2)], vec![6, 6], 1000, 25);
+        let mut amounts = vec![100000000000, 100000000000];
+        let shares = pool.add_liquidity(accounts(0).as_ref(), &mut amounts, 1, &AdminFees::zero(), false);
+
+        // seeding shares are charged with the pool fee
+        let new_shares = pool.append_token(accounts(0).as_ref(), accounts(3).as_ref(), 6, 100000000000);
+        assert!(new_shares < shares / 2);
+        assert!(new_shares > shares / 2 * 9970 / 10000);
+        assert_eq!(pool.share_total_balance(), shares + new_shares);
+        assert_eq!(pool.tokens().len(), 3);
+        assert_eq!(pool.token_decimals.len(), 3);
+        assert_eq!(pool.c_amounts.len(), 3);
+        assert_eq!(pool.volumes.len(), 3);
+
+        // dust token can be removed
+        pool.append_token(accounts(0).as_ref(), accounts(4).as_ref(), 6, 1500);
+        assert_eq!(pool.remove_token(accounts(4).as_ref()), 1500);
+        assert_eq!(
+            pool.tokens(),
+            vec![accounts(1).to_string(), accounts(2).to_string(), accounts(3).to_string()]
+        );
+        assert_eq!(pool.token_decimals.len(), 3);
+        assert_eq!(pool.c_amounts.len(), 3);
+        assert_eq!(pool.volumes.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "E96: too many tokens in pool")]
+    fn test_stable_append_token_exceed_max() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        let mut pool = StableSwapPool::new(0, vec![accounts(1), accounts(2)], vec![6, 6], 1000, 0);
+        let mut amounts = vec![100000000000, 100000000000];
+        pool.add_liquidity(accounts(0).as_ref(), &mut amounts, 1, &AdminFees::zero(), false);
+        for i in 2..=MAX_POOL_TOKENS {
+            pool.append_token(accounts(0).as_ref(), &format!("token{}.near", i), 6, 1000000);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "E97: token not drained from pool")]
+    fn test_stable_remove_undrained_token() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(accounts(0)).build());
+        let mut pool = StableSwapPool::new(0, vec![accounts(1), accounts(2), accounts(3)], vec![6, 6, 6], 1000, 0);
+        let mut amounts = vec![100000000000, 100000000000, 100000000000];
+        pool.add_liquidity(accounts(0).as_ref(), &mut amounts, 1, &AdminFees::zero(), false);
+        pool.remove_token(accounts(3).as_ref());
+    }
 }
@@ -67,7 +67,17 @@ impl StorageManagement for Contract {
                 "{}", ERR18_TOKENS_NOT_EMPTY
             );
             self.accounts.remove(&account_id);
-            Promise::new(account_id.clone()).transfer(account_deposit.near_amount);
+            // locks and lockers of the account go with it, so does its intent key
+            read_account_locks().remove(&account_id);
+            read_intent_signers().remove(&account_id);
+            update_storage_counters(|storage_counters| {
+                storage_counters.account_change -= 1;
+            });
+            // storage paid by a sponsor goes back to the sponsor's budget.
+            let refund = self.internal_release_sponsored_storage(&account_id, account_deposit.near_amount);
+            if refund > 0 {
+                Promise::new(account_id.clone()).transfer(refund);
+            }
             true
         } else {
             false
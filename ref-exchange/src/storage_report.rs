@@ -0,0 +1,177 @@
+//! Contract-wide storage report.
+//! Counters are updated where the data is written, so that `get_contract_storage_report` is a few reads.
+//! Data stored before the counters existed is counted in by the owner through `sync_storage_report`.
+//! Accounts and lostfound entries are kept as a net change since the counters started,
+//! so removing one that was stored before them doesn't get lost in a clamp before the owner seeds the legacy total.
+
+use crate::*;
+use crate::account_lostfound::read_lostfound_account_index;
+
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct StorageCounters {
+    /// Accounts registered minus accounts unregistered since the counters started.
+    pub account_change: i64,
+    /// Accounts stored before the counters started, seeded once by the owner.
+    pub legacy_account_count: Option<u64>,
+    /// Pools with id below it have been counted, in pool_counts and in swap volumes.
+    pub counted_pool_count: u64,
+    /// pool kind -> count
+    pub pool_counts: HashMap<String, u64>,
+    pub rated_registry_bytes: u64,
+    pub degen_registry_bytes: u64,
+    pub lostfound_entry_change: i64,
+    pub legacy_lostfound_entry_count: Option<u64>,
+    pub swap_volume_count: u64,
+    pub swap_volume_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct StorageReport {
+    pub storage_usage: U64,
+    pub locked_near: U128,
+    pub account_count: U64,
+    pub pool_count: U64,
+    /// Less than pool_count until sync_storage_report has gone through the pools created before the counters.
+    pub counted_pool_count: U64,
+    pub pool_counts: HashMap<String, U64>,
+    pub rated_registry_bytes: U64,
+    pub degen_registry_bytes: U64,
+    pub lostfound_account_count: U64,
+    pub lostfound_entry_count: U64,
+    pub twap_record_count: U64,
+    pub swap_volume_count: U64,
+    pub swap_volume_bytes: U64,
+    pub pool_limit_count: U64,
+}
+
+pub fn read_storage_counters() -> StorageCounters {
+    if let Some(content) = env::storage_read(STORAGE_REPORT_COUNTERS.as_bytes()) {
+        StorageCounters::try_from_slice(&content).expect("deserialize storage counters failed.")
+    } else {
+        StorageCounters::default()
+    }
+}
+
+pub fn write_storage_counters(storage_counters: &StorageCounters) {
+    env::storage_write(
+        STORAGE_REPORT_COUNTERS.as_bytes(),
+        &storage_counters.try_to_vec().unwrap(),
+    );
+}
+
+pub fn update_storage_counters<F: FnOnce(&mut StorageCounters)>(f: F) {
+    let mut storage_counters = read_storage_counters();
+    f(&mut storage_counters);
+    write_storage_counters(&storage_counters);
+}
+
+/// Add the storage change since prev_storage to the counter picked by f.
+/// Nothing is written if the size didn't change, e.g. rate and degen price updates in swaps.
+pub fn update_storage_bytes<F: FnOnce(&mut StorageCounters) -> &mut u64>(prev_storage: StorageUsage, f: F) {
+    let storage_usage = env::storage_usage();
+    if storage_usage != prev_storage {
+        update_storage_counters(|storage_counters| {
+            let bytes = f(storage_counters);
+            *bytes = (*bytes + storage_usage).saturating_sub(prev_storage);
+        });
+    }
+}
+
+/// Size of the storage record under key, measured by removing and writing it back.
+fn measure_storage_record(key: &[u8]) -> u64 {
+    if let Some(content) = env::storage_read(key) {
+        let prev_storage = env::storage_usage();
+        env::storage_remove(key);
+        let bytes = prev_storage - env::storage_usage();
+        env::storage_write(key, &content);
+        bytes
+    } else {
+        0
+    }
+}
+
+impl StorageCounters {
+    pub fn account_count(&self) -> u64 {
+        (self.legacy_account_count.unwrap_or(0) as i64 + self.account_change).max(0) as u64
+    }
+
+    pub fn lostfound_entry_count(&self) -> u64 {
+        (self.legacy_lostfound_entry_count.unwrap_or(0) as i64 + self.lostfound_entry_change).max(0) as u64
+    }
+}
+
+impl Contract {
+    /// Count the new pool, unless older pools are still waiting for sync_storage_report.
+    pub(crate) fn internal_count_new_pool(&mut self, pool_id: u64, pool_kind: String) {
+        let mut storage_counters = read_storage_counters();
+        if storage_counters.counted_pool_count == pool_id {
+            storage_counters.counted_pool_count += 1;
+            *storage_counters.pool_counts.entry(pool_kind).or_insert(0) += 1;
+            write_storage_counters(&storage_counters);
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Count data stored before the counters existed, can be called repeatedly.
+    /// Each call counts at most pool_limit pools from where the last one stopped, including their swap volumes,
+    /// and re-measures the rated and degen registries.
+    /// Accounts and lostfound entries can't be enumerated on chain,
+    /// so the owner gives how many were stored before the counters started, once for each, E106 after that.
+    #[payable]
+    pub fn sync_storage_report(&mut self, pool_limit: u64, account_count: Option<U64>, lostfound_entry_count: Option<U64>) -> StorageReport {
+        assert_one_yocto();
+        self.assert_owner();
+        let mut storage_counters = read_storage_counters();
+        if let Some(account_count) = account_count {
+            assert!(storage_counters.legacy_account_count.is_none(), "{}", ERR106_STORAGE_REPORT_SEEDED);
+            storage_counters.legacy_account_count = Some(account_count.0);
+        }
+        if let Some(lostfound_entry_count) = lostfound_entry_count {
+            assert!(storage_counters.legacy_lostfound_entry_count.is_none(), "{}", ERR106_STORAGE_REPORT_SEEDED);
+            storage_counters.legacy_lostfound_entry_count = Some(lostfound_entry_count.0);
+        }
+        storage_counters.rated_registry_bytes = measure_storage_record(RATE_STORAGE_KEY.as_bytes());
+        storage_counters.degen_registry_bytes = measure_storage_record(DEGEN_STORAGE_KEY.as_bytes());
+
+        let mut swap_volumes: LookupMap<u32, Vec<SwapVolumeU256>> = LookupMap::new(SWAP_VOLUME_KEY.as_bytes());
+        let end = std::cmp::min(self.pools.len(), storage_counters.counted_pool_count + pool_limit);
+        for pool_id in storage_counters.counted_pool_count..end {
+            let pool_kind = self.pools.get(pool_id).unwrap().kind();
+            *storage_counters.pool_counts.entry(pool_kind).or_insert(0) += 1;
+            let prev_storage = env::storage_usage();
+            if let Some(sv_u256s) = swap_volumes.remove(&(pool_id as u32)) {
+                storage_counters.swap_volume_count += 1;
+                storage_counters.swap_volume_bytes += prev_storage - env::storage_usage();
+                swap_volumes.insert(&(pool_id as u32), &sv_u256s);
+            }
+        }
+        storage_counters.counted_pool_count = end;
+        write_storage_counters(&storage_counters);
+        self.get_contract_storage_report()
+    }
+
+    pub fn get_contract_storage_report(&self) -> StorageReport {
+        let storage_usage = env::storage_usage();
+        let storage_counters = read_storage_counters();
+        StorageReport {
+            storage_usage: U64(storage_usage),
+            locked_near: U128(storage_usage as Balance * env::storage_byte_cost()),
+            account_count: U64(storage_counters.account_count()),
+            pool_count: U64(self.pools.len()),
+            counted_pool_count: U64(storage_counters.counted_pool_count),
+            pool_counts: storage_counters.pool_counts.into_iter().map(|(kind, count)| (kind, U64(count))).collect(),
+            rated_registry_bytes: U64(storage_counters.rated_registry_bytes),
+            degen_registry_bytes: U64(storage_counters.degen_registry_bytes),
+            lostfound_account_count: U64(read_lostfound_account_index().len()),
+            lostfound_entry_count: U64(storage_counters.lostfound_entry_count()),
+            twap_record_count: U64(self.unit_share_cumulative_infos.len()),
+            swap_volume_count: U64(storage_counters.swap_volume_count),
+            swap_volume_bytes: U64(storage_counters.swap_volume_bytes),
+            pool_limit_count: U64(read_pool_limit_from_storage().len()),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Storage sponsorship lets an onboarding service pay the exchange storage of its users.
+//! A sponsor pre-funds a budget, registers accounts from it through `storage_deposit_sponsored`,
+//! and gets the sponsored part back into the budget when such an account unregisters.
+
+use crate::*;
+
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+pub struct StorageSponsor {
+    /// NEAR left for sponsoring new accounts.
+    pub balance: Balance,
+    pub sponsored_count: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SponsoredStorage {
+    pub sponsor_id: AccountId,
+    /// The part of account's near_amount paid by the sponsor.
+    pub amount: Balance,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct StorageSponsorInfo {
+    pub balance: U128,
+    pub sponsored_count: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct SponsoredAccountInfo {
+    pub account_id: AccountId,
+    pub sponsor_id: AccountId,
+    pub amount: U128,
+}
+
+pub fn read_storage_sponsors() -> UnorderedMap<AccountId, StorageSponsor> {
+    if let Some(content) = env::storage_read(STORAGE_SPONSORS.as_bytes()) {
+        UnorderedMap::try_from_slice(&content).expect("deserialize storage sponsors failed.")
+    } else {
+        UnorderedMap::new(StorageKey::StorageSponsors)
+    }
+}
+
+pub fn write_storage_sponsors(storage_sponsors: UnorderedMap<AccountId, StorageSponsor>) {
+    env::storage_write(
+        STORAGE_SPONSORS.as_bytes(),
+        &storage_sponsors.try_to_vec().unwrap(),
+    );
+}
+
+pub fn read_sponsored_accounts() -> UnorderedMap<AccountId, SponsoredStorage> {
+    if let Some(content) = env::storage_read(SPONSORED_ACCOUNTS.as_bytes()) {
+        UnorderedMap::try_from_slice(&content).expect("deserialize sponsored accounts failed.")
+    } else {
+        UnorderedMap::new(StorageKey::SponsoredAccounts)
+    }
+}
+
+pub fn write_sponsored_accounts(sponsored_accounts: UnorderedMap<AccountId, SponsoredStorage>) {
+    env::storage_write(
+        SPONSORED_ACCOUNTS.as_bytes(),
+        &sponsored_accounts.try_to_vec().unwrap(),
+    );
+}
+
+impl Contract {
+    /// Called when account_id unregisters with near_amount,
+    /// returns the amount that goes back to the user, the sponsored part is credited to its sponsor.
+    pub(crate) fn internal_release_sponsored_storage(&mut self, account_id: &AccountId, near_amount: Balance) -> Balance {
+        let mut sponsored_accounts = read_sponsored_accounts();
+        if let Some(sponsored) = sponsored_accounts.get(account_id) {
+            let prev_storage = env::storage_usage();
+            sponsored_accounts.remove(account_id);
+            write_sponsored_accounts(sponsored_accounts);
+            let released = (prev_storage - env::storage_usage()) as Balance * env::storage_byte_cost();
+            let refund = std::cmp::min(sponsored.amount, near_amount);
+            let mut storage_sponsors = read_storage_sponsors();
+            let mut sponsor = storage_sponsors.get(&sponsored.sponsor_id).unwrap_or_default();
+            sponsor.balance += refund + released;
+            sponsor.sponsored_count = sponsor.sponsored_count.saturating_sub(1);
+            storage_sponsors.insert(&sponsored.sponsor_id, &sponsor);
+            write_storage_sponsors(storage_sponsors);
+            log!("Sponsor {} refunded {} from {}", sponsored.sponsor_id, refund + released, account_id);
+            near_amount - refund
+        } else {
+            near_amount
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Fund the storage budget of sponsor_id (default to the sender).
+    /// The storage of the sponsor record itself is taken from the attached deposit.
+    #[payable]
+    pub fn sponsor_storage_deposit(&mut self, sponsor_id: Option<ValidAccountId>) -> StorageSponsorInfo {
+        self.assert_contract_running();
+        let sponsor_id: AccountId = sponsor_id
+            .map(|a| a.into())
+            .unwrap_or_else(|| env::predecessor_account_id());
+        let amount = env::attached_deposit();
+        let prev_storage = env::storage_usage();
+        let mut storage_sponsors = read_storage_sponsors();
+        let mut sponsor = storage_sponsors.get(&sponsor_id).unwrap_or_default();
+        storage_sponsors.insert(&sponsor_id, &sponsor);
+        write_storage_sponsors(storage_sponsors);
+        let storage_cost = (env::storage_usage() - prev_storage) as Balance * env::storage_byte_cost();
+        assert!(amount > storage_cost, "{}", ERR11_INSUFFICIENT_STORAGE);
+        sponsor.balance += amount - storage_cost;
+        let mut storage_sponsors = read_storage_sponsors();
+        storage_sponsors.insert(&sponsor_id, &sponsor);
+        write_storage_sponsors(storage_sponsors);
+        StorageSponsorInfo {
+            balance: U128(sponsor.balance),
+            sponsored_count: sponsor.sponsored_count,
+        }
+    }
+
+    /// Withdraw amount (default to all) from the sender's unused storage budget.
+    /// The sponsor record is removed once it has neither balance nor sponsored accounts.
+    #[payable]
+    pub fn sponsor_storage_withdraw(&mut self, amount: Option<U128>) -> StorageSponsorInfo {
+        assert_one_yocto();
+        self.assert_contract_running();
+        let sponsor_id = env::predecessor_account_id();
+        let mut storage_sponsors = read_storage_sponsors();
+        let mut sponsor = storage_sponsors.get(&sponsor_id).expect(ERR150_SPONSOR_NOT_FOUND);
+        let amount = amount.map(|a| a.0).unwrap_or(sponsor.balance);
+        assert!(amount > 0 && amount <= sponsor.balance, "{}", ERR151_SPONSOR_BALANCE_NOT_ENOUGH);
+        sponsor.balance -= amount;
+        let mut withdraw_amount = amount;
+        if sponsor.balance == 0 && sponsor.sponsored_count == 0 {
+            let prev_storage = env::storage_usage();
+            storage_sponsors.remove(&sponsor_id);
+            write_storage_sponsors(storage_sponsors);
+            withdraw_amount += (prev_storage - env::storage_usage()) as Balance * env::storage_byte_cost();
+        } else {
+            storage_sponsors.insert(&sponsor_id, &sponsor);
+            write_storage_sponsors(storage_sponsors);
+        }
+        Promise::new(sponsor_id).transfer(withdraw_amount);
+        StorageSponsorInfo {
+            balance: U128(sponsor.balance),
+            sponsored_count: sponsor.sponsored_count,
+        }
+    }
+
+    /// Register account_id with min storage paid from the sender's sponsor budget.
+    /// When the account unregisters, the sponsored part goes back to the budget instead of the user.
+    pub fn storage_deposit_sponsored(&mut self, account_id: ValidAccountId) -> StorageBalance {
+        self.assert_contract_running();
+        let sponsor_id = env::predecessor_account_id();
+        let account_id: AccountId = account_id.into();
+        assert!(!self.accounts.contains_key(&account_id), "{}", ERR152_ACC_ALREADY_REGISTERED);
+        let mut storage_sponsors = read_storage_sponsors();
+        let mut sponsor = storage_sponsors.get(&sponsor_id).expect(ERR150_SPONSOR_NOT_FOUND);
+        let min_balance = self.storage_balance_bounds().min.0;
+
+        let prev_storage = env::storage_usage();
+        let mut sponsored_accounts = read_sponsored_accounts();
+        sponsored_accounts.insert(&account_id, &SponsoredStorage {
+            sponsor_id: sponsor_id.clone(),
+            amount: min_balance,
+        });
+        write_sponsored_accounts(sponsored_accounts);
+        let record_cost = (env::storage_usage() - prev_storage) as Balance * env::storage_byte_cost();
+
+        let total_cost = min_balance + record_cost;
+        assert!(sponsor.balance >= total_cost, "{}", ERR151_SPONSOR_BALANCE_NOT_ENOUGH);
+        sponsor.balance -= total_cost;
+        sponsor.sponsored_count += 1;
+        storage_sponsors.insert(&sponsor_id, &sponsor);
+        write_storage_sponsors(storage_sponsors);
+
+        self.internal_register_account(&account_id, min_balance);
+        log!("Sponsor {} registered {}", sponsor_id, account_id);
+        self.storage_balance_of(account_id.try_into().unwrap())
+            .unwrap()
+    }
+
+    pub fn get_storage_sponsor(&self, sponsor_id: ValidAccountId) -> Option<StorageSponsorInfo> {
+        read_storage_sponsors().get(sponsor_id.as_ref()).map(|sponsor| StorageSponsorInfo {
+            balance: U128(sponsor.balance),
+            sponsored_count: sponsor.sponsored_count,
+        })
+    }
+
+    pub fn get_account_sponsor(&self, account_id: ValidAccountId) -> Option<SponsoredAccountInfo> {
+        read_sponsored_accounts().get(account_id.as_ref()).map(|sponsored| SponsoredAccountInfo {
+            account_id: account_id.into(),
+            sponsor_id: sponsored.sponsor_id,
+            amount: U128(sponsored.amount),
+        })
+    }
+
+    pub fn get_sponsored_account_count(&self) -> u64 {
+        read_sponsored_accounts().len()
+    }
+
+    /// Paged view of all sponsored accounts, optionally filtered by sponsor_id.
+    pub fn list_sponsored_accounts(&self, sponsor_id: Option<ValidAccountId>, from_index: Option<u64>, limit: Option<u64>) -> Vec<SponsoredAccountInfo> {
+        let sponsored_accounts = read_sponsored_accounts();
+        let keys = sponsored_accounts.keys_as_vector();
+        let values = sponsored_accounts.values_as_vector();
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(keys.len());
+        (from_index..std::cmp::min(keys.len(), from_index + limit))
+            .map(|idx| (keys.get(idx).unwrap(), values.get(idx).unwrap()))
+            .filter(|(_, sponsored)| sponsor_id.as_ref().map(|s| s.as_ref() == &sponsored.sponsor_id).unwrap_or(true))
+            .map(|(account_id, sponsored)| SponsoredAccountInfo {
+                account_id,
+                sponsor_id: sponsored.sponsor_id,
+                amount: U128(sponsored.amount),
+            })
+            .collect()
+    }
+}
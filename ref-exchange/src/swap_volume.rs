@@ -56,8 +56,22 @@ pub fn internal_get_swap_volume_u256_vec_or_default(
 }
 
 pub fn internal_set_swap_volume_u256_vec(pool_id: u64, sv_u256s: Vec<SwapVolumeU256>) {
+    let prev_storage = env::storage_usage();
     let mut swap_volume_u256_map = LookupMap::new(SWAP_VOLUME_KEY.as_bytes());
-    swap_volume_u256_map.insert(&(pool_id as u32), &sv_u256s);
+    let is_new = swap_volume_u256_map.insert(&(pool_id as u32), &sv_u256s).is_none();
+    let storage_usage = env::storage_usage();
+    // volume updates in swaps keep the size, only new entries and added tokens touch the counters.
+    if storage_usage != prev_storage {
+        update_storage_counters(|storage_counters| {
+            // volumes of pools not counted yet are measured by sync_storage_report.
+            if pool_id < storage_counters.counted_pool_count {
+                if is_new {
+                    storage_counters.swap_volume_count += 1;
+                }
+                storage_counters.swap_volume_bytes = (storage_counters.swap_volume_bytes + storage_usage).saturating_sub(prev_storage);
+            }
+        });
+    }
 }
 
 pub fn internal_update_swap_volume_u256_vec(
@@ -35,12 +35,16 @@ enum TokenReceiverMessage {
         skip_degen_price_sync: Option<bool>,
         /// extra Tgas for ft_on_transfer
         extra_tgas_for_client_echo: Option<u32>,
+        /// If given, the actions fail after this block timestamp.
+        deadline_ns: Option<U64>,
     },
     HotZap {
         referral_id: Option<ValidAccountId>,
         hot_zap_actions: Vec<Action>,
         add_liquidity_infos: Vec<AddLiquidityInfo>,
         skip_degen_price_sync: Option<bool>,
+        /// If given, the hot zap fails after this block timestamp.
+        deadline_ns: Option<U64>,
     },
 }
 
@@ -119,12 +123,15 @@ impl FungibleTokenReceiver for Contract {
                     swap_out_recipient,
                     skip_degen_price_sync,
                     extra_tgas_for_client_echo,
+                    deadline_ns,
                 } => {
-                    let extra_tgas: Gas = extra_tgas_for_client_echo.unwrap_or(DEFAULT_EXTRA_TGAS) as Gas * 1_000_000_000_000 as Gas;
+                    assert_deadline(deadline_ns);
+                    let extra_tgas_for_client_echo = extra_tgas_for_client_echo.unwrap_or(DEFAULT_EXTRA_TGAS);
+                    let extra_tgas: Gas = extra_tgas_for_client_echo as Gas * 1_000_000_000_000 as Gas;
                     assert!(!(swap_out_recipient.is_some() && client_echo.is_some()), "client_echo and swap_out_recipient cannot have value at the same time");
                     assert_ne!(actions.len(), 0, "{}", ERR72_AT_LEAST_ONE_SWAP);
-                    if client_echo.is_some() {
-                        assert_client_echo_valid(&token_in, sender_id.as_ref());
+                    if let Some(ref message) = client_echo {
+                        assert_client_echo_valid(&token_in, sender_id.as_ref(), message, extra_tgas_for_client_echo);
                     }
                     let referral_id = referral_id.map(|x| x.to_string());
                     let out_amounts = self.internal_direct_actions(
@@ -141,7 +148,8 @@ impl FungibleTokenReceiver for Contract {
                         if let Some(ref message) = client_echo {
                             self.internal_send_token_with_msg(sender_id.as_ref(), &token_out, amount_out, message.clone(), extra_tgas);
                         } else {
-                            self.internal_send_tokens(swap_out_recipient.as_ref().unwrap_or(&sender_id).as_ref(), &token_out, amount_out, skip_unwrap_near);
+                            let recipient_id: &AccountId = swap_out_recipient.as_ref().unwrap_or(&sender_id).as_ref();
+                            self.internal_send_tokens(recipient_id, recipient_id, &token_out, amount_out, skip_unwrap_near);
                         }
                     }
                     // Even if send tokens fails, we don't return funds back to sender.
@@ -152,7 +160,9 @@ impl FungibleTokenReceiver for Contract {
                     hot_zap_actions, 
                     add_liquidity_infos,
                     skip_degen_price_sync,
+                    deadline_ns,
                 } => {
+                    assert_deadline(deadline_ns);
                     assert!(hot_zap_actions.len() > 0 && add_liquidity_infos.len() > 0);
                     let sender_id: AccountId = sender_id.into();
                     let mut account = self.internal_unwrap_account(&sender_id);      
@@ -207,11 +217,13 @@ impl FungibleTokenReceiver for Contract {
                             },
                             Pool::StableSwapPool(_) | Pool::RatedSwapPool(_) | Pool::DegenSwapPool(_) => {
                                 let min_shares = add_liquidity_info.min_shares.expect("Need input min_shares");
+                                self.assert_no_deprecated_token_deposit(add_liquidity_info.pool_id, &tokens_in_pool, &add_liquidity_amounts);
                                 pool.add_stable_liquidity(
                                     &sender_id,
                                     &add_liquidity_amounts,
                                     min_shares.into(),
                                     AdminFees::new(self.admin_fee_bps),
+                                    self.internal_get_imbalance_penalty(add_liquidity_info.pool_id),
                                     false
                                 );
                             }
@@ -223,6 +235,7 @@ impl FungibleTokenReceiver for Contract {
                         }
 
                         self.pools.replace(add_liquidity_info.pool_id, &pool);
+                        self.internal_update_virtual_price_record(add_liquidity_info.pool_id, &pool);
                     }
 
                     if env::storage_usage() > prev_storage {
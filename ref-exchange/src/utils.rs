@@ -3,9 +3,9 @@ use std::convert::TryInto;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::LookupMap;
-use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::json_types::{ValidAccountId, U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{ext_contract, AccountId, Balance, Gas, Timestamp};
+use near_sdk::{env, ext_contract, AccountId, Balance, Gas, Timestamp};
 use uint::construct_uint;
 use crate::errors::*;
 
@@ -29,15 +29,24 @@ pub const DEFAULT_EXTRA_TGAS: u32 = 15;
 /// Amount of gas for fungible token transfers, increased to 20T to support AS token contracts.
 pub const GAS_FOR_FT_TRANSFER: Gas = 20_000_000_000_000;
 pub const GAS_FOR_NEAR_WITHDRAW: Gas = 20_000_000_000_000;
+pub const GAS_FOR_NEAR_DEPOSIT: Gas = 10_000_000_000_000;
+/// Least gas left for the callback of near_deposit_and_execute, which credits the wNEAR and runs the actions.
+pub const MIN_GAS_FOR_NEAR_DEPOSIT_CALLBACK: Gas = 50_000_000_000_000;
 
 /// Call back for Near transfer needs extra gas
 pub const GAS_FOR_CB_NEAR_TRANSFER: Gas = 10_000_000_000_000;
 /// Possible user lostfound account operation won't cost beyond 5Tgas
 pub const GAS_FOR_CB_FT_TRANSFER: Gas = 5_000_000_000_000;
 
+/// Default max amount of wNEAR unwrapped for storage in a single top-up.
+pub const DEFAULT_STORAGE_TOPUP_CAP: Balance = ONE_NEAR / 10;
+
 /// Fee divisor, allowing to provide fee in bps.
 pub const FEE_DIVISOR: u32 = 10_000;
 pub const MAX_ADMIN_FEE_BPS: u32 = 8_000;
+/// Price impact is measured by the return of a probe swap of amount_in / PRICE_IMPACT_PROBE_DIVISOR,
+/// on the pool before and after the swap.
+pub const PRICE_IMPACT_PROBE_DIVISOR: u128 = 1_000;
 
 /// Initial shares supply on deposit of liquidity.
 pub const INIT_SHARES_SUPPLY: u128 = 1_000_000_000_000_000_000_000_000;
@@ -75,6 +84,7 @@ impl Default for SwapVolume {
 #[ext_contract(ext_wrap_near)]
 pub trait WrapNear {
     fn near_withdraw(&mut self, amount: U128);
+    fn near_deposit(&mut self);
 }
 
 #[ext_contract(ext_self)]
@@ -83,6 +93,7 @@ pub trait RefExchange {
         &mut self,
         sender_id: AccountId,
         amount: U128,
+        receiver_id: Option<AccountId>,
     ) -> U128 ;
     fn exchange_callback_post_withdraw(
         &mut self,
@@ -90,6 +101,33 @@ pub trait RefExchange {
         sender_id: AccountId,
         amount: U128,
     );
+    fn exchange_callback_post_withdraw_batch(
+        &mut self,
+        token_count: u64,
+    ) -> Vec<U128>;
+    fn exchange_callback_post_near_deposit(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        actions: Vec<crate::action::Action>,
+        referral_id: Option<ValidAccountId>,
+        skip_degen_price_sync: Option<bool>,
+        deadline_ns: Option<U64>,
+    );
+    fn exchange_callback_execute_actions(
+        &mut self,
+        sender_id: AccountId,
+        actions: Vec<crate::action::Action>,
+        referral_id: Option<ValidAccountId>,
+        skip_degen_price_sync: Option<bool>,
+        deadline_ns: Option<U64>,
+    ) -> crate::action::ActionResult;
+    fn exchange_callback_post_storage_topup(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        credit_account: bool,
+    ) -> U128;
     fn callback_on_shadow(
         &mut self,
         action: crate::account_deposit::ShadowActions,
@@ -165,6 +203,13 @@ impl From<TokenCache> for HashMap<AccountId, U128> {
     }
 }
 
+/// Panics if current block is later than the given deadline.
+pub fn assert_deadline(deadline_ns: Option<U64>) {
+    if let Some(deadline_ns) = deadline_ns {
+        assert!(env::block_timestamp() <= deadline_ns.0, "{}", ERR79_DEADLINE_EXCEEDED);
+    }
+}
+
 pub fn nano_to_sec(nano: u64) -> u32 {
     (nano / 10u64.pow(9)) as u32
 }
@@ -7,6 +7,7 @@ use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{near_bindgen, AccountId};
 use crate::utils::TokenCache;
 use crate::rated_swap::rate::Rate;
+use crate::rated_swap::rate_guard::{read_rate_guards_from_storage, RateGuardInfo};
 use crate::*;
 
 #[derive(Serialize)]
@@ -42,7 +43,8 @@ pub struct RatedTokenInfo {
     pub rate_price: U128,
     pub last_update_ts: U64,
     pub is_valid: bool,
-    pub extra_info: Option<String>
+    pub extra_info: Option<String>,
+    pub rate_guard: Option<RateGuardInfo>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -95,6 +97,17 @@ pub struct PoolInfo {
     pub amp: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct MigrateLiquidityPrediction {
+    /// Token amounts removed from the source pool, in its tokens sequence.
+    pub remove_amounts: Vec<U128>,
+    /// Token amounts added into the target pool, in its tokens sequence.
+    pub need_amounts: Vec<U128>,
+    pub mint_shares: U128,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
@@ -277,7 +290,6 @@ impl From<Pool> for RatedPoolInfo {
                 token_account_ids: pool.token_account_ids,
                 total_fee: pool.total_fee,
                 shares_total_supply: U128(pool.shares_total_supply),
-                
             },
             Pool::DegenSwapPool(_) => unimplemented!(),
         }
@@ -301,6 +313,12 @@ pub struct DegenPoolInfo {
     pub shares_total_supply: U128,
     pub amp: u64,
     pub degens: Vec<U128>,
+    /// Amp factor when the current ramp started.
+    pub init_amp_factor: u64,
+    /// Amp factor the current ramp heads to.
+    pub target_amp_factor: u64,
+    pub init_amp_time: U64,
+    pub stop_amp_time: U64,
 }
 
 impl From<Pool> for DegenPoolInfo {
@@ -318,7 +336,10 @@ impl From<Pool> for DegenPoolInfo {
                 token_account_ids: pool.token_account_ids,
                 total_fee: pool.total_fee,
                 shares_total_supply: U128(pool.shares_total_supply),
-                
+                init_amp_factor: pool.init_amp_factor as u64,
+                target_amp_factor: pool.target_amp_factor as u64,
+                init_amp_time: U64(pool.init_amp_time),
+                stop_amp_time: U64(pool.stop_amp_time),
             },
         }
     }
@@ -569,6 +590,7 @@ impl Contract {
     }
 
     /// Given specific pool, returns amount of token_out recevied swapping amount_in of token_in.
+    /// No price impact limit is applied here, use `predict_swap_actions` to quote with `max_price_impact_bps`.
     pub fn get_return(
         &self,
         pool_id: u64,
@@ -581,6 +603,7 @@ impl Contract {
     }
 
     /// Given a specific pool, returns the amount of token_in required to receive amount_out of token_out.
+    /// No price impact limit is applied here, use `predict_swap_actions` to quote with `max_price_impact_bps`.
     pub fn get_return_by_output(
         &self,
         pool_id: u64,
@@ -662,7 +685,7 @@ impl Contract {
     ) -> U128 {
         let mut pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
         let amounts = amounts.iter().map(|v| v.0).collect();
-        pool.add_stable_liquidity(&String::from("@view"), &amounts, 0, AdminFees::new(self.admin_fee_bps), true).into()
+        pool.add_stable_liquidity(&String::from("@view"), &amounts, 0, AdminFees::new(self.admin_fee_bps), self.internal_get_imbalance_penalty(pool_id), true).into()
     }
 
     pub fn predict_remove_liquidity(
@@ -674,6 +697,30 @@ impl Contract {
         pool.remove_liquidity(&String::from("@view"), shares.into(), vec![0; pool.tokens().len()], true).into_iter().map(|x| U128(x)).collect()
     }
 
+    pub fn predict_migrate_liquidity(
+        &self,
+        from_pool_id: u64,
+        shares: U128,
+        to_pool_id: u64,
+    ) -> MigrateLiquidityPrediction {
+        let mut from_pool = self.pools.get(from_pool_id).expect(ERR85_NO_POOL);
+        let mut to_pool = self.pools.get(to_pool_id).expect(ERR85_NO_POOL);
+        let (remove_amounts, need_amounts, mint_shares) = self.internal_migrate_liquidity(
+            &String::from("@view"),
+            &mut from_pool,
+            shares.into(),
+            to_pool_id,
+            &mut to_pool,
+            0,
+            true,
+        );
+        MigrateLiquidityPrediction {
+            remove_amounts: remove_amounts.into_iter().map(|x| U128(x)).collect(),
+            need_amounts: need_amounts.into_iter().map(|x| U128(x)).collect(),
+            mint_shares: U128(mint_shares),
+        }
+    }
+
     pub fn predict_remove_liquidity_by_tokens(
         &self,
         pool_id: u64,
@@ -681,7 +728,7 @@ impl Contract {
     ) -> U128 {
         let mut pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
         let amounts = amounts.iter().map(|v| v.0).collect();
-        pool.remove_liquidity_by_tokens(&String::from("@view"), amounts, u128::MAX, AdminFees::new(self.admin_fee_bps), true).into()
+        pool.remove_liquidity_by_tokens(&String::from("@view"), amounts, u128::MAX, AdminFees::new(self.admin_fee_bps), self.internal_get_imbalance_penalty(pool_id), true).into()
     }
 
     pub fn list_rated_tokens(&self) -> HashMap<String, RatedTokenInfo> {
@@ -691,9 +738,11 @@ impl Contract {
         } else {
             HashMap::new()
         };
+        let rate_guards = read_rate_guards_from_storage();
         rates
         .iter()
         .map(|(k, v)| {
+            let rate_guard = rate_guards.get(k).map(|g| g.into());
             match v {
                 Rate::Sfrax(r) => (k.clone(), 
                     RatedTokenInfo {
@@ -701,7 +750,8 @@ impl Contract {
                         rate_price: v.get().into(),
                         last_update_ts: v.last_update_ts().into(),
                         is_valid: v.are_actual(),
-                        extra_info: Some(near_sdk::serde_json::to_string(&r.extra_info).unwrap())
+                        extra_info: Some(near_sdk::serde_json::to_string(&r.extra_info).unwrap()),
+                        rate_guard,
                     }),
                 _ => (k.clone(), 
                     RatedTokenInfo {
@@ -709,7 +759,8 @@ impl Contract {
                         rate_price: v.get().into(),
                         last_update_ts: v.last_update_ts().into(),
                         is_valid: v.are_actual(),
-                        extra_info: None
+                        extra_info: None,
+                        rate_guard,
                     })
             }
             
@@ -963,6 +1014,7 @@ impl Contract {
                         &add_liquidity_amounts,
                         0,
                         AdminFees::new(self.admin_fee_bps),
+                        self.internal_get_imbalance_penalty(add_liquidity_info.pool_id),
                         true
                     );
                     shares
@@ -1008,6 +1060,23 @@ impl Contract {
             .collect()
     }
 
+    pub fn get_imbalance_penalty(&self, pool_id: u64) -> Option<ImbalancePenalty> {
+        read_imbalance_penalty_from_storage().get(&pool_id)
+    }
+
+    pub fn get_imbalance_penalty_paged(&self, from_index: Option<u64>, limit: Option<u64>) -> HashMap<u64, ImbalancePenalty> {
+        let imbalance_penalty = read_imbalance_penalty_from_storage();
+        let keys = imbalance_penalty.keys_as_vector();
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(keys.len() as u64);
+        (from_index..std::cmp::min(keys.len() as u64, from_index + limit))
+            .map(|idx| {
+                let key = keys.get(idx).unwrap();
+                (key.clone(), imbalance_penalty.get(&key).unwrap())
+            })
+            .collect()
+    }
+
     pub fn batch_views(
         &self,
         account_id: Option<ValidAccountId>,
@@ -0,0 +1,188 @@
+use crate::*;
+use crate::utils::{nano_to_sec, u128_dec_format};
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct VirtualPriceRecord {
+    pub time_sec: u32,
+    #[serde(with = "u128_dec_format")]
+    pub virtual_price: u128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct VirtualPriceInfo {
+    pub record_interval_sec: u32,
+    pub records: Vec<VirtualPriceRecord>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VVirtualPriceInfo {
+    Current(VirtualPriceInfo),
+}
+
+impl From<VVirtualPriceInfo> for VirtualPriceInfo {
+    fn from(v: VVirtualPriceInfo) -> Self {
+        match v {
+            VVirtualPriceInfo::Current(c) => c,
+        }
+    }
+}
+
+impl From<VirtualPriceInfo> for VVirtualPriceInfo {
+    fn from(c: VirtualPriceInfo) -> Self {
+        VVirtualPriceInfo::Current(c)
+    }
+}
+
+impl VirtualPriceInfo {
+    pub fn new(current_time_sec: u32, virtual_price: u128, record_interval_sec: u32) -> Self {
+        Self {
+            record_interval_sec,
+            records: vec![VirtualPriceRecord {
+                time_sec: current_time_sec,
+                virtual_price,
+            }],
+        }
+    }
+
+    /// Append a record if at least record_interval_sec passed since the last one.
+    /// Return true if a new record appended.
+    pub fn update(&mut self, current_time_sec: u32, virtual_price: u128) -> bool {
+        let last_record = &self.records[self.records.len() - 1];
+        if current_time_sec - last_record.time_sec >= self.record_interval_sec {
+            self.records.push(VirtualPriceRecord {
+                time_sec: current_time_sec,
+                virtual_price,
+            });
+            if self.records.len() > RECORD_COUNT_LIMIT {
+                self.records.remove(0);
+            }
+            true
+        } else {
+            false
+        }
+    }
+}
+
+pub fn read_virtual_price_infos_from_storage() -> UnorderedMap<u64, VVirtualPriceInfo> {
+    if let Some(content) = env::storage_read(VIRTUAL_PRICE_KEY.as_bytes()) {
+        UnorderedMap::try_from_slice(&content).expect("deserialize virtual price info failed.")
+    } else {
+        UnorderedMap::new(StorageKey::VirtualPriceInfo)
+    }
+}
+
+pub fn write_virtual_price_infos_to_storage(virtual_price_infos: UnorderedMap<u64, VVirtualPriceInfo>) {
+    env::storage_write(
+        VIRTUAL_PRICE_KEY.as_bytes(),
+        &virtual_price_infos.try_to_vec().unwrap(),
+    );
+}
+
+/// Virtual price of the pool, None if it's not a stable swap pool.
+fn pool_virtual_price(pool: &Pool) -> Option<u128> {
+    match pool {
+        Pool::StableSwapPool(p) => Some(p.get_virtual_price()),
+        _ => None,
+    }
+}
+
+impl Contract {
+    /// Called after each pool state change, records the virtual price if the pool is registered and interval reached.
+    pub fn internal_update_virtual_price_record(&mut self, pool_id: u64, pool: &Pool) {
+        let mut virtual_price_infos = read_virtual_price_infos_from_storage();
+        if let (Some(v), Some(virtual_price)) = (virtual_price_infos.get(&pool_id), pool_virtual_price(pool)) {
+            let mut virtual_price_info: VirtualPriceInfo = v.into();
+            if virtual_price_info.update(nano_to_sec(env::block_timestamp()), virtual_price) {
+                virtual_price_infos.insert(&pool_id, &virtual_price_info.into());
+                write_virtual_price_infos_to_storage(virtual_price_infos);
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Start recording virtual price of the given stable pool every record_interval_sec.
+    #[payable]
+    pub fn register_virtual_price_record(&mut self, pool_id: u64, record_interval_sec: u32) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        let pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
+        let virtual_price = pool_virtual_price(&pool).expect(ERR88_NOT_STABLE_POOL);
+        let mut virtual_price_infos = read_virtual_price_infos_from_storage();
+        assert!(virtual_price_infos.get(&pool_id).is_none(), "Already register");
+        let virtual_price_info = VirtualPriceInfo::new(nano_to_sec(env::block_timestamp()), virtual_price, record_interval_sec);
+        virtual_price_infos.insert(&pool_id, &virtual_price_info.into());
+        write_virtual_price_infos_to_storage(virtual_price_infos);
+    }
+
+    #[payable]
+    pub fn modify_virtual_price_record_interval_sec(&mut self, pool_id: u64, record_interval_sec: u32) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        let mut virtual_price_infos = read_virtual_price_infos_from_storage();
+        let mut virtual_price_info: VirtualPriceInfo = virtual_price_infos.get(&pool_id).expect(ERR85_NO_POOL).into();
+        virtual_price_info.record_interval_sec = record_interval_sec;
+        virtual_price_infos.insert(&pool_id, &virtual_price_info.into());
+        write_virtual_price_infos_to_storage(virtual_price_infos);
+    }
+
+    #[payable]
+    pub fn unregister_virtual_price_record(&mut self, pool_id: u64) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        let mut virtual_price_infos = read_virtual_price_infos_from_storage();
+        virtual_price_infos.remove(&pool_id).expect(ERR85_NO_POOL);
+        write_virtual_price_infos_to_storage(virtual_price_infos);
+    }
+
+    /// Owner or guardians can trigger a record in case the pool is quiet.
+    #[payable]
+    pub fn sync_virtual_price_record(&mut self, pool_id: u64) {
+        assert_one_yocto();
+        assert!(self.is_owner_or_guardians(), "{}", ERR100_NOT_ALLOWED);
+        let pool = self.pools.get(pool_id).expect(ERR85_NO_POOL);
+        self.internal_update_virtual_price_record(pool_id, &pool);
+    }
+
+    pub fn get_virtual_price_info(&self, pool_id: u64) -> Option<VirtualPriceInfo> {
+        read_virtual_price_infos_from_storage().get(&pool_id).map(|v| v.into())
+    }
+
+    pub fn list_virtual_price_infos(&self, from_index: Option<u64>, limit: Option<u64>) -> HashMap<u64, VirtualPriceInfo> {
+        let virtual_price_infos = read_virtual_price_infos_from_storage();
+        let keys = virtual_price_infos.keys_as_vector();
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(keys.len());
+        (from_index..std::cmp::min(from_index + limit, keys.len()))
+            .map(|index| {
+                let key = keys.get(index).unwrap();
+                (key, virtual_price_infos.get(&key).unwrap().into())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_price_records() {
+        let mut info = VirtualPriceInfo::new(1000, 100, 600);
+        assert!(!info.update(1599, 101));
+        assert_eq!(info.records.len(), 1);
+        assert!(info.update(1600, 102));
+        assert_eq!(info.records.len(), 2);
+        assert_eq!(info.records[1].virtual_price, 102);
+        for i in 1..=RECORD_COUNT_LIMIT as u32 {
+            assert!(info.update(1600 + i * 600, 102 + i as u128));
+        }
+        assert_eq!(info.records.len(), RECORD_COUNT_LIMIT);
+        assert_eq!(info.records[0].time_sec, 1600 + 600);
+    }
+}
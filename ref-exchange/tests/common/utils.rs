@@ -871,7 +871,22 @@ pub fn setup_degen_pool(
     ContractAccount<Exchange>,
     Vec<ContractAccount<TestToken>>,
 ) {
-    let root = init_simulator(None);
+    setup_degen_pool_with_root(init_simulator(None), tokens, amounts, decimals, pool_fee, amp)
+}
+
+pub fn setup_degen_pool_with_root(
+    root: UserAccount,
+    tokens: Vec<String>,
+    amounts: Vec<u128>,
+    decimals: Vec<u8>,
+    pool_fee: u32,
+    amp: u64,
+) -> (
+    UserAccount,
+    UserAccount,
+    ContractAccount<Exchange>,
+    Vec<ContractAccount<TestToken>>,
+) {
     let owner = root.create_user("owner".to_string(), to_yocto("100"));
     let pool = deploy!(
         contract: Exchange,
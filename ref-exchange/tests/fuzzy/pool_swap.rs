@@ -25,9 +25,11 @@ pub fn swap_action(pool :&ContractAccount<Exchange>, operator: &Operator, token_
                 token_in: token_in,
                 amount_in: Some(U128(amount_in)),
                 token_out: token_out,
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -224,9 +226,11 @@ pub fn do_stable_pool_swap(token_contracts: &Vec<ContractAccount<TestToken>>, rn
                 token_in: token_in.clone(),
                 amount_in: Some(U128(amount_in)),
                 token_out: token_out.clone(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -0,0 +1,242 @@
+use near_sdk::json_types::U128;
+use near_sdk_sim::{call, view, to_yocto};
+
+use ref_exchange::{AccountLocksInfo, SwapAction, MAX_LOCK_DURATION_SEC};
+use crate::common::utils::*;
+pub mod common;
+
+#[test]
+fn sim_account_lock() {
+    let (root, _owner, pool, _token1, _token2, _token3) = setup_pool_with_liquidity();
+    let locker = root.create_user("locker".to_string(), to_yocto("100"));
+    let other = root.create_user("other".to_string(), to_yocto("100"));
+    assert_eq!(get_deposits(&pool, root.valid_account_id()).get(&dai()).unwrap().0, to_yocto("85"));
+
+    // not authorized yet
+    let out_come = call!(
+        locker,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("80")), 3600),
+        deposit = to_yocto("0.01")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E160: locker not authorized"));
+
+    call!(
+        root,
+        pool.authorize_locker(locker.valid_account_id()),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+
+    let out_come = call!(
+        other,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("80")), 3600),
+        deposit = to_yocto("0.01")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E160: locker not authorized"));
+
+    let out_come = call!(
+        locker,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("86")), 3600),
+        deposit = to_yocto("0.01")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E161: not enough unlocked deposit"));
+
+    call!(
+        locker,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("80")), 3600),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+    let locks = view!(pool.get_account_locks(root.valid_account_id())).unwrap_json::<Option<AccountLocksInfo>>().unwrap();
+    assert_eq!(locks.authorized_lockers, vec![locker.account_id.clone()]);
+    assert_eq!(locks.locks.len(), 1);
+    assert_eq!(locks.locks[0].token_id, dai());
+    assert_eq!(locks.locks[0].amount.0, to_yocto("80"));
+    let available = view!(pool.get_available_deposits(root.valid_account_id())).unwrap_json::<std::collections::HashMap<String, U128>>();
+    assert_eq!(available.get(&dai()).unwrap().0, to_yocto("5"));
+
+    // withdraw and swap can only use the unlocked part
+    let out_come = call!(
+        root,
+        pool.withdraw(to_va(dai()), U128(to_yocto("10")), None, None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E161: not enough unlocked deposit"));
+    let out_come = call!(
+        root,
+        pool.swap(
+            vec![SwapAction {
+                pool_id: 0,
+                token_in: dai(),
+                amount_in: Some(U128(to_yocto("10"))),
+                token_out: eth(),
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
+            }],
+            None,
+            None,
+            None
+        ),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E161: not enough unlocked deposit"));
+    call!(
+        root,
+        pool.withdraw(to_va(dai()), U128(to_yocto("5")), None, None),
+        deposit = 1
+    )
+    .assert_success();
+
+    // revoked locker can still release its own lock
+    call!(
+        root,
+        pool.revoke_locker(locker.valid_account_id()),
+        deposit = 1
+    )
+    .assert_success();
+    let out_come = call!(
+        locker,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(1), 3600),
+        deposit = to_yocto("0.01")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E160: locker not authorized"));
+    let out_come = call!(
+        other,
+        pool.unlock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("80"))),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E162: not enough locked deposit"));
+    call!(
+        locker,
+        pool.unlock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("80"))),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(view!(pool.get_account_locks(root.valid_account_id())).unwrap_json::<Option<AccountLocksInfo>>().is_none());
+    assert_eq!(view!(pool.get_locked_deposit(root.valid_account_id(), to_va(dai()))).unwrap_json::<U128>().0, 0);
+
+    call!(
+        root,
+        pool.withdraw(to_va(dai()), U128(to_yocto("10")), None, None),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(get_deposits(&pool, root.valid_account_id()).get(&dai()).unwrap().0, to_yocto("70"));
+}
+
+#[test]
+fn sim_account_lock_expire_and_release() {
+    let (root, owner, pool, _token1, _token2, _token3) = setup_pool_with_liquidity();
+    let locker = root.create_user("locker".to_string(), to_yocto("100"));
+    call!(
+        root,
+        pool.authorize_locker(locker.valid_account_id()),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+
+    let out_come = call!(
+        locker,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("80")), 0),
+        deposit = to_yocto("0.01")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E163: invalid lock duration"));
+    let out_come = call!(
+        locker,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("80")), MAX_LOCK_DURATION_SEC + 1),
+        deposit = to_yocto("0.01")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E163: invalid lock duration"));
+
+    // an expired lock no longer holds the deposit
+    call!(
+        locker,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("80")), 10),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+    let out_come = call!(
+        root,
+        pool.withdraw(to_va(dai()), U128(to_yocto("10")), None, None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E161: not enough unlocked deposit"));
+    assert!(root.borrow_runtime_mut().produce_blocks(20).is_ok());
+    assert_eq!(view!(pool.get_locked_deposit(root.valid_account_id(), to_va(dai()))).unwrap_json::<U128>().0, 0);
+    let locks = view!(pool.get_account_locks(root.valid_account_id())).unwrap_json::<Option<AccountLocksInfo>>().unwrap();
+    assert!(locks.locks.is_empty());
+    call!(
+        root,
+        pool.withdraw(to_va(dai()), U128(to_yocto("10")), None, None),
+        deposit = 1
+    )
+    .assert_success();
+
+    // a revoked locker's lock stays until released by owner or guardians
+    call!(
+        locker,
+        pool.lock_deposit(root.valid_account_id(), to_va(dai()), U128(to_yocto("50")), 3600),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+    call!(
+        root,
+        pool.revoke_locker(locker.valid_account_id()),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(view!(pool.get_locked_deposit(root.valid_account_id(), to_va(dai()))).unwrap_json::<U128>().0, to_yocto("50"));
+    let out_come = call!(
+        locker,
+        pool.release_deposit_locks(root.valid_account_id()),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E100: no permission to invoke this"));
+    call!(
+        owner,
+        pool.release_deposit_locks(root.valid_account_id()),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(view!(pool.get_account_locks(root.valid_account_id())).unwrap_json::<Option<AccountLocksInfo>>().is_none());
+    call!(
+        root,
+        pool.withdraw(to_va(dai()), U128(to_yocto("70")), None, None),
+        deposit = 1
+    )
+    .assert_success();
+
+    // unregistering the account removes its lockers
+    let new_user = root.create_user("new_user".to_string(), to_yocto("100"));
+    call!(
+        new_user,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+    call!(
+        new_user,
+        pool.authorize_locker(locker.valid_account_id()),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+    assert!(view!(pool.get_account_locks(new_user.valid_account_id())).unwrap_json::<Option<AccountLocksInfo>>().is_some());
+    call!(
+        new_user,
+        pool.storage_unregister(None),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(view!(pool.get_account_locks(new_user.valid_account_id())).unwrap_json::<Option<AccountLocksInfo>>().is_none());
+}
@@ -60,9 +60,11 @@ fn modify_admin_fee() {
                 token_in: dai(),
                 amount_in: Some(U128(to_yocto("1"))),
                 token_out: eth(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -109,9 +111,11 @@ fn modify_admin_fee() {
                 token_in: usdt(),
                 amount_in: Some(U128(to_yocto("1"))),
                 token_out: eth(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -221,9 +225,11 @@ fn referral_fee() {
                 token_in: usdt(),
                 amount_in: Some(U128(to_yocto("1"))),
                 token_out: eth(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             Some(referral1.valid_account_id()),
+            None,
             None
         ),
         deposit = 1
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use near_sdk_sim::{deploy, call, view, to_yocto, ContractAccount, UserAccount};
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+
+use mock_boost_farming::{ContractContract as MockBoostFarming, FarmTerms, FarmerSeed};
+
+use crate::common::utils::*;
+pub mod common;
+
+near_sdk_sim::lazy_static_include::lazy_static_include_bytes! {
+    MOCK_BOOST_FARMING_WASM_BYTES => "../res/mock_boost_farming.wasm",
+}
+
+pub fn boost_farming() -> AccountId {
+    "boost_farming".to_string()
+}
+
+fn setup_boost_farming(root: &UserAccount) -> ContractAccount<MockBoostFarming> {
+    deploy!(
+        contract: MockBoostFarming,
+        contract_id: boost_farming(),
+        bytes: &MOCK_BOOST_FARMING_WASM_BYTES,
+        signer_account: root,
+        init_method: new(root.account_id())
+    )
+}
+
+fn get_farmer_seed(farming: &ContractAccount<MockBoostFarming>, farmer: &UserAccount, seed_id: &str) -> Option<FarmerSeed> {
+    view!(farming.get_farmer_seed(farmer.account_id(), seed_id.to_string())).unwrap_json::<Option<FarmerSeed>>()
+}
+
+fn list_lostfound(farming: &ContractAccount<MockBoostFarming>) -> HashMap<String, U128> {
+    view!(farming.list_lostfound(None, None)).unwrap_json::<HashMap<String, U128>>()
+}
+
+#[test]
+fn sim_boost_farming_mft_seed() {
+    let (root, _owner, pool, _token1, _token2, _token3) = setup_pool_with_liquidity();
+    let farmer = root.create_user("farmer".to_string(), to_yocto("100"));
+    let farming = setup_boost_farming(&root);
+    let seed_id = format!("{}@0", swap());
+
+    call!(
+        root,
+        pool.mft_register(":0".to_string(), farming.valid_account_id()),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        root,
+        pool.mft_register(":0".to_string(), farmer.valid_account_id()),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        farmer,
+        farming.storage_deposit(None, None),
+        deposit = to_yocto("0.1")
+    )
+    .assert_success();
+    call!(
+        root,
+        farming.create_seed(seed_id.clone(), 24, Some(U128(0)), Some(0)),
+        deposit = 1
+    )
+    .assert_success();
+
+    let shares = mft_balance_of(&pool, ":0", &root.account_id()) / 2;
+    call!(
+        root,
+        pool.mft_transfer(":0".to_string(), farmer.valid_account_id(), U128(shares), None),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        farmer,
+        pool.mft_transfer_call(":0".to_string(), farming.valid_account_id(), U128(shares), None, "\"Free\"".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(get_farmer_seed(&farming, &farmer, &seed_id).unwrap().free_amount, shares);
+    assert_eq!(mft_balance_of(&pool, ":0", &farmer.account_id()), 0);
+
+    // withdraw part of the seed
+    call!(
+        farmer,
+        farming.unlock_and_withdraw_seed(seed_id.clone(), U128(0), U128(shares / 2)),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(mft_balance_of(&pool, ":0", &farmer.account_id()), shares / 2);
+    assert_eq!(get_farmer_seed(&farming, &farmer, &seed_id).unwrap().free_amount, shares - shares / 2);
+
+    // receiver not registered in the pool, the seed goes to lostfound
+    call!(
+        farmer,
+        pool.mft_transfer(":0".to_string(), root.valid_account_id(), U128(shares / 2), None),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        farmer,
+        pool.mft_unregister(":0".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    let out_come = call!(
+        farmer,
+        farming.unlock_and_withdraw_seed(seed_id.clone(), U128(0), U128(shares - shares / 2)),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_farmer_seed(&farming, &farmer, &seed_id).is_none());
+    assert_eq!(list_lostfound(&farming).get(&seed_id).unwrap().0, shares - shares / 2);
+
+    // owner returns it once the farmer registered again
+    call!(
+        root,
+        pool.mft_register(":0".to_string(), farmer.valid_account_id()),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let out_come = call!(
+        farmer,
+        farming.return_seed_lostfound(farmer.account_id(), seed_id.clone(), U128(shares - shares / 2)),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E002: not allowed"));
+    call!(
+        root,
+        farming.return_seed_lostfound(farmer.account_id(), seed_id.clone(), U128(shares - shares / 2)),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(list_lostfound(&farming).get(&seed_id).is_none());
+    assert_eq!(mft_balance_of(&pool, ":0", &farmer.account_id()), shares - shares / 2);
+}
+
+#[test]
+fn sim_boost_farming_ft_seed_and_reward() {
+    let (root, _owner, _pool, token1, token2, token3) = setup_pool_with_liquidity();
+    let farmer = root.create_user("farmer".to_string(), to_yocto("100"));
+    let farming = setup_boost_farming(&root);
+    let seed_id = dai();
+
+    for token in [&token1, &token2, &token3].iter() {
+        call!(
+            root,
+            token.storage_deposit(Some(farming.valid_account_id()), None),
+            deposit = to_yocto("1")
+        )
+        .assert_success();
+        call!(
+            root,
+            token.storage_deposit(Some(farmer.valid_account_id()), None),
+            deposit = to_yocto("1")
+        )
+        .assert_success();
+    }
+    call!(
+        farmer,
+        farming.storage_deposit(None, None),
+        deposit = to_yocto("0.1")
+    )
+    .assert_success();
+    call!(
+        root,
+        farming.create_seed(seed_id.clone(), 18, Some(U128(0)), Some(0)),
+        deposit = 1
+    )
+    .assert_success();
+    let farm_id: String = call!(
+        root,
+        farming.create_farm(seed_id.clone(), FarmTerms {
+            reward_token: eth(),
+            start_at: 0,
+            daily_reward: to_yocto("86400"),
+        }),
+        deposit = 1
+    )
+    .unwrap_json();
+    call!(
+        root,
+        token2.ft_transfer_call(to_va(boost_farming()), U128(to_yocto("1000")), None, format!("{{\"Reward\": {{\"farm_id\": \"{}\"}}}}", farm_id)),
+        deposit = 1
+    )
+    .assert_success();
+
+    call!(
+        root,
+        token1.mint(farmer.valid_account_id(), U128(to_yocto("10")))
+    )
+    .assert_success();
+    call!(
+        farmer,
+        token1.ft_transfer_call(to_va(boost_farming()), U128(to_yocto("10")), None, "\"Free\"".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(root.borrow_runtime_mut().produce_blocks(60).is_ok());
+
+    // reward withdraw fails while the farmer is not registered in the reward token, the reward is kept
+    call!(
+        farmer,
+        farming.claim_reward_by_seed(seed_id.clone())
+    )
+    .assert_success();
+    let reward = view!(farming.get_farmer_reward(farmer.account_id(), eth())).unwrap_json::<U128>().0;
+    assert!(reward > 0);
+    call!(
+        farmer,
+        token2.storage_unregister(None),
+        deposit = 1
+    )
+    .assert_success();
+    let out_come = call!(
+        farmer,
+        farming.withdraw_reward(eth(), None)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert_eq!(view!(farming.get_farmer_reward(farmer.account_id(), eth())).unwrap_json::<U128>().0, reward);
+
+    call!(
+        root,
+        token2.storage_deposit(Some(farmer.valid_account_id()), None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let out_come = call!(
+        farmer,
+        farming.withdraw_reward(eth(), Some(U128(reward + 1)))
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E101: insufficient balance"));
+    call!(
+        farmer,
+        farming.withdraw_reward(eth(), None)
+    )
+    .assert_success();
+    assert_eq!(balance_of(&token2, &farmer.account_id()), reward);
+    assert_eq!(view!(farming.get_farmer_reward(farmer.account_id(), eth())).unwrap_json::<U128>().0, 0);
+
+    // seed withdraw
+    call!(
+        farmer,
+        farming.unlock_and_withdraw_seed(seed_id.clone(), U128(0), U128(to_yocto("4"))),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(balance_of(&token1, &farmer.account_id()), to_yocto("4"));
+
+    // receiver not registered in the seed token, the seed goes to lostfound
+    call!(
+        farmer,
+        token1.ft_transfer(to_va(root.account_id()), U128(to_yocto("4")), None),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        farmer,
+        token1.storage_unregister(None),
+        deposit = 1
+    )
+    .assert_success();
+    let out_come = call!(
+        farmer,
+        farming.unlock_and_withdraw_seed(seed_id.clone(), U128(0), U128(to_yocto("6"))),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_farmer_seed(&farming, &farmer, &seed_id).is_none());
+    assert_eq!(list_lostfound(&farming).get(&seed_id).unwrap().0, to_yocto("6"));
+
+    call!(
+        root,
+        token1.storage_deposit(Some(farmer.valid_account_id()), None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        root,
+        farming.return_seed_lostfound(farmer.account_id(), seed_id.clone(), U128(to_yocto("6"))),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(list_lostfound(&farming).get(&seed_id).is_none());
+    assert_eq!(balance_of(&token1, &farmer.account_id()), to_yocto("6"));
+
+    // slashed seed of a force unlock goes to the owner
+    let lock_seed_id = usdt();
+    call!(
+        root,
+        farming.create_seed(lock_seed_id.clone(), 18, Some(U128(0)), Some(3600)),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        root,
+        token3.mint(farmer.valid_account_id(), U128(to_yocto("10")))
+    )
+    .assert_success();
+    call!(
+        farmer,
+        token3.ft_transfer_call(to_va(boost_farming()), U128(to_yocto("10")), None, "{\"Lock\": {\"duration_sec\": 3600}}".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        farmer,
+        farming.force_unlock(lock_seed_id.clone(), U128(to_yocto("10"))),
+        deposit = 1
+    )
+    .assert_success();
+    let slashed = view!(farming.list_slashed(None, None)).unwrap_json::<HashMap<String, U128>>().get(&lock_seed_id).unwrap().0;
+    assert!(slashed > 0);
+    let root_balance = balance_of(&token3, &root.account_id());
+    call!(
+        root,
+        farming.withdraw_seed_slashed(lock_seed_id.clone()),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(balance_of(&token3, &root.account_id()), root_balance + slashed);
+    assert!(view!(farming.list_slashed(None, None)).unwrap_json::<HashMap<String, U128>>().get(&lock_seed_id).is_none());
+}
@@ -3,6 +3,7 @@ pub mod common;
 
 use test_token::ContractContract as TestToken;
 use mock_boost_farming::{ContractContract as MockBoostFarming};
+use ref_exchange::ClientEchoPolicy;
 
 use near_sdk::{json_types::U128, serde_json::Value, AccountId};
 use near_sdk_sim::{call, deploy, view, to_yocto, ContractAccount, ExecutionResult, UserAccount};
@@ -191,4 +192,28 @@ fn test_client_echo() {
     assert_eq!(balance_of(&token1, &mock_boost_farming.user_account.account_id), to_yocto("8"));
     assert_eq!(balance_of(&token2, &mock_boost_farming.user_account.account_id), 1814048647419868151852693);
     assert_eq!(balance_of(&token2, &new_user.account_id), 1512022210810475642302724);
+
+    // a policy of the sender takes over the legacy whitelists
+    call!(
+        owner,
+        pool.set_client_echo_policy(mock_boost_farming.account_id(), ClientEchoPolicy {
+            allowed_tokens: Some(vec![token1.account_id()]),
+            allowed_msg_prefixes: Some(vec!["\"Free\"".to_string()]),
+            max_extra_tgas: None,
+        }),
+        deposit = 1
+    )
+    .assert_success();
+
+    let out_come = do_swap(
+        &mock_boost_farming.user_account,
+        &token1,
+        vec![action.clone()],
+        to_yocto("1"),
+        Some("Hi".to_string()),
+        None,
+    );
+    out_come.assert_success();
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("Invalid client echo message"));
 }
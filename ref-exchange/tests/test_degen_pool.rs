@@ -1,9 +1,9 @@
 use mock_price_oracle::Price;
 use mock_pyth::PythPrice;
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
-use near_sdk::{json_types::U128, AccountId};
-use near_sdk_sim::{call, to_yocto, view};
-use ref_exchange::{DegenOracleConfig, DegenPoolLimitInfo, DegenTokenInfo, DegenType, PoolInfo, PriceOracleConfig, PythOracleConfig, SwapAction, VPoolLimitInfo};
+use near_sdk::{json_types::{U128, U64}, AccountId};
+use near_sdk_sim::{call, init_simulator, runtime, to_yocto, view};
+use ref_exchange::{DegenOracleConfig, DegenPoolInfo, DegenPoolLimitInfo, DegenTokenInfo, DegenType, PoolInfo, PriceOracleConfig, PythOracleConfig, SwapAction, VPoolLimitInfo};
 use std::{collections::HashMap, convert::TryInto};
 use crate::common::utils::*;
 pub mod common;
@@ -155,9 +155,11 @@ fn sim_degen() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: eth(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         gas = 300000000000000
@@ -317,9 +319,11 @@ fn sim_degen1() {
                 token_in: btc(),
                 amount_in: Some(U128(ONE_BTC)),
                 token_out: eth(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         gas = 300000000000000
@@ -636,3 +640,109 @@ fn batch_update_degen_token_same_price_id() {
          This test verifies the fix for the issue where only one token was updated."
     );
 }
+
+#[test]
+fn degen_ramp_amp() {
+    let mut genesis_config = runtime::GenesisConfig::default();
+    genesis_config.genesis_time = 86400 * 1_000_000_000;
+    let (root, owner, pool, _tokens) = 
+        setup_degen_pool_with_root(
+            init_simulator(Some(genesis_config)),
+            vec![eth(), near()],
+            vec![100000*ONE_ETH, 100000*ONE_NEAR],
+            vec![18, 24],
+            25,
+            10000,
+        );
+    let price_oracle_contract = setup_price_oracle(&root);
+    call!(
+        root,
+        price_oracle_contract.set_price_data(eth(), Price {
+            multiplier: 10000,
+            decimals: 22,
+        })
+    ).assert_success();
+    call!(
+        root,
+        price_oracle_contract.set_price_data(near(), Price {
+            multiplier: 10000,
+            decimals: 28,
+        })
+    ).assert_success();
+    call!(
+        owner, 
+        pool.register_degen_oracle_config(DegenOracleConfig::PriceOracle(PriceOracleConfig { 
+            oracle_id: price_oracle(), 
+            expire_ts: 3600 * 10u64.pow(9), 
+            maximum_recency_duration_sec: 90, 
+            maximum_staleness_duration_sec: 90
+        })),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        owner, 
+        pool.register_degen_token(to_va(eth()), DegenType::PriceOracle { decimals: 18 }),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        owner, 
+        pool.register_degen_token(to_va(near()), DegenType::PriceOracle { decimals: 24 }),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        root, 
+        pool.update_degen_token_price(to_va(eth())),
+        deposit = 0
+    )
+    .assert_success();
+    call!(
+        root, 
+        pool.update_degen_token_price(to_va(near())),
+        deposit = 0
+    )
+    .assert_success();
+
+    let pool_info = view!(pool.get_degen_pool(0)).unwrap_json::<DegenPoolInfo>();
+    assert_eq!(pool_info.init_amp_factor, 10000);
+    assert_eq!(pool_info.target_amp_factor, 10000);
+    assert_eq!(pool_info.init_amp_time.0, 0);
+    assert_eq!(pool_info.stop_amp_time.0, 0);
+
+    let now = root.borrow_runtime().current_block().block_timestamp;
+    let future_amp_time = now + 10 * 86400 * 1_000_000_000;
+
+    let outcome = call!(
+        root,
+        pool.stable_swap_ramp_amp(0, 50000, U64(future_amp_time)),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&outcome), 1);
+    assert!(get_error_status(&outcome).contains("E100: no permission to invoke this"));
+
+    call!(
+        owner,
+        pool.stable_swap_ramp_amp(0, 50000, U64(future_amp_time)),
+        deposit = 1
+    )
+    .assert_success();
+    let pool_info = view!(pool.get_degen_pool(0)).unwrap_json::<DegenPoolInfo>();
+    assert_eq!(pool_info.init_amp_factor, 10000);
+    assert_eq!(pool_info.target_amp_factor, 50000);
+    assert!(pool_info.init_amp_time.0 >= now);
+    assert_eq!(pool_info.stop_amp_time.0, future_amp_time);
+    assert_eq!(pool_info.amp, 10000);
+
+    call!(
+        owner,
+        pool.stable_swap_stop_ramp_amp(0),
+        deposit = 1
+    )
+    .assert_success();
+    let pool_info = view!(pool.get_degen_pool(0)).unwrap_json::<DegenPoolInfo>();
+    assert_eq!(pool_info.target_amp_factor, pool_info.init_amp_factor);
+    assert_eq!(pool_info.stop_amp_time.0, pool_info.init_amp_time.0);
+    assert_eq!(pool_info.amp, pool_info.init_amp_factor);
+}
@@ -148,9 +148,11 @@ fn sim_stable_e63 () {
                 token_in: token3.account_id(),
                 amount_in: Some(U128(100)),
                 token_out: usdt(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -423,9 +425,11 @@ fn sim_stable_e68 () {
                 token_in: dai(),
                 amount_in: Some(U128(ONE_DAI)),
                 token_out: usdt(),
-                min_amount_out: U128(2 * ONE_USDT)
+                min_amount_out: U128(2 * ONE_USDT),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -489,9 +493,11 @@ fn sim_stable_e69 () {
                 token_in: usdt(),
                 amount_in: Some(U128(99*ONE_USDT)),
                 token_out: dai(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -504,9 +510,11 @@ fn sim_stable_e69 () {
                 token_in: usdt(),
                 amount_in: Some(U128(99*ONE_USDT)),
                 token_out: dai(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -548,9 +556,11 @@ fn sim_stable_e71 () {
                 token_in: dai(),
                 amount_in: Some(U128(1)),
                 token_out: dai(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -3,6 +3,7 @@ pub mod common;
 
 use test_token::ContractContract as TestToken;
 use mock_boost_farming::{ContractContract as MockBoostFarming};
+use ref_exchange::ClientEchoPolicy;
 
 use near_sdk::{json_types::U128, serde_json::Value, AccountId};
 use near_sdk_sim::{call, deploy, view, to_yocto, ContractAccount, ExecutionResult, UserAccount};
@@ -171,5 +172,29 @@ fn test_extra_gas() {
     println!("logs: {:#?}", get_logs(&out_come));
     assert_eq!(get_error_count(&out_come), 1);
     assert_eq!(*get_logs(&out_come).get(4).unwrap(), "prepaid gas: 5000000000000".to_string());
-    
+
+    // a policy of the sender takes over the legacy whitelists
+    call!(
+        owner,
+        pool.set_client_echo_policy(mock_boost_farming.account_id(), ClientEchoPolicy {
+            allowed_tokens: Some(vec![token1.account_id()]),
+            allowed_msg_prefixes: None,
+            max_extra_tgas: Some(15),
+        }),
+        deposit = 1
+    )
+    .assert_success();
+
+    let out_come = do_swap(
+        &mock_boost_farming.user_account,
+        &token1,
+        vec![action.clone()],
+        to_yocto("1"),
+        Some("\\\"Free\\\"".to_string()),
+        None,
+        Some(20_u32),
+    );
+    out_come.assert_success();
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("Extra tgas for client echo exceeds limit"));
 }
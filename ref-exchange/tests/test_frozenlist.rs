@@ -162,9 +162,11 @@ fn frozenlist_scenario_02() {
                     token_in: dai(),
                     amount_in: Some(U128(to_yocto("1"))),
                     token_out: eth(),
-                    min_amount_out: U128(1)
+                    min_amount_out: U128(1),
+                    max_price_impact_bps: None,
             })],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -182,9 +184,11 @@ fn frozenlist_scenario_02() {
                 token_in: dai(),
                 amount_in: Some(U128(to_yocto("1"))),
                 token_out: eth(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -232,9 +236,11 @@ fn frozenlist_scenario_02() {
                 token_in: eth(),
                 amount_in: Some(U128(to_yocto("1"))),
                 token_out: dai(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
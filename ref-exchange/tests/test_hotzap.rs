@@ -61,14 +61,16 @@ fn test_hotzap_simple_pool() {
                 token_in: token_dai.account_id(), 
                 amount_in: Some(U128(to_yocto("1"))), 
                 token_out: token_eth.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             }),
             Action::Swap(SwapAction { 
                 pool_id: DAI_USDT, 
                 token_in: token_dai.account_id(), 
                 amount_in: Some(U128(to_yocto("1"))), 
                 token_out: token_usdt.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             })
         ],
         vec![
@@ -207,14 +209,16 @@ fn test_hotzap_simple_pool_add_two() {
                 token_in: token_dai.account_id(), 
                 amount_in: Some(U128(to_yocto("1"))), 
                 token_out: token_eth.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             }),
             Action::Swap(SwapAction { 
                 pool_id: DAI_USDT, 
                 token_in: token_dai.account_id(), 
                 amount_in: Some(U128(to_yocto("1"))), 
                 token_out: token_usdt.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             })
         ],
         vec![
@@ -411,14 +415,16 @@ fn test_hotzap_stable_pool() {
                 token_in: token_dai.account_id(), 
                 amount_in: Some(U128(5*ONE_DAI)), 
                 token_out: token_usdt.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             }),
             Action::Swap(SwapAction { 
                 pool_id: DAI_USDC, 
                 token_in: token_dai.account_id(), 
                 amount_in: Some(U128(5*ONE_DAI)), 
                 token_out: token_usdc.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             })
         ],
         vec![
@@ -622,14 +628,16 @@ fn test_hotzap_stable_pool_add_two() {
                 token_in: token_dai.account_id(), 
                 amount_in: Some(U128(5*ONE_DAI)), 
                 token_out: token_usdt.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             }),
             Action::Swap(SwapAction { 
                 pool_id: DAI_USDC, 
                 token_in: token_dai.account_id(), 
                 amount_in: Some(U128(5*ONE_DAI)), 
                 token_out: token_usdc.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             })
         ],
         vec![
@@ -826,7 +834,8 @@ fn test_hotzap_rate_pool() {
                 token_in: token_near.account_id(), 
                 amount_in: Some(U128(to_yocto("5"))), 
                 token_out: token_stnear.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             }),
         ],
         vec![
@@ -1022,7 +1031,8 @@ fn test_hotzap_rate_pool_add_two() {
                 token_in: token_near.account_id(), 
                 amount_in: Some(U128(to_yocto("5"))), 
                 token_out: token_stnear.account_id(), 
-                min_amount_out: U128(1) 
+                min_amount_out: U128(1),
+                max_price_impact_bps: None, 
             }),
         ],
         vec![
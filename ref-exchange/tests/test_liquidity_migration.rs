@@ -0,0 +1,102 @@
+use near_sdk::json_types::U128;
+use near_sdk_sim::{call, view, to_yocto};
+
+use ref_exchange::{MigrateLiquidityPrediction, PoolInfo};
+use crate::common::utils::*;
+pub mod common;
+
+const ONE_LPT: u128 = 1000000000000000000;
+const ONE_DAI: u128 = 1000000000000000000;
+const ONE_USDT: u128 = 1000000;
+const ONE_USDC: u128 = 1000000;
+
+#[test]
+fn sim_migrate_liquidity() {
+    let (root, _owner, pool, tokens) =
+        setup_stable_pool_with_liquidity(
+            vec![dai(), usdt(), usdc()],
+            vec![100000*ONE_DAI, 100000*ONE_USDT, 100000*ONE_USDC],
+            vec![18, 6, 6],
+            25,
+            10000,
+        );
+    // a simple pool of dai-usdt as pool 1
+    call!(
+        root,
+        pool.add_simple_pool(vec![to_va(dai()), to_va(usdt())], 25),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    for (token, amount) in tokens.iter().take(2).zip(vec![1000*ONE_DAI, 1000*ONE_USDT]) {
+        call!(
+            root,
+            token.ft_transfer_call(pool.valid_account_id(), U128(amount), None, "".to_string()),
+            deposit = 1
+        )
+        .assert_success();
+    }
+    call!(
+        root,
+        pool.add_liquidity(1, vec![U128(1000*ONE_DAI), U128(1000*ONE_USDT)], None),
+        deposit = to_yocto("0.0007")
+    )
+    .assert_success();
+    let simple_shares = mft_balance_of(&pool, ":1", &root.account_id());
+    let stable_shares = mft_balance_of(&pool, ":0", &root.account_id());
+
+    let out_come = call!(
+        root,
+        pool.migrate_liquidity(1, U128(simple_shares), 1, U128(1)),
+        deposit = to_yocto("0.0007")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E76: invalid params"));
+
+    // stable pool has usdc which is not in the simple pool, no swap is made for it
+    assert!(view!(pool.predict_migrate_liquidity(1, U128(simple_shares / 2), 0)).is_err());
+    let out_come = call!(
+        root,
+        pool.migrate_liquidity(1, U128(simple_shares / 2), 0, U128(1)),
+        deposit = to_yocto("0.0007")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E95: target pool token not in source pool"));
+    assert_eq!(mft_balance_of(&pool, ":1", &root.account_id()), simple_shares);
+
+    let prediction = view!(pool.predict_migrate_liquidity(0, U128(1000*ONE_LPT), 1)).unwrap_json::<MigrateLiquidityPrediction>();
+    assert_eq!(prediction.remove_amounts.len(), 3);
+    assert_eq!(prediction.need_amounts.len(), 2);
+    assert!(prediction.need_amounts[0].0 <= prediction.remove_amounts[0].0);
+    assert!(prediction.need_amounts[1].0 <= prediction.remove_amounts[1].0);
+    assert!(prediction.mint_shares.0 > 0);
+
+    let out_come = call!(
+        root,
+        pool.migrate_liquidity(0, U128(1000*ONE_LPT), 1, U128(prediction.mint_shares.0 + 1)),
+        deposit = to_yocto("0.0007")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E68: slippage error"));
+
+    let deposits_before = get_deposits(&pool, root.valid_account_id());
+    let out_come = call!(
+        root,
+        pool.migrate_liquidity(0, U128(1000*ONE_LPT), 1, prediction.mint_shares),
+        deposit = to_yocto("0.0007")
+    );
+    out_come.assert_success();
+    assert_eq!(out_come.unwrap_json::<U128>(), prediction.mint_shares);
+    assert_eq!(mft_balance_of(&pool, ":0", &root.account_id()), stable_shares - 1000*ONE_LPT);
+    assert_eq!(mft_balance_of(&pool, ":1", &root.account_id()), simple_shares + prediction.mint_shares.0);
+    assert_eq!(
+        view!(pool.get_pool(1)).unwrap_json::<PoolInfo>().amounts,
+        vec![U128(1000*ONE_DAI + prediction.need_amounts[0].0), U128(1000*ONE_USDT + prediction.need_amounts[1].0)]
+    );
+    // removed tokens not used by the simple pool stay in deposits
+    let deposits = get_deposits(&pool, root.valid_account_id());
+    for (idx, token_id) in vec![dai(), usdt(), usdc()].into_iter().enumerate() {
+        let before = deposits_before.get(&token_id).map(|v| v.0).unwrap_or(0);
+        let unused = prediction.remove_amounts[idx].0 - prediction.need_amounts.get(idx).map(|v| v.0).unwrap_or(0);
+        assert_eq!(deposits.get(&token_id).map(|v| v.0).unwrap_or(0), before + unused);
+    }
+}
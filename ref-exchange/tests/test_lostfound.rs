@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use near_sdk::json_types::U128;
 use near_sdk_sim::{
-    call, to_yocto, ContractAccount, ExecutionResult, UserAccount,
+    call, view, to_yocto, ContractAccount, ExecutionResult, UserAccount,
 };
 
 use test_token::ContractContract as TestToken;
@@ -443,3 +445,198 @@ fn lostfound_scenario_05_multiple_tokens_in_lostfound() {
 
     println!("✓ Scenario 05 PASSED: Multiple tokens accumulate and claim works correctly");
 }
+
+#[test]
+fn lostfound_scenario_06_batch_claim_to_and_redeliver() {
+    println!("\n=== Scenario 06: Batch Claim To Receiver and Guardian Redelivery ===");
+    let (root, owner, pool, token1, token2, token3) = setup_pool_with_liquidity_high_near();
+    let user = root.create_user("batch_user".to_string(), to_yocto("200"));
+    let receiver = root.create_user("receiver".to_string(), to_yocto("10"));
+
+    println!("Case 0601: Put token2 and token3 into user lostfound");
+    call!(
+        user,
+        token1.mint(to_va(user.account_id.clone()), U128(to_yocto("10")))
+    )
+    .assert_success();
+    let action = pack_action(0, &token1.account_id(), &token2.account_id(), 1);
+    direct_swap(&user, &token1, vec![action], to_yocto("3")).assert_success();
+    let action = pack_action(2, &token1.account_id(), &token3.account_id(), 1);
+    direct_swap(&user, &token1, vec![action], to_yocto("3")).assert_success();
+    let lostfound_token2 = get_lostfound_token(&pool, user.valid_account_id(), token2.valid_account_id());
+    let lostfound_token3 = get_lostfound_token(&pool, user.valid_account_id(), token3.valid_account_id());
+    assert!(lostfound_token2 > 0 && lostfound_token3 > 0);
+
+    println!("Case 0602: Paged view lists user with all lostfound tokens");
+    assert_eq!(view!(pool.get_lostfound_account_count()).unwrap_json::<u64>(), 1);
+    let accounts = view!(pool.list_lostfound_accounts(None, None)).unwrap_json::<HashMap<String, HashMap<String, U128>>>();
+    assert_eq!(accounts.get(&user.account_id).unwrap().get(&token2.account_id()).unwrap().0, lostfound_token2);
+    assert_eq!(accounts.get(&user.account_id).unwrap().get(&token3.account_id()).unwrap().0, lostfound_token3);
+
+    println!("Case 0603: Batch claim to another receiver");
+    for token in vec![&token2, &token3] {
+        call!(
+            receiver,
+            token.storage_deposit(None, None),
+            deposit = to_yocto("1")
+        )
+        .assert_success();
+    }
+    let outcome = call!(
+        user,
+        pool.claim_lostfound_batch(vec![token2.valid_account_id(), token2.valid_account_id()], Some(receiver.valid_account_id()), None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&outcome), 1);
+    assert!(get_error_status(&outcome).contains("E92: token duplicated"));
+    let outcome = call!(
+        user,
+        pool.claim_lostfound_batch((0..6).map(|i| to_va(format!("token{}", i))).collect(), Some(receiver.valid_account_id()), None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&outcome), 1);
+    assert!(get_error_status(&outcome).contains("E76: invalid params"));
+    call!(
+        user,
+        pool.claim_lostfound_batch(vec![token2.valid_account_id(), token3.valid_account_id()], Some(receiver.valid_account_id()), None),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(balance_of(&token2, &receiver.account_id), lostfound_token2);
+    assert_eq!(balance_of(&token3, &receiver.account_id), lostfound_token3);
+    assert_eq!(view!(pool.get_lostfound_account_count()).unwrap_json::<u64>(), 0);
+
+    println!("Case 0604: Only guardians can redeliver");
+    let action = pack_action(0, &token1.account_id(), &token2.account_id(), 1);
+    direct_swap(&user, &token1, vec![action], to_yocto("3")).assert_success();
+    let lostfound_token2 = get_lostfound_token(&pool, user.valid_account_id(), token2.valid_account_id());
+    assert!(lostfound_token2 > 0);
+    let outcome = call!(
+        user,
+        pool.redeliver_lostfound(user.valid_account_id(), token2.valid_account_id()),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&outcome), 1);
+    assert!(get_error_status(&outcome).contains("E100: no permission to invoke this"));
+
+    println!("Case 0605: Redelivery to an unregistered user puts tokens back");
+    let outcome = call!(
+        owner,
+        pool.redeliver_lostfound(user.valid_account_id(), token2.valid_account_id()),
+        deposit = 1
+    );
+    outcome.assert_success();
+    assert_eq!(get_error_count(&outcome), 1);
+    assert_eq!(get_lostfound_token(&pool, user.valid_account_id(), token2.valid_account_id()), lostfound_token2);
+
+    println!("Case 0606: Redelivery succeeds after user registers on token2");
+    call!(
+        user,
+        token2.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        owner,
+        pool.redeliver_lostfound(user.valid_account_id(), token2.valid_account_id()),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(balance_of(&token2, &user.account_id), lostfound_token2);
+    assert_eq!(get_lostfound_token(&pool, user.valid_account_id(), token2.valid_account_id()), 0);
+    assert_eq!(view!(pool.get_lostfound_account_count()).unwrap_json::<u64>(), 0);
+
+    println!("✓ Scenario 06 PASSED: batch claim, claim to receiver and redelivery work");
+}
+
+#[test]
+fn lostfound_scenario_07_withdraw_batch() {
+    println!("\n=== Scenario 07: Batch withdraw with partial failure ===");
+    let (root, _owner, pool, token1, token2, _) = setup_pool_with_liquidity_high_near();
+    let user = root.create_user("batch_user".to_string(), to_yocto("200"));
+    call!(
+        user,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        user,
+        token1.mint(to_va(user.account_id.clone()), U128(to_yocto("10")))
+    )
+    .assert_success();
+    call!(
+        user,
+        token1.ft_transfer_call(to_va(swap()), to_yocto("5").into(), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+
+    println!("Case 0701: Get token2 into pool account, user is NOT registered to token2");
+    let action = pack_action(0, &token1.account_id(), &token2.account_id(), 1);
+    direct_swap(&user, &token1, vec![action], to_yocto("1")).assert_success();
+    let token2_in_pool = get_deposits(&pool, user.valid_account_id()).get(&token2.account_id()).unwrap().0;
+    assert!(token2_in_pool > 0);
+
+    println!("Case 0702: Duplicated tokens are rejected");
+    let outcome = call!(
+        user,
+        pool.withdraw_batch(vec![(token1.valid_account_id(), U128(1)), (token1.valid_account_id(), U128(1))], None, None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&outcome), 1);
+    assert!(get_error_status(&outcome).contains("E92: token duplicated"));
+
+    println!("Case 0702: Batch over the cap and short of gas are rejected");
+    let outcome = call!(
+        user,
+        pool.withdraw_batch(vec![(token1.valid_account_id(), U128(1)); 6], None, None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&outcome), 1);
+    assert!(get_error_status(&outcome).contains("E76: invalid params"));
+    let outcome = call!(
+        user,
+        pool.withdraw_batch(vec![(token1.valid_account_id(), U128(1)), (token2.valid_account_id(), U128(0))], None, None),
+        deposit = 1,
+        gas = 50000000000000
+    );
+    assert_eq!(get_error_count(&outcome), 1);
+    assert!(get_error_status(&outcome).contains("E37: not enough prepaid gas"));
+
+    println!("Case 0703: token1 is delivered, failed token2 goes back to pool account");
+    let outcome = call!(
+        user,
+        pool.withdraw_batch(vec![(token1.valid_account_id(), U128(to_yocto("2"))), (token2.valid_account_id(), U128(0))], None, None),
+        deposit = 1
+    );
+    outcome.assert_success();
+    assert_eq!(get_error_count(&outcome), 1);
+    assert_eq!(outcome.unwrap_json::<Vec<U128>>(), vec![U128(to_yocto("2")), U128(0)]);
+    assert_eq!(balance_of(&token1, &user.account_id), to_yocto("6"));
+    assert_eq!(balance_of(&token2, &user.account_id), 0);
+    let deposits = get_deposits(&pool, user.valid_account_id());
+    assert_eq!(deposits.get(&token1.account_id()).unwrap().0, to_yocto("3"));
+    assert_eq!(deposits.get(&token2.account_id()).unwrap().0, token2_in_pool);
+    assert_eq!(get_lostfound_token(&pool, user.valid_account_id(), token2.valid_account_id()), 0);
+
+    println!("Case 0704: Withdraw all and unregister after user registers on token2");
+    call!(
+        user,
+        token2.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let outcome = call!(
+        user,
+        pool.withdraw_batch(vec![(token1.valid_account_id(), U128(0)), (token2.valid_account_id(), U128(0))], Some(true), None),
+        deposit = 1
+    );
+    outcome.assert_success();
+    assert_eq!(outcome.unwrap_json::<Vec<U128>>(), vec![U128(to_yocto("3")), U128(token2_in_pool)]);
+    assert_eq!(balance_of(&token1, &user.account_id), to_yocto("9"));
+    assert_eq!(balance_of(&token2, &user.account_id), token2_in_pool);
+    assert!(get_deposits(&pool, user.valid_account_id()).is_empty());
+
+    println!("✓ Scenario 07 PASSED: batch withdraw reconciles each transfer");
+}
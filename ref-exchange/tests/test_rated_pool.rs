@@ -355,9 +355,11 @@ fn sim_rated_swap_two_no_rated() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: stnear(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -462,9 +464,11 @@ fn sim_rated_swap_rate_one_with_fee() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: stnear(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -570,9 +574,11 @@ fn sim_rated_swap_rate_one_no_fee() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: stnear(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -655,9 +661,11 @@ fn sim_rated_swap_three_no_rated() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: stnear(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -673,9 +681,11 @@ fn sim_rated_swap_three_no_rated() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: linear(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -795,9 +805,11 @@ fn sim_rated_swap() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: linear(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -823,9 +835,11 @@ fn sim_rated_swap() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: linear(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -1022,9 +1036,11 @@ fn sim_rated_swap_out_zero() {
                 token_in: near(),
                 amount_in: Some(U128(0)),
                 token_out: stnear(),
-                min_amount_out: U128(0)
+                min_amount_out: U128(0),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -1040,9 +1056,11 @@ fn sim_rated_swap_out_zero() {
                 token_in: near(),
                 amount_in: Some(U128(1)),
                 token_out: stnear(),
-                min_amount_out: U128(0)
+                min_amount_out: U128(0),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -1531,9 +1549,11 @@ fn sim_rated_swap_two_no_rated_with_nearx() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: nearx(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -1638,9 +1658,11 @@ fn sim_rated_swap_rate_one_with_fee_with_nearx() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: nearx(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -1745,9 +1767,11 @@ fn sim_rated_swap_rate_one_no_fee_with_nearx() {
                 token_in: near(),
                 amount_in: Some(U128(ONE_NEAR)),
                 token_out: nearx(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -1997,9 +2021,11 @@ fn sim_sfrax_rated_swap_rate_one_price_oracle() {
                 token_in: frax(),
                 amount_in: Some(U128(ONE_FRAX)),
                 token_out: sfrax(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -2247,9 +2273,11 @@ fn sim_sfrax_rated_swap_rate_one_pyth() {
                 token_in: frax(),
                 amount_in: Some(U128(ONE_FRAX)),
                 token_out: sfrax(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -2261,4 +2289,119 @@ fn sim_sfrax_rated_swap_rate_one_pyth() {
         .unwrap_json::<HashMap<AccountId, U128>>();
     assert_eq!(balances[&frax()].0, 999999000000000000000000);
     assert_eq!(balances[&sfrax()].0, 498771039615167716);
-}
\ No newline at end of file
+}
+#[test]
+fn sim_rated_swap_rate_guard() {
+    let (root, owner, pool, tokens, token_rated_contracts) = 
+        setup_rated_pool(
+            vec![near()],
+            vec![stnear()],
+            vec![24, 24],
+            25,
+            10000,
+        );
+
+    let stnear_contract = &token_rated_contracts[0];
+
+    call!(
+        owner,
+        pool.register_rated_token(
+            "STNEAR".to_string(),
+            token_rated_contracts[0].valid_account_id(),
+            None
+        ),
+        deposit = 1
+    ).assert_success();
+
+    // first update is never guarded
+    call!(
+        root,
+        stnear_contract.set_price(U128(2 * 10u128.pow(24)))
+    ).assert_success();
+    call!(
+        owner,
+        pool.update_token_rate(
+            stnear_contract.valid_account_id()
+        ),
+        deposit = 1
+    ).assert_success();
+
+    let out_come = call!(
+        root,
+        pool.set_rated_token_guard(stnear_contract.valid_account_id(), 100, 100, 3600),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E100: no permission to invoke this"));
+
+    call!(
+        owner,
+        pool.set_rated_token_guard(stnear_contract.valid_account_id(), 100, 100, 3600),
+        deposit = 1
+    ).assert_success();
+
+    // +5% is out of the 1% bound
+    call!(
+        root,
+        stnear_contract.set_price(U128(21 * 10u128.pow(23)))
+    ).assert_success();
+    let out_come = call!(
+        owner,
+        pool.update_token_rate(
+            stnear_contract.valid_account_id()
+        ),
+        deposit = 1
+    );
+    out_come.assert_success();
+    assert!(get_logs(&out_come).iter().any(|l| l.contains("rate_guard_triggered")));
+
+    let rated_infos = view!(pool.list_rated_tokens()).unwrap_json::<HashMap<String, RatedTokenInfo>>();
+    let stnear_info = rated_infos.get(&stnear()).unwrap();
+    assert_eq!(stnear_info.rate_price.0, 2 * 10u128.pow(24));
+    let rate_guard = stnear_info.rate_guard.as_ref().unwrap();
+    assert!(rate_guard.is_paused);
+    assert_eq!(rate_guard.last_reported_rate.0, 21 * 10u128.pow(23));
+
+    let user = root.create_user("user".to_string(), to_yocto("100"));
+    mint_and_deposit_token(&user, &tokens[0], &pool, 100000*ONE_NEAR);
+    mint_and_deposit_rated_token(&user, &token_rated_contracts[0], &pool, 100000*ONE_STNEAR);
+    let out_come = call!(
+        user,
+        pool.add_stable_liquidity(0, vec![
+            U128(100000*ONE_NEAR), U128(50000*ONE_STNEAR)], U128(1)),
+        deposit = to_yocto("0.0007") 
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E121: Rate paused by rate guard"));
+
+    // owner loosens the bound and resumes the token
+    call!(
+        owner,
+        pool.set_rated_token_guard(stnear_contract.valid_account_id(), 1000, 100, 3600),
+        deposit = 1
+    ).assert_success();
+    call!(
+        owner,
+        pool.resume_rated_token(stnear_contract.valid_account_id()),
+        deposit = 1
+    ).assert_success();
+    call!(
+        owner,
+        pool.update_token_rate(
+            stnear_contract.valid_account_id()
+        ),
+        deposit = 1
+    ).assert_success();
+
+    let rated_infos = view!(pool.list_rated_tokens()).unwrap_json::<HashMap<String, RatedTokenInfo>>();
+    let stnear_info = rated_infos.get(&stnear()).unwrap();
+    assert_eq!(stnear_info.rate_price.0, 21 * 10u128.pow(23));
+    assert!(!stnear_info.rate_guard.as_ref().unwrap().is_paused);
+
+    call!(
+        user,
+        pool.add_stable_liquidity(0, vec![
+            U128(100000*ONE_NEAR), U128(50000*ONE_STNEAR)], U128(1)),
+        deposit = to_yocto("0.0007") 
+    ).assert_success();
+}
@@ -0,0 +1,254 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use near_sdk::json_types::{Base58PublicKey, Base64VecU8, U128};
+use near_sdk_sim::{call, view, to_yocto, UserAccount};
+
+use ref_exchange::IntentSignerInfo;
+use crate::common::utils::*;
+pub mod common;
+
+fn test_keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+fn to_base58_public_key(keypair: &Keypair) -> Base58PublicKey {
+    Base58PublicKey([vec![0], keypair.public.to_bytes().to_vec()].concat())
+}
+
+fn pack_intent(
+    signer: &UserAccount,
+    amount_in: u128,
+    deadline_ns: u64,
+    nonce: u64,
+    relayer_fee: u128,
+) -> String {
+    format!(
+        "{{\"signer_id\": \"{}\", \"contract_id\": \"{}\", \"actions\": [{{\"pool_id\": 0, \"token_in\": \"{}\", \"amount_in\": \"{}\", \"token_out\": \"{}\", \"min_amount_out\": \"1\"}}], \"deadline_ns\": \"{}\", \"nonce\": \"{}\", \"relayer_fee\": {{\"token_id\": \"{}\", \"amount\": \"{}\"}}}}",
+        signer.account_id, swap(), dai(), amount_in, eth(), deadline_ns, nonce, dai(), relayer_fee
+    )
+}
+
+#[test]
+fn sim_execute_signed_actions() {
+    let (root, _owner, pool, _token1, _token2, _token3) = setup_pool_with_liquidity();
+    let relayer = root.create_user("relayer".to_string(), to_yocto("100"));
+    call!(
+        relayer,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let keypair = test_keypair(7);
+    let deadline_ns = root.borrow_runtime().current_block().block_timestamp + 10u64.pow(12);
+
+    // not registered yet
+    let message = pack_intent(&root, to_yocto("1"), deadline_ns, 1, to_yocto("0.1"));
+    let signature = Base64VecU8(keypair.sign(message.as_bytes()).to_bytes().to_vec());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message.clone(), signature.clone())
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E140: intent key not registered"));
+
+    call!(
+        root,
+        pool.register_intent_key(to_base58_public_key(&keypair)),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+    let info = view!(pool.get_intent_signer(root.valid_account_id())).unwrap_json::<Option<IntentSignerInfo>>().unwrap();
+    assert_eq!(info.public_key.unwrap().0, to_base58_public_key(&keypair).0);
+    assert_eq!(info.nonce.0, 0);
+
+    // signed by another key
+    let other_signature = Base64VecU8(test_keypair(8).sign(message.as_bytes()).to_bytes().to_vec());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message.clone(), other_signature)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E142: invalid intent signature"));
+
+    let deposits_before = get_deposits(&pool, root.valid_account_id());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message.clone(), signature.clone())
+    );
+    out_come.assert_success();
+    let deposits_after = get_deposits(&pool, root.valid_account_id());
+    assert_eq!(deposits_before.get(&dai()).unwrap().0 - deposits_after.get(&dai()).unwrap().0, to_yocto("1.1"));
+    assert!(deposits_after.get(&eth()).unwrap().0 > deposits_before.get(&eth()).unwrap().0);
+    assert_eq!(get_deposits(&pool, relayer.valid_account_id()).get(&dai()).unwrap().0, to_yocto("0.1"));
+    assert!(get_logs(&out_come).iter().any(|log| log.contains("signed_intent_executed")));
+
+    // replay
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message, signature)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E144: intent nonce already used"));
+
+    // expired
+    let message = pack_intent(&root, to_yocto("1"), 1, 2, 0);
+    let signature = Base64VecU8(keypair.sign(message.as_bytes()).to_bytes().to_vec());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message, signature)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E143: intent expired"));
+
+    // key removed, nonce kept
+    call!(
+        root,
+        pool.remove_intent_key(),
+        deposit = 1
+    )
+    .assert_success();
+    let info = view!(pool.get_intent_signer(root.valid_account_id())).unwrap_json::<Option<IntentSignerInfo>>().unwrap();
+    assert!(info.public_key.is_none());
+    assert_eq!(info.nonce.0, 1);
+    let message = pack_intent(&root, to_yocto("1"), deadline_ns, 2, 0);
+    let signature = Base64VecU8(keypair.sign(message.as_bytes()).to_bytes().to_vec());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message, signature)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E140: intent key not registered"));
+}
+
+#[test]
+fn sim_execute_signed_actions_rejected() {
+    let (root, _owner, pool, _token1, _token2, _token3) = setup_pool_with_liquidity();
+    let relayer = root.create_user("relayer".to_string(), to_yocto("100"));
+    call!(
+        relayer,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let keypair = test_keypair(7);
+    let deadline_ns = root.borrow_runtime().current_block().block_timestamp + 10u64.pow(12);
+    call!(
+        root,
+        pool.register_intent_key(to_base58_public_key(&keypair)),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+
+    // malformed signature
+    let message = pack_intent(&root, to_yocto("1"), deadline_ns, 5, 0);
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message.clone(), Base64VecU8(vec![1u8; 10]))
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E142: invalid intent signature"));
+
+    // signature of another message
+    let signature = Base64VecU8(keypair.sign(message.as_bytes()).to_bytes().to_vec());
+    let tampered = pack_intent(&root, to_yocto("2"), deadline_ns, 5, 0);
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(tampered, signature.clone())
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E142: invalid intent signature"));
+
+    // signed for another contract
+    let other_contract = message.replace(&format!("\"contract_id\": \"{}\"", swap()), "\"contract_id\": \"other_exchange\"");
+    let other_signature = Base64VecU8(keypair.sign(other_contract.as_bytes()).to_bytes().to_vec());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(other_contract, other_signature)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E145: intent not for this contract"));
+
+    // relayer fee larger than signer's deposit
+    let message_fee = pack_intent(&root, to_yocto("1"), deadline_ns, 5, to_yocto("1000000"));
+    let signature_fee = Base64VecU8(keypair.sign(message_fee.as_bytes()).to_bytes().to_vec());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message_fee, signature_fee)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E22: not enough tokens in deposit"));
+    assert!(get_deposits(&pool, relayer.valid_account_id()).get(&dai()).is_none());
+
+    // nothing above consumed the nonce
+    call!(
+        relayer,
+        pool.execute_signed_actions(message, signature)
+    )
+    .assert_success();
+    let info = view!(pool.get_intent_signer(root.valid_account_id())).unwrap_json::<Option<IntentSignerInfo>>().unwrap();
+    assert_eq!(info.nonce.0, 5);
+
+    // lower nonce
+    let message = pack_intent(&root, to_yocto("1"), deadline_ns, 3, 0);
+    let signature = Base64VecU8(keypair.sign(message.as_bytes()).to_bytes().to_vec());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message, signature)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E144: intent nonce already used"));
+
+    // expired just now
+    let message = pack_intent(&root, to_yocto("1"), root.borrow_runtime().current_block().block_timestamp - 1, 6, 0);
+    let signature = Base64VecU8(keypair.sign(message.as_bytes()).to_bytes().to_vec());
+    let out_come = call!(
+        relayer,
+        pool.execute_signed_actions(message, signature)
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E143: intent expired"));
+}
+
+#[test]
+fn sim_intent_key_storage() {
+    let (root, _owner, pool, _token1, _token2, _token3) = setup_pool_with_liquidity();
+    let user = root.create_user("user".to_string(), to_yocto("100"));
+    let contract_storage = pool.user_account.account().unwrap().storage_usage;
+    call!(
+        user,
+        pool.storage_deposit(None, Some(true)),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let keypair = test_keypair(9);
+
+    // the key is paid by attached near, not by the account's storage deposit
+    let out_come = call!(
+        user,
+        pool.register_intent_key(to_base58_public_key(&keypair)),
+        deposit = 0
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("ERR_STORAGE_DEPOSIT"));
+    assert!(view!(pool.get_intent_signer(user.valid_account_id())).unwrap_json::<Option<IntentSignerInfo>>().is_none());
+
+    let account_storage = pool.user_account.account().unwrap().storage_usage;
+    call!(
+        user,
+        pool.register_intent_key(to_base58_public_key(&keypair)),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+    assert!(pool.user_account.account().unwrap().storage_usage > account_storage);
+
+    // unregistering the account drops its key, leaving no storage behind
+    call!(
+        user,
+        pool.storage_unregister(None),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(view!(pool.get_intent_signer(user.valid_account_id())).unwrap_json::<Option<IntentSignerInfo>>().is_none());
+    assert_eq!(pool.user_account.account().unwrap().storage_usage, contract_storage);
+}
@@ -7,7 +7,7 @@ use near_sdk_sim::{
     call, view, to_yocto
 };
 
-use ref_exchange::{PoolInfo, SwapAction};
+use ref_exchange::{PoolInfo, SwapAction, ImbalancePenalty, VirtualPriceInfo};
 use crate::common::utils::*;
 pub mod common;
 
@@ -74,9 +74,11 @@ fn sim_stable_swap() {
                 token_in: dai(),
                 amount_in: Some(U128(ONE_DAI)),
                 token_out: usdc(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -92,9 +94,11 @@ fn sim_stable_swap() {
                 token_in: dai(),
                 amount_in: Some(U128(ONE_DAI)),
                 token_out: usdt(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -472,4 +476,344 @@ fn sim_stable_lp_storage() {
     let sb = get_storage_balance(&pool, user.valid_account_id()).unwrap();
     assert_eq!(sb.total.0, to_yocto("0.00546"));
     assert_eq!(sb.available.0, 0);
-}
\ No newline at end of file
+}
+#[test]
+fn sim_stable_imbalance_penalty() {
+    let (root, owner, pool, tokens) = 
+        setup_stable_pool_with_liquidity(
+            vec![dai(), usdt(), usdc()],
+            vec![100000*ONE_DAI, 100000*ONE_USDT, 100000*ONE_USDC],
+            vec![18, 6, 6],
+            25,
+            10000,
+        );
+    let balanced_shares = view!(pool.predict_add_stable_liquidity(0, &vec![U128(100*ONE_DAI), U128(100*ONE_USDT), U128(100*ONE_USDC)])).unwrap_json::<U128>().0;
+    let one_side_shares = view!(pool.predict_add_stable_liquidity(0, &vec![U128(100000*ONE_DAI), U128(0), U128(0)])).unwrap_json::<U128>().0;
+
+    let out_come = call!(
+        root,
+        pool.set_imbalance_penalty(0, ImbalancePenalty { band_bps: 1000, multiplier_bps: 50000 }),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E100: no permission to invoke this"));
+
+    let out_come = call!(
+        owner,
+        pool.set_imbalance_penalty(0, ImbalancePenalty { band_bps: 1000, multiplier_bps: 9999 }),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E76: invalid params"));
+
+    call!(
+        owner,
+        pool.set_imbalance_penalty(0, ImbalancePenalty { band_bps: 1000, multiplier_bps: 50000 }),
+        deposit = 1
+    ).assert_success();
+    assert_eq!(view!(pool.get_imbalance_penalty(0)).unwrap_json::<Option<ImbalancePenalty>>().unwrap().multiplier_bps, 50000);
+
+    // balanced deposit stays in band
+    assert_eq!(balanced_shares, view!(pool.predict_add_stable_liquidity(0, &vec![U128(100*ONE_DAI), U128(100*ONE_USDT), U128(100*ONE_USDC)])).unwrap_json::<U128>().0);
+    // one side deposit pushes dai out of band
+    let penalized_shares = view!(pool.predict_add_stable_liquidity(0, &vec![U128(100000*ONE_DAI), U128(0), U128(0)])).unwrap_json::<U128>().0;
+    assert!(penalized_shares < one_side_shares);
+
+    let c = tokens.get(0).unwrap();
+    call!(
+        root,
+        c.ft_transfer_call(pool.valid_account_id(), U128(100000*ONE_DAI), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    let out_come = call!(
+        root,
+        pool.add_stable_liquidity(0, vec![U128(100000*ONE_DAI), U128(0), U128(0)], U128(1)),
+        deposit = to_yocto("0.0007")
+    );
+    out_come.assert_success();
+    assert_eq!(out_come.unwrap_json::<U128>().0, penalized_shares);
+
+    call!(
+        owner,
+        pool.remove_imbalance_penalty(0),
+        deposit = 1
+    ).assert_success();
+    assert!(view!(pool.get_imbalance_penalty(0)).unwrap_json::<Option<ImbalancePenalty>>().is_none());
+}
+
+#[test]
+fn sim_stable_virtual_price_record() {
+    let (root, owner, pool, tokens) = 
+        setup_stable_pool_with_liquidity(
+            vec![dai(), usdt(), usdc()],
+            vec![100000*ONE_DAI, 100000*ONE_USDT, 100000*ONE_USDC],
+            vec![18, 6, 6],
+            25,
+            10000,
+        );
+    call!(
+        owner,
+        pool.register_virtual_price_record(0, 0),
+        deposit = 1
+    ).assert_success();
+    let info = view!(pool.get_virtual_price_info(0)).unwrap_json::<Option<VirtualPriceInfo>>().unwrap();
+    assert_eq!(info.records.len(), 1);
+    assert_eq!(info.records[0].virtual_price, ONE_LPT);
+
+    let c = tokens.get(0).unwrap();
+    call!(
+        root,
+        c.ft_transfer_call(pool.valid_account_id(), U128(1000 * ONE_DAI), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        root,
+        pool.swap(
+            vec![SwapAction {
+                pool_id: 0,
+                token_in: dai(),
+                amount_in: Some(U128(1000 * ONE_DAI)),
+                token_out: usdc(),
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
+            }],
+            None,
+            None,
+            None
+        ),
+        deposit = 1
+    ).assert_success();
+
+    // swap fee makes virtual price grow
+    let info = view!(pool.get_virtual_price_info(0)).unwrap_json::<Option<VirtualPriceInfo>>().unwrap();
+    assert_eq!(info.records.len(), 2);
+    assert!(info.records[1].virtual_price > info.records[0].virtual_price);
+
+    // remove liquidity by shares also records
+    call!(
+        root,
+        pool.remove_liquidity(0, U128(ONE_LPT), vec![U128(1), U128(1), U128(1)]),
+        deposit = 1
+    ).assert_success();
+    let info = view!(pool.get_virtual_price_info(0)).unwrap_json::<Option<VirtualPriceInfo>>().unwrap();
+    assert_eq!(info.records.len(), 3);
+
+    // only owner or guardians can sync
+    let out_come = call!(
+        root,
+        pool.sync_virtual_price_record(0),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E100: no permission to invoke this"));
+    call!(
+        owner,
+        pool.sync_virtual_price_record(0),
+        deposit = 1
+    ).assert_success();
+    let info = view!(pool.get_virtual_price_info(0)).unwrap_json::<Option<VirtualPriceInfo>>().unwrap();
+    assert_eq!(info.records.len(), 4);
+
+    call!(
+        owner,
+        pool.unregister_virtual_price_record(0),
+        deposit = 1
+    ).assert_success();
+    assert!(view!(pool.get_virtual_price_info(0)).unwrap_json::<Option<VirtualPriceInfo>>().is_none());
+}
+
+#[test]
+fn sim_stable_add_and_deprecate_token() {
+    let (root, owner, pool, tokens) = 
+        setup_stable_pool_with_liquidity(
+            vec![dai(), usdt()],
+            vec![100000*ONE_DAI, 100000*ONE_USDT],
+            vec![18, 6],
+            25,
+            10000,
+        );
+    let token_usdc = test_token(&root, usdc(), vec![swap()]);
+    whitelist_token(&owner, &pool, vec![token_usdc.valid_account_id()]);
+    mint_and_deposit_token(&owner, &token_usdc, &pool, 100000*ONE_USDC);
+
+    let out_come = call!(
+        root,
+        pool.add_pool_token(0, token_usdc.valid_account_id(), 6, U128(100000*ONE_USDC)),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E100: no permission to invoke this"));
+
+    let out_come = call!(
+        owner,
+        pool.add_pool_token(0, token_usdc.valid_account_id(), 6, U128(100000*ONE_USDC)),
+        deposit = to_yocto("0.01")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("Requires attached deposit of exactly 1 yoctoNEAR"));
+
+    let out_come = call!(
+        owner,
+        pool.add_pool_token(0, to_va(dai()), 18, U128(1)),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E92: token duplicated"));
+
+    let out_come = call!(
+        owner,
+        pool.add_pool_token(0, token_usdc.valid_account_id(), 6, U128(100000*ONE_USDC)),
+        deposit = 1
+    );
+    out_come.assert_success();
+    let new_shares = out_come.unwrap_json::<U128>().0;
+    assert!(new_shares > 0);
+    assert_eq!(mft_balance_of(&pool, ":0", &owner.account_id()), new_shares);
+    let pool_info = view!(pool.get_pool(0)).unwrap_json::<PoolInfo>();
+    assert_eq!(pool_info.token_account_ids, vec![dai(), usdt(), usdc()]);
+    assert_eq!(pool_info.amounts, vec![U128(100000*ONE_DAI), U128(100000*ONE_USDT), U128(100000*ONE_USDC)]);
+    assert_eq!(pool_info.shares_total_supply.0, 200000*ONE_LPT + new_shares);
+    assert_eq!(get_deposits(&pool, owner.valid_account_id()).get(&usdc()).map(|v| v.0).unwrap_or(0), 0);
+
+    call!(
+        owner,
+        pool.deprecate_pool_token(0, to_va(usdt())),
+        deposit = 1
+    ).assert_success();
+    assert_eq!(view!(pool.get_pool_deprecated_tokens(0)).unwrap_json::<Vec<AccountId>>(), vec![usdt()]);
+
+    // deprecated token can not be swapped in
+    let c = tokens.get(1).unwrap();
+    call!(
+        root,
+        c.ft_transfer_call(pool.valid_account_id(), U128(100*ONE_USDT), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    let out_come = call!(
+        root,
+        pool.swap(
+            vec![SwapAction {
+                pool_id: 0,
+                token_in: usdt(),
+                amount_in: Some(U128(100*ONE_USDT)),
+                token_out: dai(),
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
+            }],
+            None,
+            None,
+            None
+        ),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E93: token deprecated in pool"));
+
+    // nor be added as liquidity
+    let out_come = call!(
+        root,
+        pool.add_stable_liquidity(0, vec![U128(0), U128(100*ONE_USDT), U128(0)], U128(1)),
+        deposit = to_yocto("0.0007")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E93: token deprecated in pool"));
+
+    // but LPs can still exit from it
+    call!(
+        root,
+        pool.remove_liquidity_by_tokens(0, vec![U128(0), U128(50000*ONE_USDT), U128(0)], U128(60000*ONE_LPT)),
+        deposit = 1
+    ).assert_success();
+    let pool_info = view!(pool.get_pool(0)).unwrap_json::<PoolInfo>();
+    assert_eq!(pool_info.amounts[1].0, 50000*ONE_USDT);
+
+    let out_come = call!(
+        owner,
+        pool.undeprecate_pool_token(0, to_va(usdt())),
+        deposit = 1
+    );
+    out_come.assert_success();
+    assert!(view!(pool.get_pool_deprecated_tokens(0)).unwrap_json::<Vec<AccountId>>().is_empty());
+}
+
+#[test]
+fn sim_stable_remove_pool_token() {
+    let (root, owner, pool, _) = 
+        setup_stable_pool_with_liquidity(
+            vec![dai(), usdt()],
+            vec![100000*ONE_DAI, 100000*ONE_USDT],
+            vec![18, 6],
+            25,
+            10000,
+        );
+    let token_usdc = test_token(&root, usdc(), vec![swap()]);
+    whitelist_token(&owner, &pool, vec![token_usdc.valid_account_id()]);
+    mint_and_deposit_token(&owner, &token_usdc, &pool, 100000*ONE_USDC);
+
+    // seeded with dust
+    call!(
+        owner,
+        pool.add_pool_token(0, token_usdc.valid_account_id(), 6, U128(1500)),
+        deposit = 1
+    ).assert_success();
+
+    let out_come = call!(
+        owner,
+        pool.remove_pool_token(0, token_usdc.valid_account_id()),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E63: missing token"));
+
+    call!(
+        owner,
+        pool.deprecate_pool_token(0, token_usdc.valid_account_id()),
+        deposit = 1
+    ).assert_success();
+
+    let out_come = call!(
+        root,
+        pool.remove_pool_token(0, token_usdc.valid_account_id()),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E100: no permission to invoke this"));
+
+    let out_come = call!(
+        owner,
+        pool.remove_pool_token(0, token_usdc.valid_account_id()),
+        deposit = 0
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("Requires attached deposit of exactly 1 yoctoNEAR"));
+
+    let out_come = call!(
+        owner,
+        pool.remove_pool_token(0, token_usdc.valid_account_id()),
+        deposit = 1
+    );
+    out_come.assert_success();
+    assert_eq!(out_come.unwrap_json::<U128>().0, 1500);
+    let pool_info = view!(pool.get_pool(0)).unwrap_json::<PoolInfo>();
+    assert_eq!(pool_info.token_account_ids, vec![dai(), usdt()]);
+    assert_eq!(pool_info.amounts, vec![U128(100000*ONE_DAI), U128(100000*ONE_USDT)]);
+    assert!(view!(pool.get_pool_deprecated_tokens(0)).unwrap_json::<Vec<AccountId>>().is_empty());
+    assert_eq!(get_lostfound_token(&pool, owner.valid_account_id(), token_usdc.valid_account_id()), 1500);
+
+    // a pool keeps at least two tokens
+    call!(
+        owner,
+        pool.deprecate_pool_token(0, to_va(dai())),
+        deposit = 1
+    ).assert_success();
+    let out_come = call!(
+        owner,
+        pool.remove_pool_token(0, to_va(dai())),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E89: wrong token count"));
+}
@@ -13,10 +13,10 @@
 ///   The contract self would be registered by pool creator 
 ///     when [add_simple_pool] and [add_stable_swap_pool], 
 ///     suggested deposit amount is 0.01, unused part would refund
-use near_sdk::json_types::{U128};
-use near_sdk_sim::{call, to_yocto};
+use near_sdk::json_types::{U128, U64};
+use near_sdk_sim::{call, view, to_yocto};
 
-use ref_exchange::SwapAction;
+use ref_exchange::{SwapAction, StorageSponsorInfo, SponsoredAccountInfo, StorageReport};
 use crate::common::utils::*;
 pub mod common;
 
@@ -199,9 +199,11 @@ fn storage_scenario_02() {
                 token_in: dai(),
                 amount_in: Some(U128(ONE_DAI)),
                 token_out: usdc(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -233,9 +235,11 @@ fn storage_scenario_02() {
                 token_in: dai(),
                 amount_in: Some(U128(ONE_DAI)),
                 token_out: usdc(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -263,9 +267,11 @@ fn storage_scenario_02() {
                 token_in: dai(),
                 amount_in: Some(U128(ONE_DAI)),
                 token_out: usdc(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -326,3 +332,197 @@ fn storage_scenario_02() {
     assert_eq!(ss.deposit.0, to_yocto("0.00546"));
     assert_eq!(ss.usage.0, to_yocto("0.00546"));
 }
+
+#[test]
+fn storage_scenario_03_sponsor() {
+    let (root, _, pool, _, _, _) = setup_pool_with_liquidity();
+    let sponsor = root.create_user("sponsor".to_string(), to_yocto("100"));
+    let new_user = root.create_user("new_user".to_string(), to_yocto("100"));
+
+    println!("Storage Case 0301: register without sponsor budget");
+    let out_come = call!(
+        sponsor,
+        pool.storage_deposit_sponsored(new_user.valid_account_id())
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E150: storage sponsor not found"));
+
+    println!("Storage Case 0302: register from sponsor budget");
+    call!(
+        sponsor,
+        pool.sponsor_storage_deposit(None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let budget = view!(pool.get_storage_sponsor(sponsor.valid_account_id())).unwrap_json::<Option<StorageSponsorInfo>>().unwrap();
+    assert!(budget.balance.0 < to_yocto("1") && budget.balance.0 > to_yocto("0.99"));
+    let orig_user_balance = new_user.account().unwrap().amount;
+    call!(
+        sponsor,
+        pool.storage_deposit_sponsored(new_user.valid_account_id())
+    )
+    .assert_success();
+    let sb = get_storage_balance(&pool, new_user.valid_account_id()).unwrap();
+    assert_eq!(sb.total.0, to_yocto("0.00102"));
+    let info = view!(pool.get_storage_sponsor(sponsor.valid_account_id())).unwrap_json::<Option<StorageSponsorInfo>>().unwrap();
+    assert_eq!(info.sponsored_count, 1);
+    assert!(budget.balance.0 - info.balance.0 > to_yocto("0.00102"));
+    let sponsored = view!(pool.list_sponsored_accounts(Some(sponsor.valid_account_id()), None, None)).unwrap_json::<Vec<SponsoredAccountInfo>>();
+    assert_eq!(sponsored.len(), 1);
+    assert_eq!(sponsored[0].account_id, new_user.account_id());
+    assert_eq!(sponsored[0].amount.0, to_yocto("0.00102"));
+
+    let out_come = call!(
+        sponsor,
+        pool.storage_deposit_sponsored(new_user.valid_account_id())
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E152: account already registered"));
+
+    println!("Storage Case 0303: unregister refunds the sponsor");
+    call!(
+        new_user,
+        pool.storage_unregister(None),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(get_storage_balance(&pool, new_user.valid_account_id()).is_none());
+    assert!(new_user.account().unwrap().amount <= orig_user_balance);
+    let info = view!(pool.get_storage_sponsor(sponsor.valid_account_id())).unwrap_json::<Option<StorageSponsorInfo>>().unwrap();
+    assert_eq!(info.sponsored_count, 0);
+    assert_eq!(info.balance.0, budget.balance.0);
+    assert!(view!(pool.get_account_sponsor(new_user.valid_account_id())).unwrap_json::<Option<SponsoredAccountInfo>>().is_none());
+
+    println!("Storage Case 0304: sponsor withdraws all");
+    call!(
+        sponsor,
+        pool.sponsor_storage_withdraw(None),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(view!(pool.get_storage_sponsor(sponsor.valid_account_id())).unwrap_json::<Option<StorageSponsorInfo>>().is_none());
+}
+
+#[test]
+fn storage_scenario_04_report() {
+    let (root, owner, pool, _, _, _) = setup_pool_with_liquidity();
+    let new_user = root.create_user("new_user".to_string(), to_yocto("100"));
+
+    println!("Storage Case 0401: counters of a fresh contract");
+    let report = view!(pool.get_contract_storage_report()).unwrap_json::<StorageReport>();
+    assert_eq!(report.account_count.0, 2);
+    assert_eq!(report.pool_count.0, 3);
+    assert_eq!(report.counted_pool_count.0, 3);
+    assert_eq!(report.pool_counts.get("SIMPLE_POOL").unwrap().0, 3);
+    assert_eq!(report.swap_volume_count.0, 3);
+    assert!(report.swap_volume_bytes.0 > 0);
+    assert_eq!(report.rated_registry_bytes.0, 0);
+    assert_eq!(report.lostfound_entry_count.0, 0);
+    assert_eq!(report.twap_record_count.0, 0);
+    assert_eq!(report.pool_limit_count.0, 0);
+    let swap_volume_bytes = report.swap_volume_bytes.0;
+
+    println!("Storage Case 0402: swaps don't change the swap volume size");
+    call!(
+        root,
+        pool.swap(
+            vec![SwapAction {
+                pool_id: 0,
+                token_in: dai(),
+                amount_in: Some(U128(ONE_DAI)),
+                token_out: eth(),
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
+            }],
+            None,
+            None,
+            None
+        ),
+        deposit = 1
+    )
+    .assert_success();
+    let report = view!(pool.get_contract_storage_report()).unwrap_json::<StorageReport>();
+    assert_eq!(report.swap_volume_bytes.0, swap_volume_bytes);
+
+    println!("Storage Case 0403: register and unregister");
+    call!(
+        new_user,
+        pool.storage_deposit(None, Some(true)),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let report = view!(pool.get_contract_storage_report()).unwrap_json::<StorageReport>();
+    assert_eq!(report.account_count.0, 3);
+    call!(
+        new_user,
+        pool.storage_unregister(None),
+        deposit = 1
+    )
+    .assert_success();
+    let report = view!(pool.get_contract_storage_report()).unwrap_json::<StorageReport>();
+    assert_eq!(report.account_count.0, 2);
+
+    println!("Storage Case 0404: only owner can sync");
+    let out_come = call!(
+        root,
+        pool.sync_storage_report(10, None, None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E100: no permission to invoke this"));
+    call!(
+        owner,
+        pool.sync_storage_report(10, Some(U64(3)), None),
+        deposit = 1
+    )
+    .assert_success();
+    let report = view!(pool.get_contract_storage_report()).unwrap_json::<StorageReport>();
+    assert_eq!(report.account_count.0, 5);
+    assert_eq!(report.lostfound_entry_count.0, 0);
+    assert_eq!(report.counted_pool_count.0, 3);
+    assert_eq!(report.swap_volume_count.0, 3);
+    assert_eq!(report.swap_volume_bytes.0, swap_volume_bytes);
+
+    println!("Storage Case 0405: legacy totals are seeded only once");
+    let out_come = call!(
+        owner,
+        pool.sync_storage_report(10, Some(U64(4)), None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E106: storage report already seeded"));
+    call!(
+        owner,
+        pool.sync_storage_report(10, None, Some(U64(2))),
+        deposit = 1
+    )
+    .assert_success();
+    let out_come = call!(
+        owner,
+        pool.sync_storage_report(10, None, Some(U64(2))),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E106: storage report already seeded"));
+    let report = view!(pool.get_contract_storage_report()).unwrap_json::<StorageReport>();
+    assert_eq!(report.account_count.0, 5);
+    assert_eq!(report.lostfound_entry_count.0, 2);
+
+    println!("Storage Case 0406: counting goes on after seeding");
+    call!(
+        new_user,
+        pool.storage_deposit(None, Some(true)),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let report = view!(pool.get_contract_storage_report()).unwrap_json::<StorageReport>();
+    assert_eq!(report.account_count.0, 6);
+    call!(
+        new_user,
+        pool.storage_unregister(None),
+        deposit = 1
+    )
+    .assert_success();
+    let report = view!(pool.get_contract_storage_report()).unwrap_json::<StorageReport>();
+    assert_eq!(report.account_count.0, 5);
+}
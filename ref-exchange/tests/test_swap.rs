@@ -2,14 +2,14 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
-use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::json_types::{ValidAccountId, U128, U64};
 use near_sdk::AccountId;
 use near_sdk_sim::transaction::ExecutionStatus;
 use near_sdk_sim::{
     call, deploy, init_simulator, to_yocto, view, ContractAccount, ExecutionResult, UserAccount,
 };
 
-use ref_exchange::{Action, ContractContract as Exchange, PoolInfo, SwapAction, SwapVolumeU256View};
+use ref_exchange::{Action, ContractContract as Exchange, PoolInfo, SwapAction, SwapByOutputAction, SwapVolumeU256View};
 use test_token::ContractContract as TestToken;
 use mock_wnear::ContractContract as MockWnear;
 
@@ -238,9 +238,11 @@ fn test_swap() {
                 token_in: dai(),
                 amount_in: Some(U128(to_yocto("1"))),
                 token_out: eth(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             }],
             None,
+            None,
             None
         ),
         deposit = 1
@@ -276,6 +278,165 @@ fn test_swap() {
     assert_eq!(balance2, to_yocto("991"));
 }
 
+#[test]
+fn test_swap_deadline_and_price_impact() {
+    let (root, _owner, pool, token1, _token2) = setup_pool_with_liquidity();
+    let swap_action = |max_price_impact_bps: Option<u32>| SwapAction {
+        pool_id: 0,
+        token_in: dai(),
+        amount_in: Some(U128(to_yocto("1"))),
+        token_out: eth(),
+        min_amount_out: U128(1),
+        max_price_impact_bps,
+    };
+
+    let out_come = call!(
+        root,
+        pool.swap(vec![swap_action(None)], None, None, Some(U64(1))),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E79: deadline exceeded"));
+
+    let msg = format!(
+        "{{\"actions\": [{{\"pool_id\": 0, \"token_in\": \"{}\", \"token_out\": \"{}\", \"min_amount_out\": \"1\"}}], \"deadline_ns\": \"1\"}}",
+        dai(), eth()
+    );
+    let out_come = call!(
+        root,
+        token1.ft_transfer_call(to_va(swap()), to_yocto("1").into(), None, msg),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E79: deadline exceeded"));
+
+    // 1 dai into a 5 dai / 10 eth pool moves the price by about 30%
+    let out_come = call!(
+        root,
+        pool.swap(vec![swap_action(Some(2000))], None, None, None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E78: price impact exceeds limit"));
+    assert_eq!(
+        view!(pool.get_pool(0)).unwrap_json::<PoolInfo>().amounts,
+        vec![to_yocto("5").into(), to_yocto("10").into()]
+    );
+
+    let deadline_ns = root.borrow_runtime().current_block().block_timestamp + 10u64.pow(12);
+    call!(
+        root,
+        pool.swap(vec![swap_action(Some(4000))], None, None, Some(U64(deadline_ns))),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(
+        view!(pool.get_pool(0)).unwrap_json::<PoolInfo>().amounts,
+        vec![to_yocto("6").into(), (to_yocto("10") - 1663192997082117548978741).into()]
+    );
+}
+
+#[test]
+fn test_swap_by_output_deadline_and_price_impact() {
+    let (root, _owner, pool, _token1, _token2) = setup_pool_with_liquidity();
+    let swap_by_output_action = |max_price_impact_bps: Option<u32>| SwapByOutputAction {
+        pool_id: 0,
+        token_in: dai(),
+        amount_out: Some(U128(to_yocto("1"))),
+        token_out: eth(),
+        max_amount_in: None,
+        max_price_impact_bps,
+    };
+
+    let out_come = call!(
+        root,
+        pool.swap_by_output(vec![swap_by_output_action(None)], None, None, Some(U64(1))),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E79: deadline exceeded"));
+
+    // taking 1 eth out of a 5 dai / 10 eth pool moves the price by about 19%
+    let out_come = call!(
+        root,
+        pool.swap_by_output(vec![swap_by_output_action(Some(1000))], None, None, None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E78: price impact exceeds limit"));
+
+    // quotes through predict_swap_actions apply the same limit
+    let mut token_deposit = HashMap::new();
+    token_deposit.insert(dai(), U128(to_yocto("1")));
+    assert!(view!(pool.predict_swap_actions(
+        token_deposit.clone(),
+        vec![Action::SwapByOutput(swap_by_output_action(Some(1000)))]
+    )).is_err());
+    assert!(view!(pool.predict_swap_actions(
+        token_deposit.clone(),
+        vec![Action::Swap(SwapAction {
+            pool_id: 0,
+            token_in: dai(),
+            amount_in: Some(U128(to_yocto("1"))),
+            token_out: eth(),
+            min_amount_out: U128(1),
+            max_price_impact_bps: Some(2000),
+        })]
+    )).is_err());
+    let predicted = view!(pool.predict_swap_actions(
+        token_deposit,
+        vec![Action::SwapByOutput(swap_by_output_action(Some(3000)))]
+    )).unwrap_json::<HashMap<AccountId, U128>>();
+    assert_eq!(predicted.get(&eth()).unwrap().0, to_yocto("1"));
+    let amount_in = to_yocto("1") - predicted.get(&dai()).unwrap().0;
+
+    let deadline_ns = root.borrow_runtime().current_block().block_timestamp + 10u64.pow(12);
+    call!(
+        root,
+        pool.swap_by_output(vec![swap_by_output_action(Some(3000))], None, None, Some(U64(deadline_ns))),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(
+        view!(pool.get_pool(0)).unwrap_json::<PoolInfo>().amounts,
+        vec![(to_yocto("5") + amount_in).into(), to_yocto("9").into()]
+    );
+}
+
+#[test]
+fn test_small_swap_with_price_impact_limit() {
+    let (root, _owner, pool, _token1, _token2) = setup_pool_with_liquidity();
+    let small_swap_action = |amount_in: u128| SwapAction {
+        pool_id: 0,
+        token_in: eth(),
+        amount_in: Some(U128(amount_in)),
+        token_out: dai(),
+        min_amount_out: U128(1),
+        max_price_impact_bps: Some(10),
+    };
+
+    // a 1/1000 probe of 500 yocto eth gets no dai out of a 5 dai / 10 eth pool,
+    // the impact is measured with the whole amount instead
+    let eth_before = get_deposits(&pool, root.valid_account_id()).get(&eth()).unwrap().0;
+    let out_come = call!(
+        root,
+        pool.swap(vec![small_swap_action(500)], None, None, None),
+        deposit = 1
+    );
+    out_come.assert_success();
+    assert!(out_come.unwrap_json::<U128>().0 > 0);
+    assert_eq!(get_deposits(&pool, root.valid_account_id()).get(&eth()).unwrap().0, eth_before - 500);
+
+    // large swaps are still checked
+    let out_come = call!(
+        root,
+        pool.swap(vec![small_swap_action(to_yocto("1"))], None, None, None),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E78: price impact exceeds limit"));
+}
+
 #[test]
 fn test_withdraw_failure() {
     let root = init_simulator(None);
@@ -905,7 +1066,8 @@ fn test_execute_actions_in_va() {
                 token_in: usdt(),
                 amount_in: Some(U128(ONE_USDT * 3000)),
                 token_out: eth(),
-                min_amount_out: U128(1)
+                min_amount_out: U128(1),
+                max_price_impact_bps: None,
             })],
             None,
             None
@@ -1268,3 +1430,394 @@ fn test_simple_swap_volume_overflow() {
     assert_eq!("339601652951602449070900047531528578712".to_string(), sv_u256[1].input);
     assert_eq!("1994".to_string(), sv_u256[1].output);
 }
+
+#[test]
+fn test_auto_storage_topup_from_wnear() {
+    let root = init_simulator(None);
+    let owner = root.create_user("owner".to_string(), to_yocto("100"));
+    let pool = deploy!(
+        contract: Exchange,
+        contract_id: swap(),
+        bytes: &EXCHANGE_WASM_BYTES,
+        signer_account: root,
+        init_method: new(to_va("owner".to_string()), to_va("boost_farm".to_string()), to_va("burrowland".to_string()), 5, 0)
+    );
+    call!(
+        owner,
+        pool.modify_wnear_id(wnear()),
+        deposit = 1
+    )
+    .assert_success();
+    let _token1 = test_token(&root, dai(), vec![swap()]);
+    let token2 = test_wnear(&root, vec![swap()]);
+    call!(
+        owner,
+        pool.extend_whitelisted_tokens(vec![to_va(dai()), to_va(wnear())]),
+        deposit=1
+    );
+
+    // room for one token only, which is wnear
+    let new_user = root.create_user("new_user".to_string(), to_yocto("100"));
+    call!(
+        new_user,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("0.0025")
+    )
+    .assert_success();
+    call!(
+        new_user,
+        token2.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        new_user,
+        token2.near_deposit(),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        new_user,
+        token2.ft_transfer_call(to_va(swap()), to_yocto("1").into(), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+
+    // without auto top-up, account storage is only credited by an explicit top-up, after the unwrapping
+    let out_come = call!(
+        new_user,
+        pool.register_tokens(vec![to_va(dai())]),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E11: insufficient $NEAR storage deposit"));
+
+    let sb_before = get_storage_balance(&pool, new_user.valid_account_id()).unwrap();
+    call!(
+        new_user,
+        pool.storage_topup_with_wnear(U128(to_yocto("0.00148"))),
+        deposit = 1
+    )
+    .assert_success();
+    let sb_after = get_storage_balance(&pool, new_user.valid_account_id()).unwrap();
+    assert_eq!(sb_after.total.0 - sb_before.total.0, to_yocto("0.00148"));
+    call!(
+        new_user,
+        pool.register_tokens(vec![to_va(dai())]),
+        deposit = 1
+    )
+    .assert_success();
+    let sb_after = get_storage_balance(&pool, new_user.valid_account_id()).unwrap();
+    assert_eq!(sb_after.available.0, 0);
+    let deposits = get_deposits(&pool, new_user.valid_account_id());
+    assert_eq!(deposits.get(&wnear()).unwrap().0, to_yocto("1") - to_yocto("0.00148"));
+    assert_eq!(deposits.get(&dai()).unwrap().0, 0);
+    // the wnear has been unwrapped into the exchange
+    assert_eq!(wnear_balance_of(&token2, &swap()), to_yocto("1") - to_yocto("0.00148"));
+
+    // storage of a call short of attached deposit
+    let out_come = call!(
+        new_user,
+        pool.add_simple_pool(vec![to_va(dai()), to_va(wnear())], 25),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("ERR_STORAGE_DEPOSIT"));
+
+    call!(
+        new_user,
+        pool.set_auto_storage_topup(true),
+        deposit = 1
+    )
+    .assert_success();
+    assert!(view!(pool.get_auto_storage_topup(new_user.valid_account_id())).unwrap_json::<bool>());
+
+    // shortage beyond the cap
+    call!(
+        owner,
+        pool.modify_storage_topup_cap(U128(to_yocto("0.0001"))),
+        deposit = 1
+    )
+    .assert_success();
+    let out_come = call!(
+        new_user,
+        pool.add_simple_pool(vec![to_va(dai()), to_va(wnear())], 25),
+        deposit = 1
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("ERR_STORAGE_DEPOSIT"));
+
+    call!(
+        owner,
+        pool.modify_storage_topup_cap(U128(to_yocto("0.1"))),
+        deposit = 1
+    )
+    .assert_success();
+    assert_eq!(view!(pool.get_storage_topup_cap()).unwrap_json::<U128>().0, to_yocto("0.1"));
+    let sb_before = get_storage_balance(&pool, new_user.valid_account_id()).unwrap();
+    call!(
+        new_user,
+        pool.add_simple_pool(vec![to_va(dai()), to_va(wnear())], 25),
+        deposit = 1
+    )
+    .assert_success();
+    // paid from wnear, not from the account storage
+    let sb_after = get_storage_balance(&pool, new_user.valid_account_id()).unwrap();
+    assert_eq!(sb_after.total.0, sb_before.total.0);
+    let wnear_left = get_deposits(&pool, new_user.valid_account_id()).get(&wnear()).unwrap().0;
+    assert!(wnear_left < to_yocto("1") - to_yocto("0.00148"));
+    assert_eq!(wnear_balance_of(&token2, &swap()), wnear_left);
+
+    // token registration short of account storage deposit
+    let sb_before = get_storage_balance(&pool, new_user.valid_account_id()).unwrap();
+    assert_eq!(sb_before.available.0, 0);
+    call!(
+        new_user,
+        pool.register_tokens(vec![to_va(eth())]),
+        deposit = 1
+    )
+    .assert_success();
+    let sb_after = get_storage_balance(&pool, new_user.valid_account_id()).unwrap();
+    assert_eq!(sb_after.total.0 - sb_before.total.0, to_yocto("0.00148"));
+    assert_eq!(sb_after.available.0, 0);
+    let deposits = get_deposits(&pool, new_user.valid_account_id());
+    assert_eq!(deposits.get(&wnear()).unwrap().0, wnear_left - to_yocto("0.00148"));
+    assert_eq!(deposits.get(&eth()).unwrap().0, 0);
+    assert_eq!(wnear_balance_of(&token2, &swap()), wnear_left - to_yocto("0.00148"));
+}
+
+#[test]
+fn test_near_deposit_and_execute() {
+    let root = init_simulator(None);
+    let owner = root.create_user("owner".to_string(), to_yocto("100"));
+    let pool = deploy!(
+        contract: Exchange,
+        contract_id: swap(),
+        bytes: &EXCHANGE_WASM_BYTES,
+        signer_account: root,
+        init_method: new(to_va("owner".to_string()), to_va("boost_farm".to_string()), to_va("burrowland".to_string()), 5, 0)
+    );
+    let token1 = test_token(&root, dai(), vec![swap()]);
+    let token2 = test_wnear(&root, vec![swap()]);
+    call!(
+        owner,
+        pool.extend_whitelisted_tokens(vec![to_va(dai()), to_va(wnear())]),
+        deposit=1
+    );
+    call!(
+        root,
+        pool.add_simple_pool(vec![to_va(dai()), to_va(wnear())], 25),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        root,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(
+        root,
+        token1.ft_transfer_call(to_va(swap()), to_yocto("105").into(), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        root,
+        token2.ft_transfer_call(to_va(swap()), to_yocto("110").into(), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        root,
+        pool.add_liquidity(0, vec![U128(to_yocto("5")), U128(to_yocto("10"))], None),
+        deposit = to_yocto("0.0007")
+    )
+    .assert_success();
+
+    let new_user = root.create_user("new_user".to_string(), to_yocto("100"));
+    call!(
+        new_user,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let swap_wnear_to_dai = |min_amount_out: u128| vec![Action::Swap(SwapAction {
+        pool_id: 0,
+        token_in: wnear(),
+        amount_in: Some(U128(to_yocto("1"))),
+        token_out: dai(),
+        min_amount_out: U128(min_amount_out),
+        max_price_impact_bps: None,
+    })];
+
+    // wnear_id not set
+    let out_come = call!(
+        new_user,
+        pool.near_deposit_and_execute(swap_wnear_to_dai(1), None, None, None),
+        deposit = to_yocto("1")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E30: wnear id not set"));
+
+    call!(
+        owner,
+        pool.modify_wnear_id(wnear()),
+        deposit = 1
+    )
+    .assert_success();
+
+    // not enough gas left for the callback
+    let out_come = call!(
+        new_user,
+        pool.near_deposit_and_execute(swap_wnear_to_dai(1), None, None, None),
+        deposit = to_yocto("1"),
+        gas = 50000000000000
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E37: not enough prepaid gas"));
+    assert_eq!(wnear_balance_of(&token2, &swap()), to_yocto("110"));
+
+    call!(
+        new_user,
+        pool.near_deposit_and_execute(swap_wnear_to_dai(1), None, None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    let deposits = get_deposits(&pool, new_user.valid_account_id());
+    assert_eq!(deposits.get(&wnear()).unwrap().0, 0);
+    let dai_amount = deposits.get(&dai()).unwrap().0;
+    assert!(dai_amount > 0);
+    assert_eq!(wnear_balance_of(&token2, &swap()), to_yocto("111"));
+
+    // actions fail on slippage, the wrapped near stays in deposit
+    let out_come = call!(
+        new_user,
+        pool.near_deposit_and_execute(swap_wnear_to_dai(to_yocto("100")), None, None, None),
+        deposit = to_yocto("1")
+    );
+    assert_eq!(get_error_count(&out_come), 1);
+    assert!(get_error_status(&out_come).contains("E68: slippage error"));
+    let deposits = get_deposits(&pool, new_user.valid_account_id());
+    assert_eq!(deposits.get(&wnear()).unwrap().0, to_yocto("1"));
+    assert_eq!(deposits.get(&dai()).unwrap().0, dai_amount);
+
+    let new_user_near_balance = new_user.account().unwrap().amount;
+    let out_come = call!(
+        new_user,
+        pool.withdraw_batch(vec![(to_va(wnear()), U128(to_yocto("0.5")))], None, Some(false)),
+        deposit = 1
+    );
+    out_come.assert_success();
+    assert_eq!(out_come.unwrap_json::<Vec<U128>>(), vec![U128(to_yocto("0.5"))]);
+    assert!(new_user.account().unwrap().amount > new_user_near_balance + to_yocto("0.49"));
+    assert_eq!(wnear_balance_of(&token2, &swap()), to_yocto("111.5"));
+    call!(
+        new_user,
+        pool.withdraw_near(U128(0), None),
+        deposit = 1
+    )
+    .assert_success();
+    let deposits = get_deposits(&pool, new_user.valid_account_id());
+    assert_eq!(deposits.get(&wnear()).unwrap().0, 0);
+    assert!(new_user.account().unwrap().amount > new_user_near_balance + to_yocto("0.99"));
+    assert_eq!(wnear_balance_of(&token2, &swap()), to_yocto("111"));
+}
+
+#[test]
+fn test_claim_lostfound_wnear_to_receiver() {
+    let root = init_simulator(None);
+    let owner = root.create_user("owner".to_string(), to_yocto("100"));
+    let pool = deploy!(
+        contract: Exchange,
+        contract_id: swap(),
+        bytes: &EXCHANGE_WASM_BYTES,
+        signer_account: root,
+        init_method: new(to_va("owner".to_string()), to_va("boost_farm".to_string()), to_va("burrowland".to_string()), 5, 0)
+    );
+    call!(
+        owner,
+        pool.modify_wnear_id(wnear()),
+        deposit = 1
+    )
+    .assert_success();
+    let token1 = test_token(&root, dai(), vec![swap()]);
+    let token2 = test_wnear(&root, vec![swap()]);
+    call!(
+        owner,
+        pool.extend_whitelisted_tokens(vec![to_va(dai()), to_va(wnear())]),
+        deposit=1
+    );
+    call!(
+        root,
+        pool.add_simple_pool(vec![to_va(dai()), to_va(wnear())], 25),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+
+    call!(
+        root,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+
+    call!(
+        owner,
+        pool.storage_deposit(None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+
+    call!(
+        root,
+        token1.ft_transfer_call(to_va(swap()), to_yocto("105").into(), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        root,
+        token2.ft_transfer_call(to_va(swap()), to_yocto("110").into(), None, "".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    call!(
+        root,
+        pool.add_liquidity(0, vec![U128(to_yocto("5")), U128(to_yocto("10"))], None),
+        deposit = to_yocto("0.0007")
+    )
+    .assert_success();
+
+
+    let new_user = root.create_user("new_user".to_string(), to_yocto("100"));
+    call!(
+        new_user,
+        token1.mint(to_va(new_user.account_id.clone()), U128(to_yocto("10")))
+    )
+    .assert_success();
+
+    // new_user is not registered on wnear, swapped out wnear goes to its lostfound
+    call!(
+        new_user,
+        token1.ft_transfer_call(
+            to_va(swap()),
+            to_yocto("1").into(),
+            None,
+            format!("{{\"actions\": [{{\"pool_id\": 0, \"token_in\": \"dai\", \"token_out\": \"wnear\", \"min_amount_out\": \"1\"}}]}}")
+        ),
+        deposit = 1
+    ).assert_success();
+    let lostfound = view!(pool.get_lostfound_token(new_user.valid_account_id(), to_va(wnear()))).unwrap_json::<U128>().0;
+    assert!(lostfound > 0);
+
+    // claimed to receiver as NEAR
+    let receiver = root.create_user("receiver".to_string(), to_yocto("10"));
+    let receiver_near_balance = receiver.account().unwrap().amount;
+    call!(
+        new_user,
+        pool.claim_lostfound_to(to_va(wnear()), receiver.valid_account_id(), Some(false)),
+        deposit = 1
+    ).assert_success();
+    assert_eq!(receiver.account().unwrap().amount, receiver_near_balance + lostfound);
+    assert_eq!(view!(pool.get_lostfound_token(new_user.valid_account_id(), to_va(wnear()))).unwrap_json::<U128>().0, 0);
+}
@@ -1,7 +1,9 @@
 
-use near_sdk::{env, near_bindgen, Promise};
-use near_sdk::json_types::{U128};
+use near_sdk::{env, near_bindgen, Promise, StorageUsage};
+use near_sdk::json_types::{ValidAccountId, U128};
 use simple_farm::{SimpleFarm, HRSimpleFarmTerms};
+use multi_reward_farm::{MultiRewardFarm, HRMultiRewardFarmTerms};
+use continuous_farm::ContinuousFarm;
 use crate::utils::{gen_farm_id, MIN_SEED_DEPOSIT, parse_farm_id};
 use crate::errors::*;
 use crate::*;
@@ -9,9 +11,10 @@ use crate::*;
 
 #[near_bindgen]
 impl Contract {
-    /// create farm and pay for its storage fee
+    /// create farm and pay for its storage fee,
+    /// creator_id, default to the owner, gets the creator rights of the farm, see farm_creator
     #[payable]
-    pub fn create_simple_farm(&mut self, terms: HRSimpleFarmTerms, min_deposit: Option<U128>) -> FarmId {
+    pub fn create_simple_farm(&mut self, terms: HRSimpleFarmTerms, min_deposit: Option<U128>, creator_id: Option<ValidAccountId>) -> FarmId {
 
         self.assert_owner();
         
@@ -19,9 +22,94 @@ impl Contract {
 
         let min_deposit: u128 = min_deposit.unwrap_or(U128(MIN_SEED_DEPOSIT)).0;
 
-        let farm_id = self.internal_add_farm(&terms, min_deposit);
+        let farm_id = self.internal_add_farm(
+            &terms.seed_id, 
+            min_deposit, 
+            creator_id,
+            |farm_id| Farm::SimpleFarm(SimpleFarm::new(farm_id, (&terms).into())),
+        );
+
+        self.internal_check_storage_and_refund(prev_storage);
+
+        farm_id
+    }
+
+    /// create farm that streams reward per second at the rate of 
+    /// reward_per_session / session_interval, and pay for its storage fee,
+    /// creator_id as in create_simple_farm
+    #[payable]
+    pub fn create_continuous_farm(&mut self, terms: HRSimpleFarmTerms, min_deposit: Option<U128>, creator_id: Option<ValidAccountId>) -> FarmId {
+
+        self.assert_owner();
+        assert!(terms.session_interval > 0, "{}", ERR45_INVALID_FARM_TERMS);
+        
+        let prev_storage = env::storage_usage();
+
+        let min_deposit: u128 = min_deposit.unwrap_or(U128(MIN_SEED_DEPOSIT)).0;
+
+        let farm_id = self.internal_add_farm(
+            &terms.seed_id, 
+            min_deposit, 
+            creator_id,
+            |farm_id| Farm::ContinuousFarm(ContinuousFarm::new(farm_id, (&terms).into())),
+        );
+
+        self.internal_check_storage_and_refund(prev_storage);
+
+        farm_id
+    }
+
+    /// create farm that distributes several reward tokens in one farm,
+    /// and pay for its storage fee, creator_id as in create_simple_farm
+    #[payable]
+    pub fn create_multi_reward_farm(&mut self, terms: HRMultiRewardFarmTerms, min_deposit: Option<U128>, creator_id: Option<ValidAccountId>) -> FarmId {
+
+        self.assert_owner();
+        
+        let prev_storage = env::storage_usage();
+
+        let min_deposit: u128 = min_deposit.unwrap_or(U128(MIN_SEED_DEPOSIT)).0;
+
+        let farm_id = self.internal_add_farm(
+            &terms.seed_id, 
+            min_deposit, 
+            creator_id,
+            |farm_id| Farm::MultiRewardFarm(MultiRewardFarm::new(farm_id, (&terms).into())),
+        );
+
+        self.internal_check_storage_and_refund(prev_storage);
+
+        farm_id
+    }
+
+    /// Change reward rate of a Created or Running farm without creating a new one,
+    /// by farm creator or owner,
+    /// reward released before keeps the old rate.
+    /// For a SimpleFarm, the new rate applies from the session not yet distributed.
+    pub fn modify_farm_terms(&mut self, farm_id: FarmId, reward_per_session: U128) {
+        self.assert_farm_creator_or_owner(&farm_id);
+        assert!(reward_per_session.0 > 0, "{}", ERR45_INVALID_FARM_TERMS);
+
+        let (seed_id, _) = parse_farm_id(&farm_id);
+        let total_seeds = self.get_seed(&seed_id).get_ref().amount;
+        let mut farm = self.data().farms.get(&farm_id).expect(ERR41_FARM_NOT_EXIST);
+        farm.modify_reward_per_session(reward_per_session.0, &total_seeds);
+        self.data_mut().farms.insert(&farm_id, &farm);
 
-        // Check how much storage cost and refund the left over back.
+        env::log(
+            format!(
+                "Farm {} reward_per_session modified to {}",
+                farm_id, reward_per_session.0
+            )
+            .as_bytes(),
+        );
+    }
+}
+
+impl Contract {
+    /// Check how much storage cost and refund the left over back.
+    /// If there is not enough attached balance to cover storage, fails.
+    fn internal_check_storage_and_refund(&self, prev_storage: StorageUsage) {
         let storage_needed = env::storage_usage() - prev_storage;
         let storage_cost = storage_needed as u128 * env::storage_byte_cost();
         assert!(
@@ -33,49 +121,48 @@ impl Contract {
         if refund > 0 {
             Promise::new(env::predecessor_account_id()).transfer(refund);
         }
-
-        farm_id
     }
-}
 
-impl Contract {
-    /// Adds given farm to the vec and returns it's id.
-    /// If there is not enough attached balance to cover storage, fails.
-    /// If too much attached - refunds it back.
-    fn internal_add_farm(&mut self, terms: &HRSimpleFarmTerms, min_deposit: Balance) -> FarmId {
+    /// Adds the farm built by new_farm to the seed and returns it's id.
+    fn internal_add_farm<F: FnOnce(FarmId) -> Farm>(
+        &mut self,
+        seed_id: &SeedId,
+        min_deposit: Balance,
+        creator_id: Option<ValidAccountId>,
+        new_farm: F,
+    ) -> FarmId {
         
         // let mut farm_seed = self.get_seed_default(&terms.seed_id, min_deposit);
         let mut farm_seed: VersionedFarmSeed;
-        if let Some(fs) = self.get_seed_wrapped(&terms.seed_id) {
+        if let Some(fs) = self.get_seed_wrapped(seed_id) {
             farm_seed = fs;
             env::log(
                 format!(
                     "New farm created In seed {}, with existed min_deposit {}",
-                    terms.seed_id, farm_seed.get_ref().min_deposit
+                    seed_id, farm_seed.get_ref().min_deposit
                 )
                 .as_bytes(),
             );
         } else {
-            farm_seed = VersionedFarmSeed::new(&terms.seed_id, min_deposit);
+            farm_seed = VersionedFarmSeed::new(seed_id, min_deposit);
             env::log(
                 format!(
                     "The first farm created In seed {}, with min_deposit {}",
-                    terms.seed_id, farm_seed.get_ref().min_deposit
+                    seed_id, farm_seed.get_ref().min_deposit
                 )
                 .as_bytes(),
             );
         }
 
-        let farm_id: FarmId = gen_farm_id(&terms.seed_id, farm_seed.get_ref().next_index as usize);
+        let farm_id: FarmId = gen_farm_id(&seed_id, farm_seed.get_ref().next_index as usize);
 
-        let farm = Farm::SimpleFarm(SimpleFarm::new(
-            farm_id.clone(),
-            terms.into(),
-        ));
+        let farm = new_farm(farm_id.clone());
+        let creator_id: AccountId = creator_id.map(|c| c.into()).unwrap_or(env::predecessor_account_id());
+        self.internal_set_farm_creator(&farm_id, &creator_id, farm.get_reward_tokens().len());
         
         farm_seed.get_ref_mut().farms.insert(farm_id.clone());
         farm_seed.get_ref_mut().next_index += 1;
-        self.data_mut().seeds.insert(&terms.seed_id, &farm_seed);
+        self.data_mut().seeds.insert(seed_id, &farm_seed);
         self.data_mut().farms.insert(&farm_id.clone(), &farm);
         farm_id
     }
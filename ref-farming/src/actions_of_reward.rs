@@ -5,6 +5,7 @@ use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, Balance, PromiseR
 
 use crate::utils::{ext_fungible_token, ext_self, GAS_FOR_FT_TRANSFER, GAS_FOR_RESOLVE_TRANSFER, parse_farm_id};
 use crate::errors::*;
+use crate::events::Event;
 use crate::*;
 use uint::construct_uint;
 
@@ -107,6 +108,13 @@ impl Contract {
                     )
                     .as_bytes(),
                 );
+                Event::RewardWithdraw {
+                    farmer_id: &sender_id,
+                    token_id: &token_id,
+                    withdraw_amount: &amount,
+                    success: true,
+                }
+                .emit();
                 amount.into()
             }
             PromiseResult::Failed => {
@@ -117,6 +125,13 @@ impl Contract {
                     )
                     .as_bytes(),
                 );
+                Event::RewardWithdraw {
+                    farmer_id: &sender_id,
+                    token_id: &token_id,
+                    withdraw_amount: &amount,
+                    success: false,
+                }
+                .emit();
                 // This reverts the changes from withdraw function.
                 let mut farmer = self.get_farmer(&sender_id);
                 farmer.get_ref_mut().add_reward(&token_id, amount.0);
@@ -129,13 +144,14 @@ impl Contract {
 
 fn claim_user_reward_from_farm(
     farm: &mut Farm, 
+    farmer_id: &AccountId,
     farmer: &mut Farmer, 
     total_seeds: &Balance,
     silent: bool,
 ) {
-    let user_seeds = farmer.seeds.get(&farm.get_seed_id()).unwrap_or(&0_u128);
+    let user_seeds = farmer.get_seed_power(&farm.get_seed_id());
     let user_rps = farmer.get_rps(&farm.get_farm_id());
-    let (new_user_rps, reward_amount) = farm.claim_user_reward(&user_rps, user_seeds, total_seeds, silent);
+    let (new_user_rps, reward_amounts) = farm.claim_user_reward(&user_rps, &user_seeds, total_seeds, silent);
     if !silent {
         env::log(
             format!(
@@ -147,16 +163,26 @@ fn claim_user_reward_from_farm(
     }
         
     farmer.set_rps(&farm.get_farm_id(), new_user_rps);
-    if reward_amount > 0 {
-        farmer.add_reward(&farm.get_reward_token(), reward_amount);
-        if !silent {
-            env::log(
-                format!(
-                    "claimed {} {} as reward from {}",
-                    reward_amount, farm.get_reward_token() , farm.get_farm_id(),
-                )
-                .as_bytes(),
-            );
+    for (token_id, reward_amount) in farm.get_reward_tokens().iter().zip(reward_amounts.into_iter()) {
+        if reward_amount > 0 {
+            farmer.add_reward(token_id, reward_amount);
+            farmer.add_claimed(&farm.get_farm_id(), token_id, reward_amount);
+            Event::RewardClaim {
+                farmer_id,
+                farm_id: &farm.get_farm_id(),
+                token_id,
+                claim_amount: &U128(reward_amount),
+            }
+            .emit();
+            if !silent {
+                env::log(
+                    format!(
+                        "claimed {} {} as reward from {}",
+                        reward_amount, token_id, farm.get_farm_id(),
+                    )
+                    .as_bytes(),
+                );
+            }
         }
     }
 }
@@ -174,12 +200,18 @@ impl Contract {
                 let mut farm = self.data().farms.get(farm_id).unwrap();
                 claim_user_reward_from_farm(
                     &mut farm, 
+                    sender_id,
                     farmer.get_ref_mut(),  
                     &amount,
                     true,
                 );
                 self.data_mut().farms.insert(farm_id, &farm);
             }
+            // reward of all farms settled, expired lock can drop its power
+            let dropped = farmer.get_ref_mut().expire_lock(seed_id);
+            if dropped > 0 {
+                farm_seed.get_ref_mut().sub_amount(dropped);
+            }
             self.data_mut().seeds.insert(seed_id, &farm_seed);
             self.data_mut().farmers.insert(sender_id, &farmer);
         }
@@ -198,6 +230,7 @@ impl Contract {
             if let Some(mut farm) = self.data().farms.get(farm_id) {
                 claim_user_reward_from_farm(
                     &mut farm, 
+                    sender_id,
                     farmer.get_ref_mut(), 
                     &amount,
                     false,
@@ -9,6 +9,7 @@ use crate::utils::{
 };
 use crate::errors::*;
 use crate::farm_seed::SeedType;
+use crate::events::Event;
 use crate::*;
 
 
@@ -94,6 +95,13 @@ impl Contract {
                     )
                     .as_bytes(),
                 );
+                Event::SeedWithdraw {
+                    farmer_id: &sender_id,
+                    seed_id: &seed_id,
+                    withdraw_amount: &U128(amount),
+                    success: false,
+                }
+                .emit();
                 // revert withdraw, equal to deposit, claim reward to update user reward_per_seed
                 self.internal_claim_user_reward_by_seed_id(&sender_id, &seed_id);
                 let mut farm_seed = self.get_seed(&seed_id);
@@ -114,6 +122,13 @@ impl Contract {
                     )
                     .as_bytes(),
                 );
+                Event::SeedWithdraw {
+                    farmer_id: &sender_id,
+                    seed_id: &seed_id,
+                    withdraw_amount: &U128(amount),
+                    success: true,
+                }
+                .emit();
                 amount.into()
             }
         }
@@ -143,6 +158,13 @@ impl Contract {
                     )
                     .as_bytes(),
                 );
+                Event::SeedWithdraw {
+                    farmer_id: &sender_id,
+                    seed_id: &seed_id,
+                    withdraw_amount: &U128(amount),
+                    success: false,
+                }
+                .emit();
                 // revert withdraw, equal to deposit, claim reward to update user reward_per_seed
                 self.internal_claim_user_reward_by_seed_id(&sender_id, &seed_id);
                 let mut farm_seed = self.get_seed(&seed_id);
@@ -163,6 +185,13 @@ impl Contract {
                     )
                     .as_bytes(),
                 );
+                Event::SeedWithdraw {
+                    farmer_id: &sender_id,
+                    seed_id: &seed_id,
+                    withdraw_amount: &U128(amount),
+                    success: true,
+                }
+                .emit();
                 amount.into()
             }
         }
@@ -232,10 +261,10 @@ impl Contract {
         let mut farmer = self.get_farmer(sender_id);
 
         // Then update user seed and total seed of this LPT
-        let farmer_seed_remain = farmer.get_ref_mut().sub_seed(seed_id, amount);
+        farmer.get_ref_mut().sub_seed(seed_id, amount);
         let _seed_remain = farm_seed.get_ref_mut().sub_amount(amount);
 
-        if farmer_seed_remain == 0 {
+        if farmer.get_ref().get_seed_power(seed_id) == 0 {
             // remove farmer rps of relative farm
             for farm_id in farm_seed.get_ref().farms.iter() {
                 farmer.get_ref_mut().remove_rps(farm_id);
@@ -0,0 +1,307 @@
+//!   The ContinuousFarm uses the same terms as SimpleFarm, but streams reward
+//! per second at the rate of `reward_per_session / session_interval`,
+//! instead of releasing a whole session at each session boundary.
+//! So that farmers staking just before a boundary can't take the session's
+//! reward from those who staked through it.
+//!   A running SimpleFarm can be converted into a ContinuousFarm by owner,
+//! see `migrate_to_continuous_farm`.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, Balance};
+
+use crate::FarmId;
+use crate::simple_farm::{SimpleFarm, SimpleFarmTerms, SimpleFarmStatus, RPS, DENOM, U256};
+use crate::errors::*;
+use crate::utils::*;
+
+/// Reward Distribution Record
+#[derive(BorshSerialize, BorshDeserialize, Clone, Default)]
+pub struct ContinuousFarmRewardDistribution {
+    /// unreleased reward
+    pub undistributed: Balance,
+    /// the total rewards distributed but not yet claimed by farmers.
+    pub unclaimed: Balance,
+    /// Reward_Per_Seed
+    /// rps(cur) = rps(prev) + distributing_reward / total_seed_staked
+    pub rps: RPS,
+    /// reward released till this timestamp in sec
+    pub distributed_at: TimestampSec,
+    /// fraction of reward released but not counted in yet, in 1/session_interval of reward,
+    /// kept so that frequent distributions don't lose it on every call
+    pub remainder: u128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ContinuousFarm {
+
+    pub farm_id: FarmId,
+
+    pub terms: SimpleFarmTerms,
+
+    pub status: SimpleFarmStatus,
+
+    pub last_distribution: ContinuousFarmRewardDistribution,
+
+    /// total reward send into this farm by far,
+    /// every time reward deposited in, add to this field
+    pub amount_of_reward: Balance,
+    /// reward token has been claimed by farmer by far
+    pub amount_of_claimed: Balance,
+    /// when there is no seed token staked, reward goes to beneficiary
+    pub amount_of_beneficiary: Balance,
+
+}
+
+/// The migration of a SimpleFarm, which has been distributed to current round.
+/// Reward of the current session, not released by SimpleFarm yet,
+/// would stream from the session start.
+impl From<SimpleFarm> for ContinuousFarm {
+    fn from(farm: SimpleFarm) -> Self {
+        let distributed_at = farm.terms.start_at + farm.last_distribution.rr * farm.terms.session_interval;
+        Self {
+            farm_id: farm.farm_id,
+            terms: farm.terms,
+            status: farm.status,
+            last_distribution: ContinuousFarmRewardDistribution {
+                undistributed: farm.last_distribution.undistributed,
+                unclaimed: farm.last_distribution.unclaimed,
+                rps: farm.last_distribution.rps,
+                distributed_at,
+                remainder: 0,
+            },
+            amount_of_reward: farm.amount_of_reward,
+            amount_of_claimed: farm.amount_of_claimed,
+            amount_of_beneficiary: farm.amount_of_beneficiary,
+        }
+    }
+}
+
+impl ContinuousFarm {
+    pub(crate) fn new(
+        id: FarmId,
+        terms: SimpleFarmTerms,
+    ) -> Self {
+        Self {
+            farm_id: id.clone(),
+            amount_of_reward: 0,
+            amount_of_claimed: 0,
+            amount_of_beneficiary: 0,
+
+            status: SimpleFarmStatus::Created,
+            last_distribution: ContinuousFarmRewardDistribution {
+                distributed_at: terms.start_at,
+                ..Default::default()
+            },
+            terms,
+        }
+    }
+
+    /// return None if the farm can not accept reward anymore
+    /// else return amount of undistributed reward
+    pub(crate) fn add_reward(&mut self, amount: &Balance) -> Option<Balance> {
+
+        match self.status {
+            SimpleFarmStatus::Created => {
+                self.status = SimpleFarmStatus::Running;
+                if self.terms.start_at == 0 {
+                    self.terms.start_at = to_sec(env::block_timestamp());
+                    self.last_distribution.distributed_at = self.terms.start_at;
+                }
+                self.amount_of_reward += amount;
+                self.last_distribution.undistributed += amount;
+                Some(self.last_distribution.undistributed)
+            },
+            SimpleFarmStatus::Running => {
+                if let Some(dis) = self.try_distribute(&DENOM) {
+                    if dis.undistributed == 0 {
+                        // farm has ended actually
+                        return None;
+                    }
+                }
+                self.amount_of_reward += amount;
+                self.last_distribution.undistributed += amount;
+                Some(self.last_distribution.undistributed)
+            },
+            _ => {None},
+        }
+
+    }
+
+    /// Try to distribute reward according to current timestamp
+    /// return None if farm is not in Running state or haven't start farming yet;
+    /// Note, if total_seed is 0, the rps in new dis would be reset to 0 too.
+    pub(crate) fn try_distribute(&self, total_seeds: &Balance) -> Option<ContinuousFarmRewardDistribution> {
+
+        if let SimpleFarmStatus::Running = self.status {
+            if env::block_timestamp() < to_nano(self.terms.start_at) {
+                // a farm haven't start yet
+                return None;
+            }
+            let mut dis = self.last_distribution.clone();
+            let now = to_sec(env::block_timestamp());
+            if now <= dis.distributed_at {
+                return Some(dis);
+            }
+            let released = U256::from(self.terms.reward_per_session)
+                * U256::from(now - dis.distributed_at)
+                + U256::from(dis.remainder);
+            let session_interval = U256::from(self.terms.session_interval);
+            let reward_added = std::cmp::min((released / session_interval).as_u128(), dis.undistributed);
+            dis.remainder = if reward_added < dis.undistributed {
+                (released % session_interval).as_u128()
+            } else {
+                0
+            };
+            dis.distributed_at = now;
+            dis.unclaimed += reward_added;
+            dis.undistributed -= reward_added;
+
+            // calculate rps
+            if total_seeds == &0 {
+                U256::from(0).to_little_endian(&mut dis.rps);
+            } else {
+                (
+                    U256::from_little_endian(&self.last_distribution.rps) +
+                    U256::from(reward_added)
+                    * U256::from(DENOM)
+                    / U256::from(*total_seeds)
+                ).to_little_endian(&mut dis.rps);
+            }
+            Some(dis)
+        } else {
+            None
+        }
+
+    }
+
+    /// Return how many reward token that the user hasn't claimed yet.
+    /// return (cur_rps - last_user_rps) * user_seeds / DENOM
+    pub(crate) fn view_farmer_unclaimed_reward(
+        &self,
+        user_rps: &RPS,
+        user_seeds: &Balance,
+        total_seeds: &Balance,
+    ) -> Balance {
+        if total_seeds == &0 {
+            return 0;
+        }
+        if user_seeds == &0 {
+            return 0;
+        }
+        if let Some(dis) = self.try_distribute(total_seeds) {
+            (U256::from(*user_seeds)
+            * (U256::from_little_endian(&dis.rps) - U256::from_little_endian(user_rps))
+            / U256::from(DENOM)).as_u128()
+        } else {
+            (U256::from(*user_seeds)
+            * (U256::from_little_endian(&self.last_distribution.rps) - U256::from_little_endian(user_rps))
+            / U256::from(DENOM)).as_u128()
+        }
+    }
+
+    /// Distribute reward released from previous distribution to now,
+    /// Note 1, if undistribute equals 0, the farm goes to Ended state;
+    /// Note 2, if total_seed is 0, reward is claimed directly by beneficiary
+    pub(crate) fn distribute(&mut self, total_seeds: &Balance, silent: bool) {
+        if let Some(dis) = self.try_distribute(total_seeds) {
+            if self.last_distribution.distributed_at != dis.distributed_at {
+                self.last_distribution = dis.clone();
+                if total_seeds == &0 {
+                    // if total_seeds == &0, reward goes to beneficiary,
+                    self.amount_of_claimed += self.last_distribution.unclaimed;
+                    self.amount_of_beneficiary += self.last_distribution.unclaimed;
+                    self.last_distribution.unclaimed = 0;
+                }
+                if !silent {
+                    env::log(
+                        format!(
+                            "{} RPS increased to {} and distributed at {}",
+                            self.farm_id, U256::from_little_endian(&dis.rps), dis.distributed_at,
+                        )
+                        .as_bytes(),
+                    );
+                }
+            }
+            if self.last_distribution.undistributed == 0 {
+                self.status = SimpleFarmStatus::Ended;
+            }
+        }
+    }
+
+    /// Claim user's unclaimed reward in this farm,
+    /// return the new user RPS (reward per seed),
+    /// and amount of reward
+    pub(crate) fn claim_user_reward(
+        &mut self,
+        user_rps: &RPS,
+        user_seeds: &Balance,
+        total_seeds: &Balance,
+        silent: bool,
+    ) -> (RPS, Balance) {
+
+        self.distribute(total_seeds, silent);
+
+        let claimed = (
+            U256::from(*user_seeds)
+            * (U256::from_little_endian(&self.last_distribution.rps) - U256::from_little_endian(user_rps))
+            / U256::from(DENOM)
+        ).as_u128();
+
+        if claimed > 0 {
+            assert!(
+                self.last_distribution.unclaimed >= claimed,
+                "{} unclaimed:{}, cur_claim:{}",
+                ERR500, self.last_distribution.unclaimed, claimed
+            );
+            self.last_distribution.unclaimed -= claimed;
+            self.amount_of_claimed += claimed;
+        }
+
+        (self.last_distribution.rps, claimed)
+    }
+
+    /// Move an Ended farm to Cleared, if any unclaimed reward exists, go to beneficiary
+    pub(crate) fn move_to_clear(&mut self, total_seeds: &Balance) -> bool {
+        if let SimpleFarmStatus::Running = self.status {
+            self.distribute(total_seeds, true);
+        }
+        if let SimpleFarmStatus::Ended = self.status {
+            if self.last_distribution.unclaimed > 0 {
+                self.amount_of_claimed += self.last_distribution.unclaimed;
+                self.amount_of_beneficiary += self.last_distribution.unclaimed;
+                self.last_distribution.unclaimed = 0;
+            }
+            self.status = SimpleFarmStatus::Cleared;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Change reward_per_session after distributing reward to now with the old one,
+    /// only for farms in Created or Running state.
+    pub(crate) fn modify_reward_per_session(&mut self, reward_per_session: Balance, total_seeds: &Balance) {
+        self.distribute(total_seeds, true);
+        match self.status {
+            SimpleFarmStatus::Created | SimpleFarmStatus::Running => {
+                self.terms.reward_per_session = reward_per_session;
+            },
+            _ => env::panic(ERR43_INVALID_FARM_STATUS.as_bytes()),
+        }
+    }
+
+    pub fn can_be_removed(&self, total_seeds: &Balance) -> bool {
+        match self.status {
+            SimpleFarmStatus::Ended => true,
+            SimpleFarmStatus::Running => {
+                if let Some(dis) = self.try_distribute(total_seeds) {
+                    dis.undistributed == 0
+                } else {
+                    false
+                }
+            },
+            _ => false,
+        }
+    }
+
+}
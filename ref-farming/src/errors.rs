@@ -8,8 +8,12 @@ pub const ERR14_ACC_ALREADY_REGISTERED: &str = "E14: account already registered"
 // Reward errors //
 pub const ERR21_TOKEN_NOT_REG: &str = "E21: token not registered";
 pub const ERR22_NOT_ENOUGH_TOKENS: &str = "E22: not enough tokens in deposit";
+pub const ERR23_NOT_CLAIM_DELEGATE: &str = "E23: not the farmer or his claim delegate";
+pub const ERR24_INVALID_REWARD_ROUTE: &str = "E24: invalid reward route";
 
 pub const ERR25_CALLBACK_POST_WITHDRAW_INVALID: &str = "E25: expected 1 promise result from withdraw";
+pub const ERR26_INVALID_ROUTE_TOKENS: &str = "E26: invalid number of reward tokens to route";
+pub const ERR27_NOT_ENOUGH_GAS: &str = "E27: not enough gas attached";
 
 // Seed errors //
 pub const ERR31_SEED_NOT_EXIST: &str = "E31: seed not exist";
@@ -17,12 +21,20 @@ pub const ERR32_NOT_ENOUGH_SEED: &str = "E32: not enough amount of seed";
 pub const ERR33_INVALID_SEED_ID: &str = "E33: invalid seed id";
 pub const ERR34_BELOW_MIN_SEED_DEPOSITED: &str = "E34: below min_deposit of this seed";
 pub const ERR35_ILLEGAL_TOKEN_ID: &str = "E35: illegal token_id in mft_transfer_call";
+pub const ERR36_INVALID_LOCK_CONFIG: &str = "E36: invalid lock config";
+pub const ERR37_INVALID_LOCK_DURATION: &str = "E37: invalid locking duration";
+pub const ERR38_LOCK_CAUSE_PRE_UNLOCK: &str = "E38: relock would unlock earlier";
+pub const ERR39_SEED_NOT_LOCKED: &str = "E39: seed not locked";
 
 // farm errors //
 pub const ERR41_FARM_NOT_EXIST: &str = "E41: farm not exist";
 pub const ERR42_INVALID_FARM_ID: &str = "E42: invalid farm id";
 pub const ERR43_INVALID_FARM_STATUS: &str = "E43: invalid farm status";
 pub const ERR44_INVALID_FARM_REWARD: &str = "E44: invalid reward token for this farm";
+pub const ERR45_INVALID_FARM_TERMS: &str = "E45: invalid farm terms";
+pub const ERR46_INVALID_FARM_KIND: &str = "E46: invalid farm kind for this operation";
+pub const ERR47_FARM_ALREADY_STARTED: &str = "E47: farm already started";
+pub const ERR48_NOT_FARM_CREATOR: &str = "E48: not farm creator";
 
 pub const ERR500: &str = "E500: Internal ERROR!";
 
@@ -0,0 +1,49 @@
+//! Structured events of farmer's reward and seed flow,
+//! following NEP-297: https://nomicon.io/Standards/EventsFormat
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json::json;
+use near_sdk::{env, AccountId};
+
+const EVENT_STANDARD: &str = "ref-farming";
+const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum Event<'a> {
+    RewardClaim {
+        farmer_id: &'a AccountId,
+        farm_id: &'a String,
+        token_id: &'a AccountId,
+        claim_amount: &'a U128,
+    },
+    RewardWithdraw {
+        farmer_id: &'a AccountId,
+        token_id: &'a AccountId,
+        withdraw_amount: &'a U128,
+        success: bool,
+    },
+    SeedWithdraw {
+        farmer_id: &'a AccountId,
+        seed_id: &'a String,
+        withdraw_amount: &'a U128,
+        success: bool,
+    },
+}
+
+impl Event<'_> {
+    pub fn emit(&self) {
+        let result = json!(self);
+        let event_json = json!({
+            "standard": EVENT_STANDARD,
+            "version": EVENT_STANDARD_VERSION,
+            "event": result["event"],
+            "data": [result["data"]]
+        })
+        .to_string();
+        env::log(format!("EVENT_JSON:{}", event_json).as_bytes());
+    }
+}
@@ -1,10 +1,15 @@
 //! Wrapper of different types of farms 
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::{AccountId, Balance};
+use near_sdk::{env, AccountId, Balance};
 
 use crate::simple_farm::{SimpleFarm, RPS};
+use crate::multi_reward_farm::MultiRewardFarm;
+use crate::continuous_farm::ContinuousFarm;
 use crate::SeedId;
+use crate::simple_farm::SimpleFarmStatus;
+use crate::errors::*;
+use crate::utils::to_nano;
 
 pub(crate) type FarmId = String;
 
@@ -14,6 +19,8 @@ pub(crate) type FarmId = String;
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum Farm {
     SimpleFarm(SimpleFarm),
+    MultiRewardFarm(MultiRewardFarm),
+    ContinuousFarm(ContinuousFarm),
 }
 
 impl Farm {
@@ -21,14 +28,18 @@ impl Farm {
     pub fn kind(&self) -> String {
         match self {
             Farm::SimpleFarm(_) => "SIMPLE_FARM".to_string(),
+            Farm::MultiRewardFarm(_) => "MULTI_REWARD_FARM".to_string(),
+            Farm::ContinuousFarm(_) => "CONTINUOUS_FARM".to_string(),
         }
     }
 
     /// return None if the farm can not accept reward anymore
-    /// else return amount of undistributed reward 
-    pub fn add_reward(&mut self, amount: &Balance) -> Option<Balance> {
+    /// else return amount of undistributed reward of the token
+    pub fn add_reward(&mut self, token_id: &AccountId, amount: &Balance) -> Option<Balance> {
         match self {
             Farm::SimpleFarm(farm) => farm.add_reward(amount),
+            Farm::MultiRewardFarm(farm) => farm.add_reward(token_id, amount),
+            Farm::ContinuousFarm(farm) => farm.add_reward(amount),
         }
     }
 
@@ -36,58 +47,134 @@ impl Farm {
     pub fn get_seed_id(&self) -> SeedId {
         match self {
             Farm::SimpleFarm(farm) => farm.terms.seed_id.clone(),
+            Farm::MultiRewardFarm(farm) => farm.terms.seed_id.clone(),
+            Farm::ContinuousFarm(farm) => farm.terms.seed_id.clone(),
         }
     }
 
-    /// Returns token contract id this farm used for reward.
-    pub fn get_reward_token(&self) -> AccountId {
+    /// Returns token contract ids this farm used for reward.
+    pub fn get_reward_tokens(&self) -> Vec<AccountId> {
         match self {
-            Farm::SimpleFarm(farm) => farm.terms.reward_token.clone(),
+            Farm::SimpleFarm(farm) => vec![farm.terms.reward_token.clone()],
+            Farm::MultiRewardFarm(farm) => farm.terms.reward_tokens.clone(),
+            Farm::ContinuousFarm(farm) => vec![farm.terms.reward_token.clone()],
+        }
+    }
+
+    /// Returns total reward deposited, in the order of get_reward_tokens.
+    pub fn get_total_rewards(&self) -> Vec<Balance> {
+        match self {
+            Farm::SimpleFarm(farm) => vec![farm.amount_of_reward],
+            Farm::MultiRewardFarm(farm) => farm.amount_of_reward.clone(),
+            Farm::ContinuousFarm(farm) => vec![farm.amount_of_reward],
+        }
+    }
+
+    /// Returns reward went to beneficiary by far, in the order of get_reward_tokens.
+    pub fn get_beneficiary_rewards(&self) -> Vec<Balance> {
+        match self {
+            Farm::SimpleFarm(farm) => vec![farm.amount_of_beneficiary],
+            Farm::MultiRewardFarm(farm) => farm.amount_of_beneficiary.clone(),
+            Farm::ContinuousFarm(farm) => vec![farm.amount_of_beneficiary],
+        }
+    }
+
+    /// A farm can be cancelled before it starts farming.
+    pub fn can_be_cancelled(&self) -> bool {
+        let (status, start_at) = match self {
+            Farm::SimpleFarm(farm) => (&farm.status, farm.terms.start_at),
+            Farm::MultiRewardFarm(farm) => (&farm.status, farm.terms.start_at),
+            Farm::ContinuousFarm(farm) => (&farm.status, farm.terms.start_at),
+        };
+        match status {
+            SimpleFarmStatus::Created => true,
+            SimpleFarmStatus::Running => env::block_timestamp() < to_nano(start_at),
+            _ => false,
         }
     }
 
     pub fn get_farm_id(&self) -> FarmId {
         match self {
             Farm::SimpleFarm(farm) => farm.farm_id.clone(),
+            Farm::MultiRewardFarm(farm) => farm.farm_id.clone(),
+            Farm::ContinuousFarm(farm) => farm.farm_id.clone(),
         }
     }
 
-    /// Returns how many reward tokens can given farmer claim.
+    /// Returns how many reward tokens can given farmer claim, 
+    /// in the order of get_reward_tokens.
     pub fn view_farmer_unclaimed_reward(
         &self,
         user_rps: &RPS,
         user_seeds: &Balance,
         total_seeds: &Balance,
-    ) -> Balance {
+    ) -> Vec<Balance> {
         match self {
             Farm::SimpleFarm(farm) 
+                => vec![farm.view_farmer_unclaimed_reward(user_rps, user_seeds, total_seeds)],
+            Farm::MultiRewardFarm(farm) 
                 => farm.view_farmer_unclaimed_reward(user_rps, user_seeds, total_seeds),
+            Farm::ContinuousFarm(farm) 
+                => vec![farm.view_farmer_unclaimed_reward(user_rps, user_seeds, total_seeds)],
         }
     }
 
     /// return the new user reward per seed 
-    /// and amount of reward as (user_rps, reward_amount) 
+    /// and amount of each reward as (user_rps, reward_amounts),
+    /// reward amounts are in the order of get_reward_tokens
     pub fn claim_user_reward(&mut self, 
         user_rps: &RPS,
         user_seeds: &Balance, 
         total_seeds: &Balance, 
         silent: bool,
-    ) -> (RPS, Balance) {
+    ) -> (RPS, Vec<Balance>) {
         match self {
-            Farm::SimpleFarm(farm) 
+            Farm::SimpleFarm(farm) => {
+                let (rps, amount) = farm.claim_user_reward(user_rps, user_seeds, total_seeds, silent);
+                (rps, vec![amount])
+            },
+            Farm::MultiRewardFarm(farm) 
                 => farm.claim_user_reward(user_rps, user_seeds, total_seeds, silent),
+            Farm::ContinuousFarm(farm) => {
+                let (rps, amount) = farm.claim_user_reward(user_rps, user_seeds, total_seeds, silent);
+                (rps, vec![amount])
+            },
+        }
+    }
+
+    /// Distribute reward released till now,
+    /// reward goes to beneficiary if no seed staked.
+    pub fn distribute(&mut self, total_seeds: &Balance) {
+        match self {
+            Farm::SimpleFarm(farm) => farm.distribute(total_seeds, true),
+            Farm::MultiRewardFarm(farm) => farm.distribute(total_seeds, true),
+            Farm::ContinuousFarm(farm) => farm.distribute(total_seeds, true),
+        }
+    }
+
+    /// Change reward rate from now on.
+    /// MultiRewardFarm can't, as its unclaimed sessions are paid at the current rates.
+    pub fn modify_reward_per_session(&mut self, reward_per_session: Balance, total_seeds: &Balance) {
+        match self {
+            Farm::SimpleFarm(farm) => farm.modify_reward_per_session(reward_per_session, total_seeds),
+            Farm::MultiRewardFarm(_) => env::panic(ERR46_INVALID_FARM_KIND.as_bytes()),
+            Farm::ContinuousFarm(farm) => farm.modify_reward_per_session(reward_per_session, total_seeds),
         }
     }
 
     pub fn can_be_removed(&self, total_seeds: &Balance) -> bool {
         match self {
             Farm::SimpleFarm(farm) => farm.can_be_removed(total_seeds),
+            Farm::MultiRewardFarm(farm) => farm.can_be_removed(total_seeds),
+            Farm::ContinuousFarm(farm) => farm.can_be_removed(total_seeds),
         }
     }
 
     pub fn move_to_clear(&mut self, total_seeds: &Balance) -> bool {
         match self {
             Farm::SimpleFarm(farm) => farm.move_to_clear(total_seeds),
+            Farm::MultiRewardFarm(farm) => farm.move_to_clear(total_seeds),
+            Farm::ContinuousFarm(farm) => farm.move_to_clear(total_seeds),
         }
     }
 
@@ -0,0 +1,129 @@
+//! FarmCreator records who created a farm, so that the creator can
+//! * change the reward rate of the farm,
+//! * cancel the farm before it starts, deposited reward refunded,
+//! * claim beneficiary reward, which is released when no seed staked.
+//! Refunded and beneficiary reward go to the creator's reward balance,
+//! and can be withdrawn by `withdraw_reward` as farmers do.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, near_bindgen, AccountId, Balance};
+
+use crate::utils::parse_farm_id;
+use crate::errors::*;
+use crate::*;
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FarmCreator {
+    pub creator_id: AccountId,
+    /// beneficiary reward claimed by creator, in the order of farm reward tokens
+    pub beneficiary_claimed: Vec<Balance>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Cancel a farm that hasn't started farming yet,
+    /// reward deposited goes to creator's reward balance.
+    /// The creator should have registered as farmer.
+    pub fn cancel_farm(&mut self, farm_id: FarmId) {
+        assert!(self.data().state == RunningState::Running, "{}", ERR600_CONTRACT_PAUSED);
+        self.assert_farm_creator_or_owner(&farm_id);
+
+        let farm = self.data().farms.get(&farm_id).expect(ERR41_FARM_NOT_EXIST);
+        assert!(farm.can_be_cancelled(), "{}", ERR47_FARM_ALREADY_STARTED);
+
+        let creator_id = self.internal_get_farm_creator(&farm_id);
+        let mut creator = self.get_farmer(&creator_id);
+        for (token_id, amount) in farm.get_reward_tokens().iter().zip(farm.get_total_rewards().into_iter()) {
+            if amount > 0 {
+                creator.get_ref_mut().add_reward(token_id, amount);
+            }
+        }
+        self.data_mut().farmers.insert(&creator_id, &creator);
+
+        let (seed_id, _) = parse_farm_id(&farm_id);
+        let mut farm_seed = self.get_seed(&seed_id);
+        farm_seed.get_ref_mut().farms.remove(&farm_id);
+        self.data_mut().seeds.insert(&seed_id, &farm_seed);
+        self.data_mut().farms.remove(&farm_id);
+        self.data_mut().farm_creators.remove(&farm_id);
+
+        self.assert_storage_usage(&creator_id);
+
+        env::log(
+            format!(
+                "Farm {} cancelled, reward refunded to {}",
+                farm_id, creator_id
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Beneficiary reward of the farm, running or outdated, goes to creator's reward balance.
+    /// The creator should have registered as farmer.
+    pub fn claim_beneficiary_reward_by_farm(&mut self, farm_id: FarmId) {
+        assert!(self.data().state == RunningState::Running, "{}", ERR600_CONTRACT_PAUSED);
+        let creator_id = self.internal_get_farm_creator(&farm_id);
+        assert_eq!(env::predecessor_account_id(), creator_id, "{}", ERR48_NOT_FARM_CREATOR);
+
+        let farm = if let Some(mut farm) = self.data().farms.get(&farm_id) {
+            // beneficiary reward of a running farm grows on distribution
+            let (seed_id, _) = parse_farm_id(&farm_id);
+            let total_seeds = self.get_seed(&seed_id).get_ref().amount;
+            farm.distribute(&total_seeds);
+            self.data_mut().farms.insert(&farm_id, &farm);
+            farm
+        } else {
+            self.data().outdated_farms.get(&farm_id).expect(ERR41_FARM_NOT_EXIST)
+        };
+        let reward_tokens = farm.get_reward_tokens();
+        let mut farm_creator = self.data().farm_creators.get(&farm_id).unwrap_or(FarmCreator {
+            creator_id: creator_id.clone(),
+            beneficiary_claimed: vec![0; reward_tokens.len()],
+        });
+
+        let mut creator = self.get_farmer(&creator_id);
+        for (index, amount) in farm.get_beneficiary_rewards().into_iter().enumerate() {
+            let claimable = amount - farm_creator.beneficiary_claimed[index];
+            if claimable > 0 {
+                farm_creator.beneficiary_claimed[index] = amount;
+                creator.get_ref_mut().add_reward(&reward_tokens[index], claimable);
+                env::log(
+                    format!(
+                        "{} claimed {} {} as beneficiary reward from {}",
+                        creator_id, claimable, reward_tokens[index], farm_id,
+                    )
+                    .as_bytes(),
+                );
+            }
+        }
+        self.data_mut().farmers.insert(&creator_id, &creator);
+        self.data_mut().farm_creators.insert(&farm_id, &farm_creator);
+
+        self.assert_storage_usage(&creator_id);
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_set_farm_creator(&mut self, farm_id: &FarmId, creator_id: &AccountId, reward_token_count: usize) {
+        self.data_mut().farm_creators.insert(farm_id, &FarmCreator {
+            creator_id: creator_id.clone(),
+            beneficiary_claimed: vec![0; reward_token_count],
+        });
+    }
+
+    /// Farms created before creators were recorded belong to the owner.
+    pub(crate) fn internal_get_farm_creator(&self, farm_id: &FarmId) -> AccountId {
+        self.data().farm_creators.get(farm_id)
+            .map(|farm_creator| farm_creator.creator_id)
+            .unwrap_or(self.data().owner_id.clone())
+    }
+
+    pub(crate) fn assert_farm_creator_or_owner(&self, farm_id: &FarmId) {
+        let predecessor_id = env::predecessor_account_id();
+        assert!(
+            predecessor_id == self.data().owner_id
+            || predecessor_id == self.internal_get_farm_creator(farm_id),
+            "{}", ERR48_NOT_FARM_CREATOR
+        );
+    }
+}
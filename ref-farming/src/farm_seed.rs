@@ -9,6 +9,8 @@ use near_sdk::json_types::{U128};
 use crate::errors::*;
 use crate::farm::FarmId;
 use crate::utils::parse_seed_id;
+use crate::legacy::FarmSeedV101;
+use crate::seed_lock::SeedLockConfig;
 
 
 /// For MFT, SeedId composes of token_contract_id 
@@ -34,9 +36,16 @@ pub struct FarmSeed {
     /// FarmId = {seed_id}#{next_index}
     pub farms: HashSet<FarmId>,
     pub next_index: u32,
-    /// total (staked) balance of this seed (Farming Token)
+    /// total seed power of this seed (Farming Token),
+    /// which equals to the staked balance if nothing locked.
     pub amount: Balance,
     pub min_deposit: Balance,
+    /// locking is disabled when max_locking_duration_sec is 0
+    pub lock_config: SeedLockConfig,
+    /// total locked balance
+    pub locked_amount: Balance,
+    /// seed slashed from early unlock, waiting for owner to withdraw
+    pub slashed_amount: Balance,
 }
 
 impl FarmSeed {
@@ -55,6 +64,9 @@ impl FarmSeed {
             next_index: 0,
             amount: 0,
             min_deposit,
+            lock_config: SeedLockConfig::default(),
+            locked_amount: 0,
+            slashed_amount: 0,
         }
     }
 
@@ -77,19 +89,21 @@ impl FarmSeed {
 /// each function of this enum should be carefully re-code!
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum VersionedFarmSeed {
-    V101(FarmSeed),
+    V101(FarmSeedV101),
+    V102(FarmSeed),
 }
 
 impl VersionedFarmSeed {
 
     pub fn new(seed_id: &SeedId, min_deposit: Balance) -> Self {
-        VersionedFarmSeed::V101(FarmSeed::new(seed_id, min_deposit))
+        VersionedFarmSeed::V102(FarmSeed::new(seed_id, min_deposit))
     }
 
     /// Upgrades from other versions to the currently used version.
     pub fn upgrade(self) -> Self {
         match self {
-            VersionedFarmSeed::V101(farm_seed) => VersionedFarmSeed::V101(farm_seed),
+            VersionedFarmSeed::V101(farm_seed) => VersionedFarmSeed::V102(farm_seed.into()),
+            VersionedFarmSeed::V102(farm_seed) => VersionedFarmSeed::V102(farm_seed),
         }
     }
 
@@ -97,7 +111,7 @@ impl VersionedFarmSeed {
     #[allow(unreachable_patterns)]
     pub fn need_upgrade(&self) -> bool {
         match self {
-            VersionedFarmSeed::V101(_) => false,
+            VersionedFarmSeed::V102(_) => false,
             _ => true,
         }
     }
@@ -106,7 +120,7 @@ impl VersionedFarmSeed {
     #[allow(unreachable_patterns)]
     pub fn get_ref(&self) -> &FarmSeed {
         match self {
-            VersionedFarmSeed::V101(farm_seed) => farm_seed,
+            VersionedFarmSeed::V102(farm_seed) => farm_seed,
             _ => unimplemented!(),
         }
     }
@@ -115,7 +129,7 @@ impl VersionedFarmSeed {
     #[allow(unreachable_patterns)]
     pub fn get_ref_mut(&mut self) -> &mut FarmSeed {
         match self {
-            VersionedFarmSeed::V101(farm_seed) => farm_seed,
+            VersionedFarmSeed::V102(farm_seed) => farm_seed,
             _ => unimplemented!(),
         }
     }
@@ -129,8 +143,16 @@ pub struct SeedInfo {
     pub seed_type: String,
     pub farms: Vec<FarmId>,
     pub next_index: u32,
+    /// total seed power
     pub amount: U128,
     pub min_deposit: U128,
+    pub locked_amount: U128,
+    pub slashed_amount: U128,
+    pub max_locking_duration_sec: u32,
+    /// in basis points, 10000 means 1x
+    pub max_locking_multiplier: u32,
+    /// in basis points, of the locked seed unlocked right after locking
+    pub slash_rate: u32,
 }
 
 impl From<&FarmSeed> for SeedInfo {
@@ -146,6 +168,11 @@ impl From<&FarmSeed> for SeedInfo {
             next_index: fs.next_index,
             amount: fs.amount.into(),
             min_deposit: fs.min_deposit.into(),
+            locked_amount: fs.locked_amount.into(),
+            slashed_amount: fs.slashed_amount.into(),
+            max_locking_duration_sec: fs.lock_config.max_locking_duration_sec,
+            max_locking_multiplier: fs.lock_config.max_locking_multiplier,
+            slash_rate: fs.lock_config.slash_rate,
             farms: fs.farms.iter().map(|key| key.clone()).collect(),
         }
     }
@@ -1,7 +1,10 @@
 //! Farmer records a farmer's 
 //! * all claimed reward tokens, 
 //! * all seeds he staked,
+//! * seeds he locked for more seed power,
 //! * user_rps per farm,
+//! * reward he claimed so far, per farm and per reward token,
+//! * who can claim for him and where the claimed reward goes,
 //! and the deposited near amount prepaid as storage fee
 
 
@@ -13,10 +16,17 @@ use crate::{SeedId, FarmId, RPS};
 use crate::errors::*;
 use crate::utils::MAX_ACCOUNT_LENGTH;
 use crate::StorageKeys;
+use crate::legacy::{FarmerV101, FarmerV102, FarmerV103};
+use crate::reward_route::RewardRoute;
+use crate::seed_lock::SeedLock;
 /// each entry cost MAX_ACCOUNT_LENGTH bytes, 
 /// amount: Balance cost 16 bytes
 /// each empty hashmap cost 4 bytes
 pub const MIN_FARMER_LENGTH: u128 = MAX_ACCOUNT_LENGTH + 16 + 4 * 3;
+/// claimed history of a farm cost a farm_id and an empty hashmap
+pub const FARM_CLAIMED_LENGTH: u128 = 4 + 2 * MAX_ACCOUNT_LENGTH + 4;
+/// each claimed token cost a token_id and a Balance
+pub const TOKEN_CLAIMED_LENGTH: u128 = 4 + MAX_ACCOUNT_LENGTH + 16;
 
 /// Account deposits information and storage cost.
 #[derive(BorshSerialize, BorshDeserialize)]
@@ -32,6 +42,16 @@ pub struct Farmer {
     /// record user_last_rps of farms
     pub user_rps: LookupMap<FarmId, RPS>,
     pub rps_count: u32,
+    /// Seeds locked for more seed power, not in `seeds`.
+    pub locks: HashMap<SeedId, SeedLock>,
+    /// Cumulative claimed amount of each reward token per farm.
+    pub farm_claimed: HashMap<FarmId, HashMap<AccountId, Balance>>,
+    /// Cumulative claimed amount per reward token.
+    pub token_claimed: HashMap<AccountId, Balance>,
+    /// Account allowed to claim and route reward for the farmer.
+    pub claim_delegate: Option<AccountId>,
+    /// Where routed reward goes, None means the farmer himself.
+    pub reward_route: Option<RewardRoute>,
 }
 
 impl Farmer {
@@ -45,6 +65,14 @@ impl Farmer {
         }
     }
 
+    /// Records amount of token claimed from farm into claimed history,
+    /// its storage is counted in storage_usage and charged as other farmer data.
+    pub(crate) fn add_claimed(&mut self, farm_id: &FarmId, token: &AccountId, amount: Balance) {
+        let farm_claimed = self.farm_claimed.entry(farm_id.clone()).or_default();
+        *farm_claimed.entry(token.clone()).or_default() += amount;
+        *self.token_claimed.entry(token.clone()).or_default() += amount;
+    }
+
     /// Subtract from `reward` balance.
     /// if amount == 0, subtract all reward balance.
     /// Panics if `amount` is bigger than the current balance.
@@ -84,6 +112,19 @@ impl Farmer {
         cur_balance
     }
 
+    /// staked seed plus the power of locked seed,
+    /// it is the amount farms count in.
+    pub fn get_seed_power(&self, seed_id: &SeedId) -> Balance {
+        self.seeds.get(seed_id).unwrap_or(&0_u128)
+            + self.locks.get(seed_id).map(|lock| lock.x_locked_amount).unwrap_or(0)
+    }
+
+    /// Drop power of expired lock of the seed to 1x,
+    /// return the power dropped.
+    pub(crate) fn expire_lock(&mut self, seed_id: &SeedId) -> Balance {
+        self.locks.get_mut(seed_id).map(|lock| lock.expire()).unwrap_or(0)
+    }
+
     pub fn get_rps(&self, farm_id: &FarmId) -> RPS {
         self.user_rps.get(farm_id).unwrap_or(RPS::default()).clone()
     }
@@ -109,6 +150,13 @@ impl Farmer {
             + self.rewards.len() as u128 * (4 + MAX_ACCOUNT_LENGTH + 16)
             + self.seeds.len() as u128 * (4 + MAX_ACCOUNT_LENGTH + 16)
             + self.rps_count as u128 * (4 + 1 + 2 * MAX_ACCOUNT_LENGTH + 32)
+            + self.locks.len() as u128 * (4 + MAX_ACCOUNT_LENGTH + 16 * 2 + 4 * 2)
+            + self.farm_claimed.values().map(
+                |claimed| FARM_CLAIMED_LENGTH + claimed.len() as u128 * TOKEN_CLAIMED_LENGTH
+            ).sum::<u128>()
+            + self.token_claimed.len() as u128 * TOKEN_CLAIMED_LENGTH
+            + self.claim_delegate.as_ref().map(|_| 1 + 4 + MAX_ACCOUNT_LENGTH).unwrap_or(0)
+            + self.reward_route.as_ref().map(|route| route.storage_length()).unwrap_or(0)
         )
         * env::storage_byte_cost()
     }
@@ -121,13 +169,16 @@ impl Farmer {
 /// each function of this enum should be carefully re-code!
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum VersionedFarmer {
-    V101(Farmer),
+    V101(FarmerV101),
+    V102(FarmerV102),
+    V103(FarmerV103),
+    V104(Farmer),
 }
 
 impl VersionedFarmer {
 
     pub fn new(farmer_id: AccountId, amount: Balance) -> Self {
-        VersionedFarmer::V101(Farmer {
+        VersionedFarmer::V104(Farmer {
             amount: amount,
             rewards: HashMap::new(),
             seeds: HashMap::new(),
@@ -135,13 +186,21 @@ impl VersionedFarmer {
                 account_id: farmer_id.clone(),
             }),
             rps_count: 0,
+            locks: HashMap::new(),
+            farm_claimed: HashMap::new(),
+            token_claimed: HashMap::new(),
+            claim_delegate: None,
+            reward_route: None,
         })
     }
 
     /// Upgrades from other versions to the currently used version.
     pub fn upgrade(self) -> Self {
         match self {
-            VersionedFarmer::V101(farmer) => VersionedFarmer::V101(farmer),
+            VersionedFarmer::V101(farmer) => VersionedFarmer::V104(FarmerV103::from(FarmerV102::from(farmer)).into()),
+            VersionedFarmer::V102(farmer) => VersionedFarmer::V104(FarmerV103::from(farmer).into()),
+            VersionedFarmer::V103(farmer) => VersionedFarmer::V104(farmer.into()),
+            VersionedFarmer::V104(farmer) => VersionedFarmer::V104(farmer),
         }
     }
 
@@ -149,7 +208,7 @@ impl VersionedFarmer {
     #[allow(unreachable_patterns)]
     pub fn need_upgrade(&self) -> bool {
         match self {
-            VersionedFarmer::V101(_) => false,
+            VersionedFarmer::V104(_) => false,
             _ => true,
         }
     }
@@ -158,7 +217,7 @@ impl VersionedFarmer {
     #[allow(unreachable_patterns)]
     pub fn get_ref(&self) -> &Farmer {
         match self {
-            VersionedFarmer::V101(farmer) => farmer,
+            VersionedFarmer::V104(farmer) => farmer,
             _ => unimplemented!(),
         }
     }
@@ -167,7 +226,7 @@ impl VersionedFarmer {
     #[allow(unreachable_patterns)]
     pub fn get(self) -> Farmer {
         match self {
-            VersionedFarmer::V101(farmer) => farmer,
+            VersionedFarmer::V104(farmer) => farmer,
             _ => unimplemented!(),
         }
     }
@@ -176,7 +235,7 @@ impl VersionedFarmer {
     #[allow(unreachable_patterns)]
     pub fn get_ref_mut(&mut self) -> &mut Farmer {
         match self {
-            VersionedFarmer::V101(farmer) => farmer,
+            VersionedFarmer::V104(farmer) => farmer,
             _ => unimplemented!(),
         }
     }
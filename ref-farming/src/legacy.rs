@@ -2,11 +2,13 @@
 use near_sdk::collections::{UnorderedMap, LookupMap};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{AccountId, Balance};
+use std::collections::{HashMap, HashSet};
 
 use crate::farm::{Farm, FarmId};
-use crate::farm_seed::{VersionedFarmSeed, SeedId};
-use crate::farmer::VersionedFarmer;
-use crate::{ContractData, RunningState};
+use crate::farm_seed::{VersionedFarmSeed, FarmSeed, SeedId, SeedType};
+use crate::farmer::{VersionedFarmer, Farmer};
+use crate::seed_lock::{SeedLock, SeedLockConfig};
+use crate::{ContractData, RunningState, StorageKeys, RPS};
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct ContractDataV0104 {
@@ -39,6 +41,180 @@ impl From<ContractDataV0104> for ContractData {
             farmer_count,
             reward_info,
             state: RunningState::Running,
+            farm_creators: LookupMap::new(StorageKeys::FarmCreator),
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractDataV0110 {
+    pub owner_id: AccountId,
+    pub seeds: UnorderedMap<SeedId, VersionedFarmSeed>,
+    pub farmers: LookupMap<AccountId, VersionedFarmer>,
+    pub farms: UnorderedMap<FarmId, Farm>,
+    pub outdated_farms: UnorderedMap<FarmId, Farm>,
+    pub farmer_count: u64,
+    pub reward_info: UnorderedMap<AccountId, Balance>,
+    pub state: RunningState,
+}
+
+impl From<ContractDataV0110> for ContractData {
+    fn from(a: ContractDataV0110) -> Self {
+        let ContractDataV0110 {
+            owner_id,
+            seeds,
+            farmers,
+            farms,
+            outdated_farms,
+            farmer_count,
+            reward_info,
+            state,
+        } = a;
+        Self {
+            owner_id,
+            seeds,
+            farmers,
+            farms,
+            outdated_farms,
+            farmer_count,
+            reward_info,
+            state,
+            farm_creators: LookupMap::new(StorageKeys::FarmCreator),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FarmSeedV101 {
+    pub seed_id: SeedId,
+    pub seed_type: SeedType,
+    pub farms: HashSet<FarmId>,
+    pub next_index: u32,
+    pub amount: Balance,
+    pub min_deposit: Balance,
+}
+
+impl From<FarmSeedV101> for FarmSeed {
+    fn from(a: FarmSeedV101) -> Self {
+        let FarmSeedV101 {
+            seed_id,
+            seed_type,
+            farms,
+            next_index,
+            amount,
+            min_deposit,
+        } = a;
+        Self {
+            seed_id,
+            seed_type,
+            farms,
+            next_index,
+            amount,
+            min_deposit,
+            lock_config: SeedLockConfig::default(),
+            locked_amount: 0,
+            slashed_amount: 0,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FarmerV101 {
+    pub amount: Balance,
+    pub rewards: HashMap<AccountId, Balance>,
+    pub seeds: HashMap<SeedId, Balance>,
+    pub user_rps: LookupMap<FarmId, RPS>,
+    pub rps_count: u32,
+}
+
+impl From<FarmerV101> for FarmerV102 {
+    fn from(a: FarmerV101) -> Self {
+        let FarmerV101 {
+            amount,
+            rewards,
+            seeds,
+            user_rps,
+            rps_count,
+        } = a;
+        Self {
+            amount,
+            rewards,
+            seeds,
+            user_rps,
+            rps_count,
+            locks: HashMap::new(),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FarmerV102 {
+    pub amount: Balance,
+    pub rewards: HashMap<AccountId, Balance>,
+    pub seeds: HashMap<SeedId, Balance>,
+    pub user_rps: LookupMap<FarmId, RPS>,
+    pub rps_count: u32,
+    pub locks: HashMap<SeedId, SeedLock>,
+}
+
+impl From<FarmerV102> for FarmerV103 {
+    fn from(a: FarmerV102) -> Self {
+        let FarmerV102 {
+            amount,
+            rewards,
+            seeds,
+            user_rps,
+            rps_count,
+            locks,
+        } = a;
+        Self {
+            amount,
+            rewards,
+            seeds,
+            user_rps,
+            rps_count,
+            locks,
+            farm_claimed: HashMap::new(),
+            token_claimed: HashMap::new(),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FarmerV103 {
+    pub amount: Balance,
+    pub rewards: HashMap<AccountId, Balance>,
+    pub seeds: HashMap<SeedId, Balance>,
+    pub user_rps: LookupMap<FarmId, RPS>,
+    pub rps_count: u32,
+    pub locks: HashMap<SeedId, SeedLock>,
+    pub farm_claimed: HashMap<FarmId, HashMap<AccountId, Balance>>,
+    pub token_claimed: HashMap<AccountId, Balance>,
+}
+
+impl From<FarmerV103> for Farmer {
+    fn from(a: FarmerV103) -> Self {
+        let FarmerV103 {
+            amount,
+            rewards,
+            seeds,
+            user_rps,
+            rps_count,
+            locks,
+            farm_claimed,
+            token_claimed,
+        } = a;
+        Self {
+            amount,
+            rewards,
+            seeds,
+            user_rps,
+            rps_count,
+            locks,
+            farm_claimed,
+            token_claimed,
+            claim_delegate: None,
+            reward_route: None,
+        }
+    }
+}
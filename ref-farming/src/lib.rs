@@ -15,11 +15,15 @@ use crate::simple_farm::{RPS};
 use crate::farm_seed::{VersionedFarmSeed, SeedId};
 use crate::farmer::{VersionedFarmer, Farmer};
 use crate::errors::ERR600_CONTRACT_PAUSED;
-use crate::legacy::ContractDataV0104;
+use crate::legacy::{ContractDataV0104, ContractDataV0110};
+use crate::farm_creator::FarmCreator;
 
 // for simulator test
 pub use crate::simple_farm::HRSimpleFarmTerms;
-pub use crate::view::FarmInfo;
+pub use crate::multi_reward_farm::HRMultiRewardFarmTerms;
+pub use crate::view::{FarmInfo, FarmRewardInfo, FarmProjection, FarmRewardProjection, FarmerClaimHistory};
+pub use crate::seed_lock::SeedLockInfo;
+pub use crate::reward_route::{RewardRoute, RewardRouteInfo};
 
 
 mod utils;
@@ -28,13 +32,19 @@ mod farmer;
 mod token_receiver;
 mod farm_seed;
 mod farm;
+mod farm_creator;
+mod seed_lock;
+mod reward_route;
 mod simple_farm;
+mod multi_reward_farm;
+mod continuous_farm;
 mod storage_impl;
 
 mod actions_of_farm;
 mod actions_of_seed;
 mod actions_of_reward;
 mod view;
+mod events;
 
 mod owner;
 mod legacy;
@@ -50,6 +60,7 @@ pub enum StorageKeys {
     Farmer,
     RewardInfo,
     UserRps { account_id: AccountId },
+    FarmCreator,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -82,13 +93,18 @@ pub struct ContractData {
 
     /// Running state
     state: RunningState,
+
+    /// creator of farms, farms created before v1.2.0 have no record,
+    /// their creator is the owner.
+    farm_creators: LookupMap<FarmId, FarmCreator>,
 }
 
 /// Versioned contract data. Allows to easily upgrade contracts.
 #[derive(BorshSerialize, BorshDeserialize)]
 pub enum VersionedContractData {
     V0104(ContractDataV0104),
-    V0110(ContractData),
+    V0110(ContractDataV0110),
+    V0120(ContractData),
 }
 
 impl VersionedContractData {}
@@ -106,7 +122,7 @@ impl Contract {
     pub fn new(owner_id: ValidAccountId) -> Self {
         assert!(!env::state_exists(), "Already initialized");
         Self {
-            data: VersionedContractData::V0110(ContractData {
+            data: VersionedContractData::V0120(ContractData {
                 owner_id: owner_id.into(),
                 farmer_count: 0,
                 seeds: UnorderedMap::new(StorageKeys::Seed),
@@ -115,6 +131,7 @@ impl Contract {
                 outdated_farms: UnorderedMap::new(StorageKeys::OutdatedFarm),
                 reward_info: UnorderedMap::new(StorageKeys::RewardInfo),
                 state: RunningState::Running,
+                farm_creators: LookupMap::new(StorageKeys::FarmCreator),
             }),
         }
     }
@@ -123,14 +140,14 @@ impl Contract {
 impl Contract {
     fn data(&self) -> &ContractData {
         match &self.data {
-            VersionedContractData::V0110(data) => data,
+            VersionedContractData::V0120(data) => data,
             _ => unimplemented!(),
         }
     }
 
     fn data_mut(&mut self) -> &mut ContractData {
         match &mut self.data {
-            VersionedContractData::V0110(data) => data,
+            VersionedContractData::V0120(data) => data,
             _ => unimplemented!(),
         }
     }
@@ -144,6 +161,7 @@ mod tests {
     use near_sdk::{testing_env, Balance, MockedBlockchain};
     use near_sdk::json_types::{ValidAccountId, U128};
     use simple_farm::{HRSimpleFarmTerms};
+    use multi_reward_farm::{HRMultiRewardFarmTerms};
     use near_contract_standards::storage_management::{StorageBalance, StorageManagement};
 
     use super::utils::*;
@@ -175,7 +193,7 @@ mod tests {
             start_at: 0,
             reward_per_session: U128(session_amount),
             session_interval: session_interval,
-        }, Some(U128(10)))
+        }, Some(U128(10)), None)
     }
 
     fn deposit_reward(
@@ -298,6 +316,12 @@ mod tests {
         contract.remove_user_rps_by_farm(farm_id)
     }
 
+    /// unclaimed reward of charlie, the reward token of farms in these tests
+    fn get_unclaimed(contract: &Contract, farmer: ValidAccountId, farm_id: &FarmId) -> U128 {
+        contract.get_unclaimed_reward(farmer, farm_id.clone())
+            .get(accounts(2).as_ref()).cloned().unwrap_or(U128(0))
+    }
+
     fn to_yocto(value: &str) -> u128 {
         let vals: Vec<_> = value.split('.').collect();
         let part1 = vals[0].parse::<u128>().unwrap() * 10u128.pow(24);
@@ -335,7 +359,7 @@ mod tests {
         // Farmer accounts(0) come in round 1
         register_farmer(&mut context, &mut contract, accounts(0));
         deposit_seed(&mut context, &mut contract, accounts(0), 160, 10);
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(0));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.beneficiary_reward, U128(5000));
@@ -348,7 +372,7 @@ mod tests {
             .block_timestamp(to_nano(210))
             .is_view(true)
             .build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(5000));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 2);
@@ -358,9 +382,9 @@ mod tests {
         register_farmer(&mut context, &mut contract, accounts(3));
         // deposit seed
         deposit_seed(&mut context, &mut contract, accounts(3), 260, 10);
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(0));
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(10000));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 3);
@@ -372,9 +396,9 @@ mod tests {
             .block_timestamp(to_nano(320))
             .is_view(true)
             .build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(12500));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(2500));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 4);
@@ -386,9 +410,9 @@ mod tests {
         withdraw_seed(&mut context, &mut contract, accounts(3), 370, 10);
         testing_env!(context.predecessor_account_id(accounts(0))
             .block_timestamp(to_nano(380)).is_view(true).build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(0));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(0));
         let rewarded = contract.get_reward(accounts(0), accounts(2));
         assert_eq!(rewarded, U128(15000));
@@ -402,9 +426,9 @@ mod tests {
         // move to round 7, account3 come in again
         println!("----> move to round 7, account3 come in again");
         deposit_seed(&mut context, &mut contract, accounts(3), 460, 10);
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(0));
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(0));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.beneficiary_reward, U128(15000));
@@ -414,9 +438,9 @@ mod tests {
         // move to round 8, account0 come in again
         println!("----> move to round 8, account0 come in again");
         deposit_seed(&mut context, &mut contract, accounts(0), 520, 10);
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(5000));
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(0));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 8);
@@ -428,9 +452,9 @@ mod tests {
             .block_timestamp(to_nano(580))
             .is_view(true)
             .build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(2500));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(7500));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 9);
@@ -443,9 +467,9 @@ mod tests {
             .block_timestamp(to_nano(610))
             .is_view(true)
             .build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(5000));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(10000));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 10);
@@ -459,17 +483,17 @@ mod tests {
             .block_timestamp(to_nano(710))
             .is_view(true)
             .build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(5000));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(10000));
         claim_reward(&mut context, &mut contract, accounts(0), 720);
         claim_reward(&mut context, &mut contract, accounts(3), 730);
         testing_env!(context.predecessor_account_id(accounts(0))
             .block_timestamp(to_nano(740)).is_view(true).build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(0));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed, U128(0));
         let rewarded = contract.get_reward(accounts(0), accounts(2));
         assert_eq!(rewarded, U128(20000));
@@ -509,6 +533,734 @@ mod tests {
         
     }
 
+    #[test]
+    fn test_multi_reward_farm() {
+        let (mut context, mut contract) = setup_contract();
+        // seed is bob, rewards are charlie and danny
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(env::storage_byte_cost() * 1000)
+            .build());
+        let farm_id = contract.create_multi_reward_farm(HRMultiRewardFarmTerms {
+            seed_id: accounts(1).into(),
+            reward_tokens: vec![accounts(2), accounts(3)],
+            start_at: 0,
+            rewards_per_session: vec![U128(5000), U128(1000)],
+            session_interval: 50,
+        }, Some(U128(10)), None);
+        assert_eq!(farm_id, String::from("bob#0"));
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.farm_kind, String::from("MULTI_REWARD_FARM"));
+
+        // only charlie deposited, farm can't start
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.farm_status, String::from("Created"));
+        assert_eq!(farm_info.start_at, 0);
+
+        // danny funds 5 sessions, farm starts
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(100))
+            .attached_deposit(1)
+            .build());
+        contract.ft_on_transfer(accounts(0), U128(5000), farm_id.clone());
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.farm_status, String::from("Running"));
+        assert_eq!(farm_info.start_at, 100);
+        assert_eq!(farm_info.rewards[0].total_reward, U128(50000));
+        assert_eq!(farm_info.rewards[1].total_reward, U128(5000));
+
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+
+        // move to round 2, both tokens distributed from one rps
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(210))
+            .is_view(true)
+            .build());
+        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        assert_eq!(unclaimed.len(), 2);
+        assert_eq!(unclaimed.get(accounts(2).as_ref()).unwrap(), &U128(10000));
+        assert_eq!(unclaimed.get(accounts(3).as_ref()).unwrap(), &U128(2000));
+        claim_reward(&mut context, &mut contract, accounts(0), 210);
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(10000));
+        assert_eq!(contract.get_reward(accounts(0), accounts(3)), U128(2000));
+        assert_eq!(contract.get_user_rps(accounts(0), farm_id.clone()), format!("2{}", "0".repeat(47)));
+
+        // danny runs out after 5 sessions, the farm ends there
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(500))
+            .is_view(true)
+            .build());
+        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        assert_eq!(unclaimed.get(accounts(2).as_ref()).unwrap(), &U128(15000));
+        assert_eq!(unclaimed.get(accounts(3).as_ref()).unwrap(), &U128(3000));
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.farm_status, String::from("Ended"));
+        assert_eq!(farm_info.cur_round, 5);
+        claim_reward(&mut context, &mut contract, accounts(0), 500);
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(25000));
+        assert_eq!(contract.get_reward(accounts(0), accounts(3)), U128(5000));
+
+        // charlie left over goes to beneficiary
+        remove_farm(&mut context, &mut contract, 500);
+        let farm_info = contract.get_outdated_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.farm_status, String::from("Cleared"));
+        assert_eq!(farm_info.rewards[0].beneficiary_reward, U128(25000));
+        assert_eq!(farm_info.rewards[1].beneficiary_reward, U128(0));
+        assert_eq!(farm_info.rewards[0].claimed_reward, U128(50000));
+    }
+
+    #[test]
+    fn test_continuous_farm() {
+        let (mut context, mut contract) = setup_contract();
+        // seed is bob, reward is charlie, 5000 per 50 secs streamed per second
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(env::storage_byte_cost() * 559)
+            .build());
+        let farm_id = contract.create_continuous_farm(HRSimpleFarmTerms {
+            seed_id: accounts(1).into(),
+            reward_token: accounts(2).into(),
+            start_at: 0,
+            reward_per_session: U128(5000),
+            session_interval: 50,
+        }, Some(U128(10)), None);
+        assert_eq!(farm_id, String::from("bob#0"));
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.farm_kind, String::from("CONTINUOUS_FARM"));
+
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+
+        // half a session streamed
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(125))
+            .is_view(true)
+            .build());
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(2500));
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.cur_round, 0);
+
+        // accounts(3) only shares reward after it comes in
+        register_farmer(&mut context, &mut contract, accounts(3));
+        deposit_seed(&mut context, &mut contract, accounts(3), 125, 10);
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(175))
+            .is_view(true)
+            .build());
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(5000));
+        assert_eq!(get_unclaimed(&contract, accounts(3), &farm_id), U128(2500));
+        claim_reward(&mut context, &mut contract, accounts(0), 175);
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(5000));
+
+        // all reward released at 600
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(700))
+            .is_view(true)
+            .build());
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.farm_status, String::from("Ended"));
+        assert_eq!(farm_info.last_round, 1);
+        assert_eq!(farm_info.cur_round, 12);
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(21250));
+        assert_eq!(get_unclaimed(&contract, accounts(3), &farm_id), U128(23750));
+    }
+
+    #[test]
+    fn test_continuous_farm_short_intervals() {
+        let (mut context, mut contract) = setup_contract();
+        // 10 per 3 secs, a third of reward left over each second
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(env::storage_byte_cost() * 559)
+            .build());
+        let farm_id = contract.create_continuous_farm(HRSimpleFarmTerms {
+            seed_id: accounts(1).into(),
+            reward_token: accounts(2).into(),
+            start_at: 0,
+            reward_per_session: U128(10),
+            session_interval: 3,
+        }, Some(U128(10)), None);
+        deposit_reward(&mut context, &mut contract, 1000, 100);
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+
+        // distributed every second, released in total as in one go
+        for time_stamp in 101..=130 {
+            claim_reward(&mut context, &mut contract, accounts(0), time_stamp);
+        }
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(100));
+        claim_reward(&mut context, &mut contract, accounts(0), 131);
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(103));
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.claimed_reward, U128(103));
+        assert_eq!(farm_info.unclaimed_reward, U128(0));
+    }
+
+    #[test]
+    fn test_migrate_to_continuous_farm() {
+        let (mut context, mut contract) = setup_contract();
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(125))
+            .is_view(true)
+            .build());
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(0));
+
+        // the current session streams from its start after migration
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(125))
+            .is_view(false)
+            .build());
+        contract.migrate_to_continuous_farm(farm_id.clone());
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.farm_kind, String::from("CONTINUOUS_FARM"));
+        assert_eq!(farm_info.farm_status, String::from("Running"));
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(2500));
+
+        claim_reward(&mut context, &mut contract, accounts(0), 160);
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(6000));
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(0));
+    }
+
+    #[test]
+    fn test_farm_projection_and_modify_terms() {
+        let (mut context, mut contract) = setup_contract();
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        let projection = contract.get_farm_projection(farm_id.clone()).expect("Error");
+        assert_eq!(projection.remaining_sessions, 10);
+        assert_eq!(projection.end_at, 600);
+        assert_eq!(projection.seed_amount, U128(0));
+        assert_eq!(projection.rewards[0].daily_reward, U128(8640000));
+        assert_eq!(projection.rewards[0].undistributed_reward, U128(50000));
+
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+
+        // double the rate at round 2, the first 2 rounds keep the old rate
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(210))
+            .is_view(false)
+            .build());
+        contract.modify_farm_terms(farm_id.clone(), U128(10000));
+        let projection = contract.get_farm_projection(farm_id.clone()).expect("Error");
+        assert_eq!(projection.remaining_sessions, 4);
+        assert_eq!(projection.end_at, 400);
+        assert_eq!(projection.seed_amount, U128(10));
+        assert_eq!(projection.rewards[0].reward_per_session, U128(10000));
+
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(260))
+            .is_view(true)
+            .build());
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(20000));
+        assert!(contract.get_farm_projection(String::from("bob#1")).is_none());
+    }
+
+    #[test]
+    fn test_farm_creator() {
+        let (mut context, mut contract) = setup_contract();
+        register_farmer(&mut context, &mut contract, accounts(0));
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.creator_id, accounts(0).to_string());
+
+        // no seed staked in 3 rounds, all goes to beneficiary
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(260))
+            .is_view(false)
+            .build());
+        contract.claim_beneficiary_reward_by_farm(farm_id.clone());
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(15000));
+        contract.claim_beneficiary_reward_by_farm(farm_id.clone());
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(15000));
+
+        // a farm starting in future can be cancelled with reward refunded
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(300))
+            .attached_deposit(env::storage_byte_cost() * 559)
+            .build());
+        let farm_id = contract.create_simple_farm(HRSimpleFarmTerms {
+            seed_id: accounts(1).into(),
+            reward_token: accounts(2).into(),
+            start_at: 1000,
+            reward_per_session: U128(5000),
+            session_interval: 50,
+        }, Some(U128(10)), None);
+        assert_eq!(farm_id, String::from("bob#1"));
+        testing_env!(context
+            .predecessor_account_id(accounts(2))
+            .block_timestamp(to_nano(400))
+            .attached_deposit(1)
+            .build());
+        contract.ft_on_transfer(accounts(0), U128(20000), farm_id.clone());
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(500))
+            .attached_deposit(0)
+            .build());
+        contract.cancel_farm(farm_id.clone());
+        assert!(contract.get_farm(farm_id.clone()).is_none());
+        assert_eq!(contract.list_farms_by_seed(accounts(1).into()).len(), 1);
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(35000));
+        // total reward received stays cumulative
+        assert_eq!(contract.list_rewards_info(0, 10).get(accounts(2).as_ref()), Some(&U128(70000)));
+    }
+
+    #[test]
+    #[should_panic(expected = "E48: not farm creator")]
+    fn test_cancel_farm_not_creator() {
+        let (mut context, mut contract) = setup_contract();
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .build());
+        contract.cancel_farm(farm_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "E47: farm already started")]
+    fn test_cancel_started_farm() {
+        let (mut context, mut contract) = setup_contract();
+        register_farmer(&mut context, &mut contract, accounts(0));
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(120))
+            .build());
+        contract.cancel_farm(farm_id);
+    }
+
+    fn create_farm_for_creator(
+        context: &mut VMContextBuilder,
+        contract: &mut Contract,
+        creator: ValidAccountId,
+        start_at: u32,
+    ) -> FarmId {
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(env::storage_byte_cost() * 559)
+            .build());
+        contract.create_simple_farm(HRSimpleFarmTerms {
+            seed_id: accounts(1).into(),
+            reward_token: accounts(2).into(),
+            start_at,
+            reward_per_session: U128(5000),
+            session_interval: 50,
+        }, Some(U128(10)), Some(creator))
+    }
+
+    #[test]
+    fn test_farm_for_creator() {
+        let (mut context, mut contract) = setup_contract();
+        register_farmer(&mut context, &mut contract, accounts(3));
+        let farm_id = create_farm_for_creator(&mut context, &mut contract, accounts(3), 0);
+        let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
+        assert_eq!(farm_info.creator_id, accounts(3).to_string());
+
+        // no seed staked, round 1 at the old rate and rounds 2, 3 at the new one go to beneficiary
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(150))
+            .attached_deposit(0)
+            .build());
+        contract.modify_farm_terms(farm_id.clone(), U128(10000));
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(260))
+            .build());
+        contract.claim_beneficiary_reward_by_farm(farm_id.clone());
+        assert_eq!(contract.get_reward(accounts(3), accounts(2)), U128(25000));
+
+        // the creator cancels its farm starting in future
+        let farm_id = create_farm_for_creator(&mut context, &mut contract, accounts(3), 1000);
+        testing_env!(context
+            .predecessor_account_id(accounts(2))
+            .block_timestamp(to_nano(400))
+            .attached_deposit(1)
+            .build());
+        contract.ft_on_transfer(accounts(0), U128(20000), farm_id.clone());
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(500))
+            .attached_deposit(0)
+            .build());
+        contract.cancel_farm(farm_id.clone());
+        assert!(contract.get_farm(farm_id.clone()).is_none());
+        assert_eq!(contract.get_reward(accounts(3), accounts(2)), U128(45000));
+    }
+
+    #[test]
+    #[should_panic(expected = "E48: not farm creator")]
+    fn test_modify_farm_terms_not_creator() {
+        let (mut context, mut contract) = setup_contract();
+        register_farmer(&mut context, &mut contract, accounts(3));
+        let farm_id = create_farm_for_creator(&mut context, &mut contract, accounts(3), 0);
+        testing_env!(context
+            .predecessor_account_id(accounts(4))
+            .attached_deposit(0)
+            .build());
+        contract.modify_farm_terms(farm_id, U128(10000));
+    }
+
+    #[test]
+    #[should_panic(expected = "E48: not farm creator")]
+    fn test_claim_beneficiary_reward_not_creator() {
+        let (mut context, mut contract) = setup_contract();
+        register_farmer(&mut context, &mut contract, accounts(3));
+        let farm_id = create_farm_for_creator(&mut context, &mut contract, accounts(3), 0);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        // the owner isn't the creator of this farm
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(260))
+            .attached_deposit(0)
+            .build());
+        contract.claim_beneficiary_reward_by_farm(farm_id);
+    }
+
+    #[test]
+    fn test_seed_lock() {
+        let (mut context, mut contract) = setup_contract();
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        // 2x seed power for 1000 seconds, half slashed if unlock right after locking
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.modify_seed_lock_config(accounts(1).into(), 1000, 20000, 5000);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+        register_farmer(&mut context, &mut contract, accounts(3));
+        deposit_seed(&mut context, &mut contract, accounts(3), 100, 10);
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(100))
+            .attached_deposit(1)
+            .build());
+        contract.lock_seed(accounts(1).into(), U128(10), 1000);
+        let locks = contract.list_user_seed_locks(accounts(3));
+        let lock = locks.get(accounts(1).as_ref()).expect("Error");
+        assert_eq!(lock.locked_amount, U128(10));
+        assert_eq!(lock.x_locked_amount, U128(20));
+        assert_eq!(lock.unlock_at, 1100);
+        assert!(!lock.unlockable);
+        assert!(contract.list_user_seeds(accounts(3)).is_empty());
+        let seed_info = contract.get_seed_info(accounts(1).into()).expect("Error");
+        assert_eq!(seed_info.amount, U128(30));
+        assert_eq!(seed_info.locked_amount, U128(10));
+
+        // reward of round 1 shared by seed power 10 : 20
+        testing_env!(context
+            .block_timestamp(to_nano(160))
+            .is_view(true)
+            .build());
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(1666));
+        assert_eq!(get_unclaimed(&contract, accounts(3), &farm_id), U128(3333));
+
+        // unlock with half of the lock remaining, a quarter slashed
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(600))
+            .is_view(false)
+            .attached_deposit(1)
+            .build());
+        contract.unlock_seed(accounts(1).into(), U128(8));
+        assert_eq!(contract.get_reward(accounts(3), accounts(2)), U128(33333));
+        let lock = contract.list_user_seed_locks(accounts(3)).remove(accounts(1).as_ref()).expect("Error");
+        assert_eq!(lock.locked_amount, U128(2));
+        assert_eq!(lock.x_locked_amount, U128(4));
+        assert_eq!(contract.list_user_seeds(accounts(3)).get(accounts(1).as_ref()), Some(&U128(6)));
+        let seed_info = contract.get_seed_info(accounts(1).into()).expect("Error");
+        assert_eq!(seed_info.amount, U128(20));
+        assert_eq!(seed_info.locked_amount, U128(2));
+        assert_eq!(seed_info.slashed_amount, U128(2));
+
+        // no slash once expired
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(1100))
+            .attached_deposit(1)
+            .build());
+        contract.unlock_seed(accounts(1).into(), U128(2));
+        assert!(contract.list_user_seed_locks(accounts(3)).is_empty());
+        assert_eq!(contract.list_user_seeds(accounts(3)).get(accounts(1).as_ref()), Some(&U128(8)));
+        let seed_info = contract.get_seed_info(accounts(1).into()).expect("Error");
+        assert_eq!(seed_info.amount, U128(18));
+        assert_eq!(seed_info.slashed_amount, U128(2));
+    }
+
+    #[test]
+    fn test_seed_lock_expire() {
+        let (mut context, mut contract) = setup_contract();
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .build());
+        contract.modify_seed_lock_config(accounts(1).into(), 1000, 20000, 5000);
+        deposit_reward(&mut context, &mut contract, 500000, 100);
+
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+        register_farmer(&mut context, &mut contract, accounts(3));
+        deposit_seed(&mut context, &mut contract, accounts(3), 100, 10);
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(100))
+            .attached_deposit(1)
+            .build());
+        contract.lock_seed(accounts(1).into(), U128(10), 1000);
+
+        // still 2x after expired, until someone expires it
+        testing_env!(context
+            .block_timestamp(to_nano(1100))
+            .is_view(true)
+            .build());
+        let lock = contract.list_user_seed_locks(accounts(3)).remove(accounts(1).as_ref()).expect("Error");
+        assert!(lock.unlockable);
+        assert_eq!(lock.x_locked_amount, U128(20));
+
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(1100))
+            .is_view(false)
+            .build());
+        contract.expire_seed_lock(accounts(3), accounts(1).into());
+        assert_eq!(contract.get_reward(accounts(3), accounts(2)), U128(66666));
+        let lock = contract.list_user_seed_locks(accounts(3)).remove(accounts(1).as_ref()).expect("Error");
+        assert_eq!(lock.locked_amount, U128(10));
+        assert_eq!(lock.x_locked_amount, U128(10));
+        assert_eq!(contract.get_seed_info(accounts(1).into()).expect("Error").amount, U128(20));
+
+        // reward after expiring shared 1:1
+        testing_env!(context
+            .block_timestamp(to_nano(1150))
+            .is_view(true)
+            .build());
+        assert_eq!(get_unclaimed(&contract, accounts(3), &farm_id), U128(2500));
+    }
+
+    #[test]
+    fn test_claim_history() {
+        let (mut context, mut contract) = setup_contract();
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        register_farmer(&mut context, &mut contract, accounts(0));
+        assert!(contract.get_farmer_claim_history(accounts(0)).tokens.is_empty());
+        assert!(contract.get_farmer_claim_history(accounts(3)).farms.is_empty());
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+
+        claim_reward(&mut context, &mut contract, accounts(0), 160);
+        let history = contract.get_farmer_claim_history(accounts(0));
+        assert_eq!(history.tokens.get(accounts(2).as_ref()), Some(&U128(5000)));
+        assert_eq!(history.farms.get(&farm_id).unwrap().get(accounts(2).as_ref()), Some(&U128(5000)));
+
+        // claimed history stays after reward withdrawn
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        contract.withdraw_reward(accounts(2), None);
+        claim_reward_by_seed(&mut context, &mut contract, accounts(0), 210);
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(5000));
+        let history = contract.get_farmer_claim_history(accounts(0));
+        assert_eq!(history.tokens.get(accounts(2).as_ref()), Some(&U128(10000)));
+        assert_eq!(history.farms.get(&farm_id).unwrap().get(accounts(2).as_ref()), Some(&U128(10000)));
+    }
+
+    #[test]
+    fn test_claim_history_farms() {
+        let (mut context, mut contract) = setup_contract();
+        let farm_id0 = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        let farm_id1 = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 2000, 50);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        testing_env!(context
+            .predecessor_account_id(accounts(2))
+            .block_timestamp(to_nano(100))
+            .attached_deposit(1)
+            .build());
+        contract.ft_on_transfer(accounts(0), U128(20000), farm_id1.clone());
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+
+        claim_reward_by_seed(&mut context, &mut contract, accounts(0), 160);
+        claim_reward_by_seed(&mut context, &mut contract, accounts(0), 260);
+        let history = contract.get_farmer_claim_history(accounts(0));
+        assert_eq!(history.farms.len(), 2);
+        assert_eq!(history.farms.get(&farm_id0).unwrap().get(accounts(2).as_ref()), Some(&U128(15000)));
+        assert_eq!(history.farms.get(&farm_id1).unwrap().get(accounts(2).as_ref()), Some(&U128(6000)));
+        assert_eq!(history.tokens.get(accounts(2).as_ref()), Some(&U128(21000)));
+
+        // claiming one farm leaves the other's history
+        claim_reward(&mut context, &mut contract, accounts(0), 310);
+        let history = contract.get_farmer_claim_history(accounts(0));
+        assert_eq!(history.farms.get(&farm_id0).unwrap().get(accounts(2).as_ref()), Some(&U128(20000)));
+        assert_eq!(history.farms.get(&farm_id1).unwrap().get(accounts(2).as_ref()), Some(&U128(6000)));
+        assert_eq!(history.tokens.get(accounts(2).as_ref()), Some(&U128(26000)));
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(26000));
+    }
+
+    #[test]
+    fn test_claim_history_storage() {
+        let (_, _) = setup_contract();
+        let mut farmer = VersionedFarmer::new(accounts(0).into(), 0).get();
+        let storage_usage = farmer.storage_usage();
+        let farm_claimed = (crate::farmer::FARM_CLAIMED_LENGTH + crate::farmer::TOKEN_CLAIMED_LENGTH)
+            * env::storage_byte_cost();
+        let token_claimed = crate::farmer::TOKEN_CLAIMED_LENGTH * env::storage_byte_cost();
+
+        // history is counted in storage usage, so claims are charged by assert_storage_usage
+        farmer.add_claimed(&String::from("bob#0"), accounts(2).as_ref(), 100);
+        assert_eq!(farmer.storage_usage(), storage_usage + farm_claimed + token_claimed);
+        farmer.add_claimed(&String::from("bob#0"), accounts(2).as_ref(), 50);
+        assert_eq!(farmer.storage_usage(), storage_usage + farm_claimed + token_claimed);
+        farmer.add_claimed(&String::from("bob#1"), accounts(2).as_ref(), 10);
+        assert_eq!(farmer.storage_usage(), storage_usage + 2 * farm_claimed + token_claimed);
+        assert_eq!(farmer.farm_claimed.get("bob#0").unwrap().get(accounts(2).as_ref()), Some(&150));
+        assert_eq!(farmer.token_claimed.get(accounts(2).as_ref()), Some(&160));
+    }
+
+    #[test]
+    fn test_claim_and_route() {
+        let (mut context, mut contract) = setup_contract();
+        let farm_id = create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+        assert_eq!(contract.get_reward_route(accounts(0)), Some(RewardRouteInfo {
+            claim_delegate: None,
+            reward_route: None,
+        }));
+        assert!(contract.get_reward_route(accounts(3)).is_none());
+
+        // accounts(3) as keeper, swapped reward goes to accounts(4)
+        let route = RewardRoute::Exchange {
+            exchange_id: accounts(5).into(),
+            msg: format!("{{\"actions\":[],\"swap_out_recipient\":\"{}\"}}", accounts(4).as_ref()),
+        };
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        contract.set_claim_delegate(Some(accounts(3)));
+        contract.set_reward_route(Some(route.clone()));
+        assert_eq!(contract.get_reward_route(accounts(0)), Some(RewardRouteInfo {
+            claim_delegate: Some(accounts(3).into()),
+            reward_route: Some(route),
+        }));
+
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .block_timestamp(to_nano(160))
+            .attached_deposit(1)
+            .build());
+        contract.claim_and_route(accounts(0), vec![accounts(2)]);
+        assert_eq!(get_unclaimed(&contract, accounts(0), &farm_id), U128(0));
+        assert_eq!(contract.get_reward(accounts(0), accounts(2)), U128(0));
+        let history = contract.get_farmer_claim_history(accounts(0));
+        assert_eq!(history.tokens.get(accounts(2).as_ref()), Some(&U128(5000)));
+    }
+
+    #[test]
+    #[should_panic(expected = "E23: not the farmer or his claim delegate")]
+    fn test_claim_and_route_not_delegate() {
+        let (mut context, mut contract) = setup_contract();
+        register_farmer(&mut context, &mut contract, accounts(0));
+        testing_env!(context
+            .predecessor_account_id(accounts(3))
+            .attached_deposit(1)
+            .build());
+        contract.claim_and_route(accounts(0), vec![accounts(2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "E26: invalid number of reward tokens to route")]
+    fn test_claim_and_route_too_many_tokens() {
+        let (mut context, mut contract) = setup_contract();
+        register_farmer(&mut context, &mut contract, accounts(0));
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        contract.claim_and_route(accounts(0), vec![accounts(1), accounts(2), accounts(3), accounts(4)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "E27: not enough gas attached")]
+    fn test_claim_and_route_not_enough_gas() {
+        let (mut context, mut contract) = setup_contract();
+        create_farm(&mut context, &mut contract,
+            accounts(1), accounts(2), 5000, 50);
+        deposit_reward(&mut context, &mut contract, 50000, 100);
+        register_farmer(&mut context, &mut contract, accounts(0));
+        deposit_seed(&mut context, &mut contract, accounts(0), 100, 10);
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        contract.set_reward_route(Some(RewardRoute::Exchange {
+            exchange_id: accounts(5).into(),
+            msg: format!("{{\"actions\":[],\"swap_out_recipient\":\"{}\"}}", accounts(0).as_ref()),
+        }));
+        // 60T for the swap and 10T for the resolve
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .block_timestamp(to_nano(160))
+            .prepaid_gas(50_000_000_000_000)
+            .attached_deposit(1)
+            .build());
+        contract.claim_and_route(accounts(0), vec![accounts(2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "E24: invalid reward route")]
+    fn test_reward_route_without_recipient() {
+        let (mut context, mut contract) = setup_contract();
+        register_farmer(&mut context, &mut contract, accounts(0));
+        testing_env!(context
+            .predecessor_account_id(accounts(0))
+            .attached_deposit(1)
+            .build());
+        contract.set_reward_route(Some(RewardRoute::Exchange {
+            exchange_id: accounts(5).into(),
+            msg: "{\"actions\":[]}".to_string(),
+        }));
+    }
+
     #[test]
     fn test_unclaimed_rewards() {
 
@@ -524,7 +1276,7 @@ mod tests {
         // Farmer1 accounts(0) come in round 0
         register_farmer(&mut context, &mut contract, accounts(0));
         deposit_seed(&mut context, &mut contract, accounts(0), 110, to_yocto("1"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed, U128(0));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 0);
@@ -538,7 +1290,7 @@ mod tests {
             .block_timestamp(to_nano(160))
             .is_view(true)
             .build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("1"));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 1);
@@ -550,9 +1302,9 @@ mod tests {
         register_farmer(&mut context, &mut contract, accounts(3));
         // deposit seed
         deposit_seed(&mut context, &mut contract, accounts(3), 180, to_yocto("1"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("1"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0"));
 
         // move to round 2,
@@ -561,9 +1313,9 @@ mod tests {
             .block_timestamp(to_nano(210))
             .is_view(true)
             .build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("1.5"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0.5"));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 2);
@@ -573,9 +1325,9 @@ mod tests {
 
         // farmer1 claim reward by farm_id at round 3
         claim_reward(&mut context, &mut contract, accounts(0), 260);
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("1"));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 3);
@@ -585,9 +1337,9 @@ mod tests {
 
         // farmer2 claim reward by seed_id at round 4
         claim_reward_by_seed(&mut context, &mut contract, accounts(3), 310);
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0.5"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0"));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 4);
@@ -597,9 +1349,9 @@ mod tests {
 
         // farmer1 unstake half lpt at round 5
         withdraw_seed(&mut context, &mut contract, accounts(0), 360, to_yocto("0.4"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0.5"));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 5);
@@ -609,9 +1361,9 @@ mod tests {
 
         // farmer2 unstake all his lpt at round 6
         withdraw_seed(&mut context, &mut contract, accounts(3), 410, to_yocto("1"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0.375"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0"));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 6);
@@ -625,9 +1377,9 @@ mod tests {
             .block_timestamp(to_nano(460))
             .is_view(true)
             .build());
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("1.374999999999999999999999"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0"));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 7);
@@ -635,9 +1387,9 @@ mod tests {
         assert_eq!(farm_info.claimed_reward.0, to_yocto("5.625"));
         assert_eq!(farm_info.unclaimed_reward.0, to_yocto("1.375"));
         withdraw_seed(&mut context, &mut contract, accounts(0), 470, to_yocto("0.6"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(0), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(0), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0"));
-        let unclaimed = contract.get_unclaimed_reward(accounts(3), farm_id.clone());
+        let unclaimed = get_unclaimed(&contract, accounts(3), &farm_id);
         assert_eq!(unclaimed.0, to_yocto("0"));
         let farm_info = contract.get_farm(farm_id.clone()).expect("Error");
         assert_eq!(farm_info.cur_round, 7);
@@ -0,0 +1,329 @@
+//!   The MultiRewardFarm distributes several reward tokens per session from
+//! one RPS table, so that a seed with dual incentives needs only one farm,
+//! and farmers only one user_rps record for it.
+//!   Instead of reward per seed, the RPS here records sessions per seed,
+//! each reward token is then paid by its own `reward_per_session`.
+//!   A session is distributed only when every reward token has funded it,
+//! remainders that can't fund a whole session go to beneficiary when the
+//! farm is cleared.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, ValidAccountId};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, Balance};
+
+use crate::{SeedId, FarmId};
+use crate::simple_farm::{SimpleFarmStatus, RPS, DENOM, U256};
+use crate::errors::*;
+use crate::utils::*;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone)]
+pub struct MultiRewardFarmTerms {
+    pub seed_id: SeedId,
+    pub reward_tokens: Vec<AccountId>,
+    pub start_at: TimestampSec,
+    /// reward amount per session of each token, in the order of reward_tokens
+    pub rewards_per_session: Vec<Balance>,
+    pub session_interval: TimestampSec,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HRMultiRewardFarmTerms {
+    pub seed_id: SeedId,
+    pub reward_tokens: Vec<ValidAccountId>,
+    pub start_at: u32,
+    pub rewards_per_session: Vec<U128>,
+    pub session_interval: u32,
+}
+
+impl From<&HRMultiRewardFarmTerms> for MultiRewardFarmTerms {
+    fn from(terms: &HRMultiRewardFarmTerms) -> Self {
+        let reward_tokens: Vec<AccountId> = terms.reward_tokens.iter().map(|token| token.clone().into()).collect();
+        let rewards_per_session: Vec<Balance> = terms.rewards_per_session.iter().map(|amount| amount.0).collect();
+        assert!(
+            !reward_tokens.is_empty()
+            && reward_tokens.len() == rewards_per_session.len()
+            && rewards_per_session.iter().all(|amount| *amount > 0)
+            && terms.session_interval > 0,
+            "{}", ERR45_INVALID_FARM_TERMS
+        );
+        for (index, token) in reward_tokens.iter().enumerate() {
+            assert!(!reward_tokens[index + 1..].contains(token), "{}", ERR45_INVALID_FARM_TERMS);
+        }
+        MultiRewardFarmTerms {
+            seed_id: terms.seed_id.clone(),
+            reward_tokens,
+            start_at: terms.start_at,
+            rewards_per_session,
+            session_interval: terms.session_interval,
+        }
+    }
+}
+
+/// Reward Distribution Record, reward amounts are in the order of reward_tokens
+#[derive(BorshSerialize, BorshDeserialize, Clone, Default)]
+pub struct MultiRewardFarmDistribution {
+    /// unreleased reward of each token
+    pub undistributed: Vec<Balance>,
+    /// the total rewards of each token distributed but not yet claimed by farmers.
+    pub unclaimed: Vec<Balance>,
+    /// Sessions_Per_Seed
+    /// rps(cur) = rps(prev) + distributing_sessions * DENOM * DENOM / total_seed_staked
+    pub rps: RPS,
+    /// Reward_Round
+    /// rr = (cur_block_timestamp in sec - start_at) / session_interval
+    pub rr: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct MultiRewardFarm {
+
+    pub farm_id: FarmId,
+
+    pub terms: MultiRewardFarmTerms,
+
+    pub status: SimpleFarmStatus,
+
+    pub last_distribution: MultiRewardFarmDistribution,
+
+    /// total reward of each token send into this farm by far
+    pub amount_of_reward: Vec<Balance>,
+    /// reward of each token has been claimed by farmer by far
+    pub amount_of_claimed: Vec<Balance>,
+    /// when there is no seed token staked, reward goes to beneficiary
+    pub amount_of_beneficiary: Vec<Balance>,
+
+}
+
+impl MultiRewardFarm {
+    pub(crate) fn new(
+        id: FarmId,
+        terms: MultiRewardFarmTerms,
+    ) -> Self {
+        let token_count = terms.reward_tokens.len();
+        Self {
+            farm_id: id.clone(),
+            amount_of_reward: vec![0; token_count],
+            amount_of_claimed: vec![0; token_count],
+            amount_of_beneficiary: vec![0; token_count],
+
+            status: SimpleFarmStatus::Created,
+            last_distribution: MultiRewardFarmDistribution {
+                undistributed: vec![0; token_count],
+                unclaimed: vec![0; token_count],
+                rps: RPS::default(),
+                rr: 0,
+            },
+            terms,
+        }
+    }
+
+    fn token_index(&self, token_id: &AccountId) -> usize {
+        self.terms.reward_tokens.iter()
+            .position(|token| token == token_id)
+            .expect(ERR44_INVALID_FARM_REWARD)
+    }
+
+    /// How many whole sessions the undistributed rewards can fund.
+    pub(crate) fn funded_sessions(&self, undistributed: &Vec<Balance>) -> u128 {
+        undistributed.iter()
+            .zip(self.terms.rewards_per_session.iter())
+            .map(|(amount, per_session)| amount / per_session)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// return None if the farm can not accept reward anymore
+    /// else return amount of undistributed reward of the token
+    pub(crate) fn add_reward(&mut self, token_id: &AccountId, amount: &Balance) -> Option<Balance> {
+        let index = self.token_index(token_id);
+        match self.status {
+            SimpleFarmStatus::Created => {
+                self.amount_of_reward[index] += amount;
+                self.last_distribution.undistributed[index] += amount;
+                // It turns to Running state only when every reward token could fund a session
+                if self.funded_sessions(&self.last_distribution.undistributed) > 0 {
+                    self.status = SimpleFarmStatus::Running;
+                    if self.terms.start_at == 0 {
+                        self.terms.start_at = to_sec(env::block_timestamp());
+                    }
+                }
+                Some(self.last_distribution.undistributed[index])
+            },
+            SimpleFarmStatus::Running => {
+                if let Some(dis) = self.try_distribute(&DENOM) {
+                    if self.funded_sessions(&dis.undistributed) == 0 {
+                        // farm has ended actually
+                        return None;
+                    }
+                }
+                self.amount_of_reward[index] += amount;
+                self.last_distribution.undistributed[index] += amount;
+                Some(self.last_distribution.undistributed[index])
+            },
+            _ => {None},
+        }
+    }
+
+    /// Try to distribute reward according to current timestamp
+    /// return None if farm is not in Running state or haven't start farming yet;
+    /// Note, if total_seed is 0, the rps in new dis would be reset to 0 too.
+    pub(crate) fn try_distribute(&self, total_seeds: &Balance) -> Option<MultiRewardFarmDistribution> {
+        if let SimpleFarmStatus::Running = self.status {
+            if env::block_timestamp() < to_nano(self.terms.start_at) {
+                return None;
+            }
+            let mut dis = self.last_distribution.clone();
+            dis.rr = (to_sec(env::block_timestamp()) - self.terms.start_at) / self.terms.session_interval;
+            let funded = self.funded_sessions(&self.last_distribution.undistributed);
+            let mut sessions = (dis.rr - self.last_distribution.rr) as u128;
+            if funded < sessions {
+                sessions = funded;
+                dis.rr = self.last_distribution.rr + sessions as u32;
+            }
+            for (index, per_session) in self.terms.rewards_per_session.iter().enumerate() {
+                let reward_added = sessions * per_session;
+                dis.unclaimed[index] += reward_added;
+                dis.undistributed[index] -= reward_added;
+            }
+
+            if total_seeds == &0 {
+                U256::from(0).to_little_endian(&mut dis.rps);
+            } else {
+                (
+                    U256::from_little_endian(&self.last_distribution.rps) +
+                    U256::from(sessions)
+                    * U256::from(DENOM)
+                    * U256::from(DENOM)
+                    / U256::from(*total_seeds)
+                ).to_little_endian(&mut dis.rps);
+            }
+            Some(dis)
+        } else {
+            None
+        }
+    }
+
+    /// Reward of each token for sessions_per_seed of user,
+    /// sessions = user_seeds * (rps - user_rps) / DENOM, which still has a DENOM in it.
+    fn calc_rewards(&self, rps: &RPS, user_rps: &RPS, user_seeds: &Balance) -> Vec<Balance> {
+        let sessions = U256::from(*user_seeds)
+            * (U256::from_little_endian(rps) - U256::from_little_endian(user_rps))
+            / U256::from(DENOM);
+        self.terms.rewards_per_session.iter()
+            .map(|per_session| (sessions * U256::from(*per_session) / U256::from(DENOM)).as_u128())
+            .collect()
+    }
+
+    /// Return how many reward of each token that the user hasn't claimed yet.
+    pub(crate) fn view_farmer_unclaimed_reward(
+        &self,
+        user_rps: &RPS,
+        user_seeds: &Balance,
+        total_seeds: &Balance,
+    ) -> Vec<Balance> {
+        if total_seeds == &0 || user_seeds == &0 {
+            return vec![0; self.terms.reward_tokens.len()];
+        }
+        if let Some(dis) = self.try_distribute(total_seeds) {
+            self.calc_rewards(&dis.rps, user_rps, user_seeds)
+        } else {
+            self.calc_rewards(&self.last_distribution.rps, user_rps, user_seeds)
+        }
+    }
+
+    /// Distribute reward generated from previous distribution to now,
+    /// Note 1, if the undistributed can't fund a session, the farm goes to Ended state;
+    /// Note 2, if total_seed is 0, reward is claimed directly by beneficiary
+    pub(crate) fn distribute(&mut self, total_seeds: &Balance, silent: bool) {
+        if let Some(dis) = self.try_distribute(total_seeds) {
+            if self.last_distribution.rr != dis.rr {
+                self.last_distribution = dis.clone();
+                if total_seeds == &0 {
+                    for index in 0..self.terms.reward_tokens.len() {
+                        self.amount_of_claimed[index] += self.last_distribution.unclaimed[index];
+                        self.amount_of_beneficiary[index] += self.last_distribution.unclaimed[index];
+                        self.last_distribution.unclaimed[index] = 0;
+                    }
+                }
+                if !silent {
+                    env::log(
+                        format!(
+                            "{} RPS increased to {} and RR update to #{}",
+                            self.farm_id, U256::from_little_endian(&dis.rps), dis.rr,
+                        )
+                        .as_bytes(),
+                    );
+                }
+            }
+            if self.funded_sessions(&self.last_distribution.undistributed) == 0 {
+                self.status = SimpleFarmStatus::Ended;
+            }
+        }
+    }
+
+    /// Claim user's unclaimed reward in this farm,
+    /// return the new user RPS (sessions per seed),
+    /// and amount of reward of each token
+    pub(crate) fn claim_user_reward(
+        &mut self,
+        user_rps: &RPS,
+        user_seeds: &Balance,
+        total_seeds: &Balance,
+        silent: bool,
+    ) -> (RPS, Vec<Balance>) {
+        self.distribute(total_seeds, silent);
+
+        let claimed = self.calc_rewards(&self.last_distribution.rps, user_rps, user_seeds);
+        for (index, amount) in claimed.iter().enumerate() {
+            if *amount > 0 {
+                assert!(
+                    self.last_distribution.unclaimed[index] >= *amount,
+                    "{} unclaimed:{}, cur_claim:{}",
+                    ERR500, self.last_distribution.unclaimed[index], amount
+                );
+                self.last_distribution.unclaimed[index] -= amount;
+                self.amount_of_claimed[index] += amount;
+            }
+        }
+
+        (self.last_distribution.rps, claimed)
+    }
+
+    /// Move an Ended farm to Cleared,
+    /// any unclaimed reward and undistributed remainder go to beneficiary
+    pub(crate) fn move_to_clear(&mut self, total_seeds: &Balance) -> bool {
+        if let SimpleFarmStatus::Running = self.status {
+            self.distribute(total_seeds, true);
+        }
+        if let SimpleFarmStatus::Ended = self.status {
+            for index in 0..self.terms.reward_tokens.len() {
+                let left = self.last_distribution.unclaimed[index] + self.last_distribution.undistributed[index];
+                self.amount_of_claimed[index] += left;
+                self.amount_of_beneficiary[index] += left;
+                self.last_distribution.unclaimed[index] = 0;
+                self.last_distribution.undistributed[index] = 0;
+            }
+            self.status = SimpleFarmStatus::Cleared;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn can_be_removed(&self, total_seeds: &Balance) -> bool {
+        match self.status {
+            SimpleFarmStatus::Ended => true,
+            SimpleFarmStatus::Running => {
+                if let Some(dis) = self.try_distribute(total_seeds) {
+                    self.funded_sessions(&dis.undistributed) == 0
+                } else {
+                    false
+                }
+            },
+            _ => false,
+        }
+    }
+
+}
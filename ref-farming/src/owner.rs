@@ -1,4 +1,8 @@
 use crate::*;
+use crate::errors::*;
+use crate::simple_farm::SimpleFarmStatus;
+use crate::utils::parse_farm_id;
+use crate::seed_lock::{SeedLockConfig, BP_DENOM};
 use near_sdk::assert_one_yocto;
 use near_sdk::json_types::U128;
 
@@ -15,6 +19,28 @@ impl Contract {
         self.internal_remove_farm_by_farm_id(&farm_id)
     }
 
+    /// Convert a SimpleFarm in Created or Running state to ContinuousFarm,
+    /// reward of its current session would then stream from the session start.
+    /// Farmers' rps of the farm keep valid, as the farm keeps its farm_id and RPS.
+    pub fn migrate_to_continuous_farm(&mut self, farm_id: FarmId) {
+        self.assert_owner();
+        let (seed_id, _) = parse_farm_id(&farm_id);
+        let total_seeds = self.get_seed(&seed_id).get_ref().amount;
+        let farm = match self.data().farms.get(&farm_id).expect(ERR41_FARM_NOT_EXIST) {
+            Farm::SimpleFarm(mut farm) => {
+                farm.distribute(&total_seeds, true);
+                match farm.status {
+                    SimpleFarmStatus::Created | SimpleFarmStatus::Running => {},
+                    _ => env::panic(ERR43_INVALID_FARM_STATUS.as_bytes()),
+                }
+                Farm::ContinuousFarm(farm.into())
+            },
+            _ => env::panic(ERR46_INVALID_FARM_KIND.as_bytes()),
+        };
+        self.data_mut().farms.insert(&farm_id, &farm);
+        env::log(format!("Farm {} migrated to continuous farm", farm_id).as_bytes());
+    }
+
     pub fn modify_seed_min_deposit(&mut self, seed_id: String, min_deposit: U128) {
         self.assert_owner();
         let mut farm_seed = self.get_seed(&seed_id);
@@ -22,6 +48,30 @@ impl Contract {
         self.data_mut().seeds.insert(&seed_id, &farm_seed);
     }
 
+    /// Set locking rules of the seed, multiplier and slash rate are in basis points,
+    /// max_locking_duration_sec of 0 disables locking.
+    /// Existing locks keep their seed power till relocked or unlocked.
+    pub fn modify_seed_lock_config(
+        &mut self,
+        seed_id: SeedId,
+        max_locking_duration_sec: u32,
+        max_locking_multiplier: u32,
+        slash_rate: u32,
+    ) {
+        self.assert_owner();
+        assert!(
+            max_locking_multiplier >= BP_DENOM && slash_rate <= BP_DENOM,
+            "{}", ERR36_INVALID_LOCK_CONFIG
+        );
+        let mut farm_seed = self.get_seed(&seed_id);
+        farm_seed.get_ref_mut().lock_config = SeedLockConfig {
+            max_locking_duration_sec,
+            max_locking_multiplier,
+            slash_rate,
+        };
+        self.data_mut().seeds.insert(&seed_id, &farm_seed);
+    }
+
     #[payable]
     pub fn pause_contract(&mut self) {
         assert_one_yocto();
@@ -57,8 +107,9 @@ impl Contract {
         // see if ContractData need upgrade
         contract.data = 
         match contract.data {
-            VersionedContractData::V0104(data) => VersionedContractData::V0110(data.into()),
-            VersionedContractData::V0110(data) => VersionedContractData::V0110(data),
+            VersionedContractData::V0104(data) => VersionedContractData::V0120(data.into()),
+            VersionedContractData::V0110(data) => VersionedContractData::V0120(data.into()),
+            VersionedContractData::V0120(data) => VersionedContractData::V0120(data),
         };
         contract
     }
@@ -0,0 +1,302 @@
+//! A farmer can set a claim delegate, such as a keeper,
+//! who calls claim_and_route to claim reward of all his seeds
+//! and send given reward tokens to where the farmer routes them:
+//! the farmer himself, another account,
+//! or an ft_transfer_call into ref-exchange with a swap message.
+//! Reward not delivered goes back to the farmer's reward balance.
+
+use std::collections::HashSet;
+use std::convert::TryInto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, Value};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Gas, Promise, PromiseResult};
+
+use crate::utils::{
+    assert_one_yocto, ext_fungible_token, ext_self, MAX_ACCOUNT_LENGTH,
+    GAS_FOR_FT_TRANSFER, GAS_FOR_FT_TRANSFER_CALL, GAS_FOR_RESOLVE_TRANSFER,
+};
+use crate::errors::*;
+use crate::events::Event;
+use crate::*;
+
+/// Most reward tokens routed in one claim_and_route, bounded by the gas of a call.
+pub const MAX_ROUTE_TOKENS: usize = 3;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub enum RewardRoute {
+    /// ft_transfer to receiver_id
+    Account { receiver_id: AccountId },
+    /// ft_transfer_call to exchange_id with msg,
+    /// the msg must give swap_out_recipient,
+    /// or the swapped tokens would go to this contract
+    Exchange { exchange_id: AccountId, msg: String },
+}
+
+impl RewardRoute {
+    pub(crate) fn assert_valid(&self) {
+        match self {
+            RewardRoute::Account { receiver_id } => {
+                assert!(env::is_valid_account_id(receiver_id.as_bytes()), "{}", ERR24_INVALID_REWARD_ROUTE);
+            }
+            RewardRoute::Exchange { exchange_id, msg } => {
+                assert!(env::is_valid_account_id(exchange_id.as_bytes()), "{}", ERR24_INVALID_REWARD_ROUTE);
+                let message: Value = serde_json::from_str(msg).expect(ERR24_INVALID_REWARD_ROUTE);
+                assert!(
+                    message.get("swap_out_recipient").map(|v| v.is_string()).unwrap_or(false),
+                    "{}", ERR24_INVALID_REWARD_ROUTE
+                );
+            }
+        }
+    }
+
+    /// gas of sending one reward token along this route, resolve included
+    pub(crate) fn gas_per_token(route: &Option<RewardRoute>) -> Gas {
+        match route {
+            Some(RewardRoute::Exchange { .. }) => GAS_FOR_FT_TRANSFER_CALL + GAS_FOR_RESOLVE_TRANSFER,
+            _ => GAS_FOR_FT_TRANSFER + GAS_FOR_RESOLVE_TRANSFER,
+        }
+    }
+
+    /// bytes taken in Farmer, including the option and enum tags
+    pub(crate) fn storage_length(&self) -> u128 {
+        match self {
+            RewardRoute::Account { .. } => 1 + 1 + 4 + MAX_ACCOUNT_LENGTH,
+            RewardRoute::Exchange { msg, .. } => 1 + 1 + 4 + MAX_ACCOUNT_LENGTH + 4 + msg.len() as u128,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct RewardRouteInfo {
+    pub claim_delegate: Option<AccountId>,
+    /// None means the farmer himself
+    pub reward_route: Option<RewardRoute>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Set the account who can claim and route reward for the caller,
+    /// None to remove it.
+    #[payable]
+    pub fn set_claim_delegate(&mut self, delegate_id: Option<ValidAccountId>) {
+        assert_one_yocto();
+        assert!(self.data().state == RunningState::Running, "{}", ERR600_CONTRACT_PAUSED);
+        let sender_id = env::predecessor_account_id();
+
+        let mut farmer = self.get_farmer(&sender_id);
+        farmer.get_ref_mut().claim_delegate = delegate_id.map(|a| a.into());
+        self.data_mut().farmers.insert(&sender_id, &farmer);
+        self.assert_storage_usage(&sender_id);
+
+        env::log(
+            format!(
+                "{} set claim delegate to {:?}",
+                sender_id, farmer.get_ref().claim_delegate,
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Set where claim_and_route sends the caller's reward,
+    /// None to send to the caller.
+    #[payable]
+    pub fn set_reward_route(&mut self, reward_route: Option<RewardRoute>) {
+        assert_one_yocto();
+        assert!(self.data().state == RunningState::Running, "{}", ERR600_CONTRACT_PAUSED);
+        let sender_id = env::predecessor_account_id();
+        if let Some(route) = &reward_route {
+            route.assert_valid();
+        }
+
+        let mut farmer = self.get_farmer(&sender_id);
+        farmer.get_ref_mut().reward_route = reward_route;
+        self.data_mut().farmers.insert(&sender_id, &farmer);
+        self.assert_storage_usage(&sender_id);
+
+        env::log(
+            format!(
+                "{} set reward route to {}",
+                sender_id, serde_json::to_string(&farmer.get_ref().reward_route).unwrap(),
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Claims reward of all seeds of the farmer,
+    /// and sends his balance of given reward tokens along his reward route,
+    /// at most MAX_ROUTE_TOKENS tokens a call.
+    /// Called by the farmer or his claim delegate.
+    #[payable]
+    pub fn claim_and_route(&mut self, farmer_id: ValidAccountId, token_ids: Vec<ValidAccountId>) {
+        assert_one_yocto();
+        assert!(self.data().state == RunningState::Running, "{}", ERR600_CONTRACT_PAUSED);
+        let farmer_id: AccountId = farmer_id.into();
+        let sender_id = env::predecessor_account_id();
+        let token_ids: HashSet<AccountId> = token_ids.into_iter().map(|a| a.into()).collect();
+        assert!(
+            !token_ids.is_empty() && token_ids.len() <= MAX_ROUTE_TOKENS,
+            "{}", ERR26_INVALID_ROUTE_TOKENS
+        );
+
+        let farmer = self.get_farmer(&farmer_id);
+        assert!(
+            sender_id == farmer_id
+                || farmer.get_ref().claim_delegate.as_ref() == Some(&sender_id),
+            "{}", ERR23_NOT_CLAIM_DELEGATE
+        );
+        let seed_ids: HashSet<SeedId> = farmer.get_ref().seeds.keys()
+            .chain(farmer.get_ref().locks.keys())
+            .cloned()
+            .collect();
+        for seed_id in seed_ids.iter() {
+            self.internal_claim_user_reward_by_seed_id(&farmer_id, seed_id);
+        }
+
+        // Note: subtraction, will be reverted if the promise fails.
+        let mut farmer = self.get_farmer(&farmer_id);
+        let rewards: Vec<(AccountId, Balance)> = token_ids.into_iter()
+            .filter(|token_id| farmer.get_ref().rewards.get(token_id).map(|amount| *amount > 0).unwrap_or(false))
+            .map(|token_id| {
+                let amount = farmer.get_ref_mut().sub_reward(&token_id, 0);
+                (token_id, amount)
+            })
+            .collect();
+        let reward_route = farmer.get_ref().reward_route.clone();
+        assert!(
+            env::prepaid_gas() - env::used_gas()
+                >= rewards.len() as Gas * RewardRoute::gas_per_token(&reward_route) + GAS_FOR_RESOLVE_TRANSFER,
+            "{}", ERR27_NOT_ENOUGH_GAS
+        );
+        self.data_mut().farmers.insert(&farmer_id, &farmer);
+        self.assert_storage_usage(&farmer_id);
+
+        for (token_id, amount) in rewards {
+            env::log(
+                format!(
+                    "{} route reward {} amount {} by {}",
+                    farmer_id, token_id, amount, sender_id,
+                )
+                .as_bytes(),
+            );
+            self.internal_route_reward(&farmer_id, &reward_route, token_id, amount);
+        }
+    }
+
+    #[private]
+    pub fn callback_post_route_reward(
+        &mut self,
+        token_id: AccountId,
+        sender_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        assert_eq!(
+            env::promise_results_count(),
+            1,
+            "{}",
+            ERR25_CALLBACK_POST_WITHDRAW_INVALID
+        );
+        let amount: Balance = amount.into();
+        // ft_transfer_call returns the used amount
+        let used = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                serde_json::from_slice::<U128>(&value)
+                    .map(|used| std::cmp::min(used.0, amount))
+                    .unwrap_or(amount)
+            }
+            PromiseResult::Failed => 0,
+        };
+        env::log(
+            format!(
+                "{} route reward {} amount {}, {} used.",
+                sender_id, token_id, amount, used,
+            )
+            .as_bytes(),
+        );
+        Event::RewardWithdraw {
+            farmer_id: &sender_id,
+            token_id: &token_id,
+            withdraw_amount: &U128(used),
+            success: used > 0,
+        }
+        .emit();
+        if used < amount {
+            // This reverts the unused part of claim_and_route.
+            let mut farmer = self.get_farmer(&sender_id);
+            farmer.get_ref_mut().add_reward(&token_id, amount - used);
+            self.data_mut().farmers.insert(&sender_id, &farmer);
+        }
+        used.into()
+    }
+
+    /// Returns claim delegate and reward route of given user.
+    pub fn get_reward_route(&self, account_id: ValidAccountId) -> Option<RewardRouteInfo> {
+        self.get_farmer_wrapped(account_id.as_ref()).map(|farmer| {
+            let farmer = farmer.get();
+            RewardRouteInfo {
+                claim_delegate: farmer.claim_delegate,
+                reward_route: farmer.reward_route,
+            }
+        })
+    }
+}
+
+impl Contract {
+    fn internal_route_reward(
+        &self,
+        farmer_id: &AccountId,
+        reward_route: &Option<RewardRoute>,
+        token_id: AccountId,
+        amount: Balance,
+    ) -> Promise {
+        match reward_route {
+            Some(RewardRoute::Exchange { exchange_id, msg }) => {
+                ext_fungible_token::ft_transfer_call(
+                    exchange_id.clone().try_into().unwrap(),
+                    amount.into(),
+                    None,
+                    msg.clone(),
+                    &token_id,
+                    1,  // one yocto near
+                    GAS_FOR_FT_TRANSFER_CALL,
+                )
+                .then(ext_self::callback_post_route_reward(
+                    token_id,
+                    farmer_id.clone(),
+                    amount.into(),
+                    &env::current_account_id(),
+                    0,
+                    GAS_FOR_RESOLVE_TRANSFER,
+                ))
+            }
+            _ => {
+                let receiver_id = match reward_route {
+                    Some(RewardRoute::Account { receiver_id }) => receiver_id.clone(),
+                    _ => farmer_id.clone(),
+                };
+                ext_fungible_token::ft_transfer(
+                    receiver_id.try_into().unwrap(),
+                    amount.into(),
+                    None,
+                    &token_id,
+                    1,  // one yocto near
+                    GAS_FOR_FT_TRANSFER,
+                )
+                .then(ext_self::callback_post_withdraw_reward(
+                    token_id,
+                    farmer_id.clone(),
+                    amount.into(),
+                    &env::current_account_id(),
+                    0,
+                    GAS_FOR_RESOLVE_TRANSFER,
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! SeedLock lets a farmer lock staked seed for a while to get more seed power,
+//! the power of locked seed grows linearly with the locking duration,
+//! up to max_locking_multiplier at max_locking_duration_sec of the seed.
+//! Locked seed can be unlocked before it expires with a slash,
+//! which decreases linearly to zero at the unlock time.
+//! Slashed seed goes to the seed's slashed_amount, withdrawn by owner.
+//! Once a lock expires its power drops back to 1x,
+//! which happens on the farmer's next claim by seed or seed change,
+//! or when anyone calls expire_seed_lock for him.
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseResult};
+
+use crate::utils::{
+    assert_one_yocto, ext_multi_fungible_token, ext_fungible_token, ext_self,
+    wrap_mft_token_id, parse_seed_id, to_sec, TimestampSec, U256,
+    GAS_FOR_FT_TRANSFER, GAS_FOR_RESOLVE_WITHDRAW_SEED,
+};
+use crate::farm_seed::{FarmSeed, SeedType};
+use crate::farmer::Farmer;
+use crate::errors::*;
+use crate::*;
+
+/// multiplier and slash rate are in basis points
+pub const BP_DENOM: u32 = 10000;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Default)]
+pub struct SeedLockConfig {
+    /// 0 means locking is disabled
+    pub max_locking_duration_sec: u32,
+    /// seed power of seed locked for max_locking_duration_sec, 10000 means 1x
+    pub max_locking_multiplier: u32,
+    /// slashed part of the seed unlocked right after locking
+    pub slash_rate: u32,
+}
+
+impl SeedLockConfig {
+    /// power of amount seed locked for duration_sec
+    pub(crate) fn compute_x_amount(&self, amount: Balance, duration_sec: u32) -> Balance {
+        amount + (
+            U256::from(amount)
+            * U256::from(self.max_locking_multiplier - BP_DENOM)
+            * U256::from(duration_sec)
+            / U256::from(self.max_locking_duration_sec)
+            / U256::from(BP_DENOM)
+        ).as_u128()
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Default)]
+pub struct SeedLock {
+    pub locked_amount: Balance,
+    /// seed power of the locked amount
+    pub x_locked_amount: Balance,
+    pub unlock_at: TimestampSec,
+    /// duration of current locking
+    pub duration_sec: u32,
+}
+
+impl SeedLock {
+    /// Lock more seed, the whole locked seed is relocked for duration_sec,
+    /// which can't make it unlock earlier.
+    pub(crate) fn lock(&mut self, amount: Balance, duration_sec: u32, config: &SeedLockConfig) {
+        assert!(
+            duration_sec > 0 && duration_sec <= config.max_locking_duration_sec,
+            "{}", ERR37_INVALID_LOCK_DURATION
+        );
+        let now = to_sec(env::block_timestamp());
+        let unlock_at = now + duration_sec;
+        if self.unlock_at > now {
+            assert!(self.unlock_at <= unlock_at, "{}", ERR38_LOCK_CAUSE_PRE_UNLOCK);
+            let relocked_x = config.compute_x_amount(self.locked_amount, duration_sec);
+            self.x_locked_amount = std::cmp::max(self.x_locked_amount, relocked_x)
+                + config.compute_x_amount(amount, duration_sec);
+        } else {
+            self.x_locked_amount = config.compute_x_amount(self.locked_amount + amount, duration_sec);
+        }
+        self.locked_amount += amount;
+        self.unlock_at = unlock_at;
+        self.duration_sec = duration_sec;
+    }
+
+    /// Drop power of an expired lock to 1x,
+    /// return the power dropped.
+    pub(crate) fn expire(&mut self) -> Balance {
+        if self.unlock_at <= to_sec(env::block_timestamp()) && self.x_locked_amount > self.locked_amount {
+            let dropped = self.x_locked_amount - self.locked_amount;
+            self.x_locked_amount = self.locked_amount;
+            dropped
+        } else {
+            0
+        }
+    }
+
+    /// Unlock amount of seed, return the slashed part of it,
+    /// which is zero if the lock has expired.
+    pub(crate) fn unlock(&mut self, amount: Balance, slash_rate: u32) -> Balance {
+        assert!(amount > 0 && amount <= self.locked_amount, "{}", ERR32_NOT_ENOUGH_SEED);
+        let now = to_sec(env::block_timestamp());
+        let slashed = if self.unlock_at > now {
+            (
+                U256::from(amount)
+                * U256::from(slash_rate)
+                * U256::from(self.unlock_at - now)
+                / U256::from(BP_DENOM)
+                / U256::from(self.duration_sec)
+            ).as_u128()
+        } else {
+            0
+        };
+        if amount < self.locked_amount {
+            self.x_locked_amount = (
+                U256::from(self.x_locked_amount)
+                * U256::from(self.locked_amount - amount)
+                / U256::from(self.locked_amount)
+            ).as_u128();
+        } else {
+            self.x_locked_amount = 0;
+            self.unlock_at = 0;
+            self.duration_sec = 0;
+        }
+        self.locked_amount -= amount;
+        slashed
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct SeedLockInfo {
+    pub locked_amount: U128,
+    pub x_locked_amount: U128,
+    pub unlock_at: TimestampSec,
+    pub duration_sec: u32,
+    /// can be unlocked without slash
+    pub unlockable: bool,
+}
+
+impl From<&SeedLock> for SeedLockInfo {
+    fn from(lock: &SeedLock) -> Self {
+        Self {
+            locked_amount: lock.locked_amount.into(),
+            x_locked_amount: lock.x_locked_amount.into(),
+            unlock_at: lock.unlock_at,
+            duration_sec: lock.duration_sec,
+            unlockable: lock.unlock_at <= to_sec(env::block_timestamp()),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Lock amount of staked seed for duration_sec,
+    /// existing lock of the seed is relocked together.
+    #[payable]
+    pub fn lock_seed(&mut self, seed_id: SeedId, amount: U128, duration_sec: u32) {
+        assert_one_yocto();
+        assert!(self.data().state == RunningState::Running, "{}", ERR600_CONTRACT_PAUSED);
+        let sender_id = env::predecessor_account_id();
+        let amount: Balance = amount.into();
+
+        self.internal_update_seed_lock(&seed_id, &sender_id, |farmer, farm_seed| {
+            let config = farm_seed.lock_config.clone();
+            farmer.sub_seed(&seed_id, amount);
+            farmer.locks.entry(seed_id.clone()).or_default().lock(amount, duration_sec, &config);
+            farm_seed.locked_amount += amount;
+        });
+        self.assert_storage_usage(&sender_id);
+
+        env::log(
+            format!(
+                "{} locked {} seed {} for {} seconds",
+                sender_id, amount, seed_id, duration_sec,
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Unlock amount of locked seed back to staked,
+    /// if the lock hasn't expired, part of it is slashed.
+    #[payable]
+    pub fn unlock_seed(&mut self, seed_id: SeedId, amount: U128) {
+        assert_one_yocto();
+        assert!(self.data().state == RunningState::Running, "{}", ERR600_CONTRACT_PAUSED);
+        let sender_id = env::predecessor_account_id();
+        let amount: Balance = amount.into();
+
+        let mut slashed = 0;
+        self.internal_update_seed_lock(&seed_id, &sender_id, |farmer, farm_seed| {
+            let lock = farmer.locks.get_mut(&seed_id).expect(ERR39_SEED_NOT_LOCKED);
+            slashed = lock.unlock(amount, farm_seed.lock_config.slash_rate);
+            if lock.locked_amount == 0 {
+                farmer.locks.remove(&seed_id);
+            }
+            farmer.add_seed(&seed_id, amount - slashed);
+            farm_seed.locked_amount -= amount;
+            farm_seed.slashed_amount += slashed;
+        });
+        self.assert_storage_usage(&sender_id);
+
+        env::log(
+            format!(
+                "{} unlocked {} seed {} with {} slashed",
+                sender_id, amount, seed_id, slashed,
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Drop power of farmer's expired lock of the seed to 1x,
+    /// reward till now is claimed with the boosted power.
+    /// Anyone can call it, as the boosted power dilutes other farmers.
+    pub fn expire_seed_lock(&mut self, farmer_id: ValidAccountId, seed_id: SeedId) {
+        assert!(self.data().state == RunningState::Running, "{}", ERR600_CONTRACT_PAUSED);
+        let farmer = self.get_farmer(farmer_id.as_ref());
+        assert!(farmer.get_ref().locks.contains_key(&seed_id), "{}", ERR39_SEED_NOT_LOCKED);
+        self.internal_claim_user_reward_by_seed_id(farmer_id.as_ref(), &seed_id);
+    }
+
+    /// Withdraw all slashed seed to owner.
+    #[payable]
+    pub fn withdraw_seed_slashed(&mut self, seed_id: SeedId) -> Promise {
+        assert_one_yocto();
+        self.assert_owner();
+        let owner_id = self.data().owner_id.clone();
+
+        let mut farm_seed = self.get_seed(&seed_id);
+        let amount = farm_seed.get_ref().slashed_amount;
+        assert!(amount > 0, "{}", ERR32_NOT_ENOUGH_SEED);
+        farm_seed.get_ref_mut().slashed_amount = 0;
+        self.data_mut().seeds.insert(&seed_id, &farm_seed);
+
+        match farm_seed.get_ref().seed_type {
+            SeedType::FT => {
+                ext_fungible_token::ft_transfer(
+                    owner_id.clone().try_into().unwrap(),
+                    amount.into(),
+                    None,
+                    &seed_id,
+                    1,  // one yocto near
+                    GAS_FOR_FT_TRANSFER,
+                )
+            }
+            SeedType::MFT => {
+                let (receiver_id, token_id) = parse_seed_id(&seed_id);
+                ext_multi_fungible_token::mft_transfer(
+                    wrap_mft_token_id(&token_id),
+                    owner_id.clone().try_into().unwrap(),
+                    amount.into(),
+                    None,
+                    &receiver_id,
+                    1,  // one yocto near
+                    GAS_FOR_FT_TRANSFER,
+                )
+            }
+        }
+        .then(ext_self::callback_post_withdraw_seed_slashed(
+            seed_id,
+            amount.into(),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_WITHDRAW_SEED,
+        ))
+    }
+
+    #[private]
+    pub fn callback_post_withdraw_seed_slashed(
+        &mut self,
+        seed_id: SeedId,
+        amount: U128,
+    ) -> U128 {
+        assert_eq!(
+            env::promise_results_count(),
+            1,
+            "{}",
+            ERR25_CALLBACK_POST_WITHDRAW_INVALID
+        );
+        let amount: Balance = amount.into();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Failed => {
+                env::log(
+                    format!(
+                        "withdraw slashed seed {} with amount {}, Callback Failed.",
+                        seed_id, amount,
+                    )
+                    .as_bytes(),
+                );
+                // revert withdraw
+                let mut farm_seed = self.get_seed(&seed_id);
+                farm_seed.get_ref_mut().slashed_amount += amount;
+                self.data_mut().seeds.insert(&seed_id, &farm_seed);
+                0.into()
+            },
+            PromiseResult::Successful(_) => {
+                env::log(
+                    format!(
+                        "withdraw slashed seed {} with amount {}, Succeed.",
+                        seed_id, amount,
+                    )
+                    .as_bytes(),
+                );
+                amount.into()
+            }
+        }
+    }
+
+    /// Returns all seed locks of given user.
+    pub fn list_user_seed_locks(&self, account_id: ValidAccountId) -> HashMap<SeedId, SeedLockInfo> {
+        if let Some(farmer) = self.get_farmer_wrapped(account_id.as_ref()) {
+            farmer
+                .get_ref()
+                .locks
+                .iter()
+                .map(|(seed_id, lock)| (seed_id.clone(), lock.into()))
+                .collect()
+        } else {
+            HashMap::new()
+        }
+    }
+}
+
+impl Contract {
+    /// Change the farmer's lock of the seed by f,
+    /// farmer's reward is claimed before the seed power changes,
+    /// and total seed power follows the farmer's.
+    fn internal_update_seed_lock<F: FnOnce(&mut Farmer, &mut FarmSeed)>(
+        &mut self,
+        seed_id: &SeedId,
+        sender_id: &AccountId,
+        f: F,
+    ) {
+        self.internal_claim_user_reward_by_seed_id(sender_id, seed_id);
+
+        let mut farm_seed = self.get_seed(seed_id);
+        let mut farmer = self.get_farmer(sender_id);
+        let prev_power = farmer.get_ref().get_seed_power(seed_id);
+        f(farmer.get_ref_mut(), farm_seed.get_ref_mut());
+        let power = farmer.get_ref().get_seed_power(seed_id);
+        if power > prev_power {
+            farm_seed.get_ref_mut().add_amount(power - prev_power);
+        } else {
+            farm_seed.get_ref_mut().sub_amount(prev_power - power);
+        }
+
+        if power == 0 {
+            // remove farmer rps of relative farm
+            for farm_id in farm_seed.get_ref().farms.iter() {
+                farmer.get_ref_mut().remove_rps(farm_id);
+            }
+        }
+        self.data_mut().farmers.insert(sender_id, &farmer);
+        self.data_mut().seeds.insert(seed_id, &farm_seed);
+    }
+}
@@ -337,6 +337,18 @@ impl SimpleFarm {
         }
     }
 
+    /// Change reward_per_session after distributing reward to now with the old one,
+    /// only for farms in Created or Running state.
+    pub(crate) fn modify_reward_per_session(&mut self, reward_per_session: Balance, total_seeds: &Balance) {
+        self.distribute(total_seeds, true);
+        match self.status {
+            SimpleFarmStatus::Created | SimpleFarmStatus::Running => {
+                self.terms.reward_per_session = reward_per_session;
+            },
+            _ => env::panic(ERR43_INVALID_FARM_STATUS.as_bytes()),
+        }
+    }
+
     pub fn can_be_removed(&self, total_seeds: &Balance) -> bool {
         match self.status {
             SimpleFarmStatus::Ended => true,
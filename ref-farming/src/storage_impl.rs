@@ -99,7 +99,7 @@ impl StorageManagement for Contract {
                 "{}", ERR12_STORAGE_UNREGISTER_REWARDS_NOT_EMPTY
             );
             assert!(
-                farmer.get_ref().seeds.is_empty(),
+                farmer.get_ref().seeds.is_empty() && farmer.get_ref().locks.is_empty(),
                 "{}", ERR13_STORAGE_UNREGISTER_SEED_NOT_EMPTY
             );
             self.data_mut().farmers.remove(&account_id);
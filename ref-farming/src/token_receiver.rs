@@ -63,16 +63,17 @@ impl FungibleTokenReceiver for Contract {
             let mut farm = self.data().farms.get(&farm_id).expect(ERR41_FARM_NOT_EXIST);
 
             // update farm
-            assert_eq!(farm.get_reward_token(), env::predecessor_account_id(), "{}", ERR44_INVALID_FARM_REWARD);
-            if let Some(cur_remain) = farm.add_reward(&amount) {
+            let token_id = env::predecessor_account_id();
+            assert!(farm.get_reward_tokens().contains(&token_id), "{}", ERR44_INVALID_FARM_REWARD);
+            if let Some(cur_remain) = farm.add_reward(&token_id, &amount) {
                 self.data_mut().farms.insert(&farm_id, &farm);
-                let old_balance = self.data().reward_info.get(&env::predecessor_account_id()).unwrap_or(0);
-                self.data_mut().reward_info.insert(&env::predecessor_account_id(), &(old_balance + amount));
+                let old_balance = self.data().reward_info.get(&token_id).unwrap_or(0);
+                self.data_mut().reward_info.insert(&token_id, &(old_balance + amount));
                 
                 env::log(
                     format!(
-                        "{} added {} Reward Token, Now has {} left",
-                        sender, amount, cur_remain
+                        "{} added {} Reward Token {}, Now has {} left",
+                        sender, amount, token_id, cur_remain
                     )
                     .as_bytes(),
                 );
@@ -11,6 +11,8 @@ pub const MIN_SEED_DEPOSIT: u128 = 1_000_000_000_000_000_000;
 pub const MAX_ACCOUNT_LENGTH: u128 = 64;
 /// Amount of gas for fungible token transfers.
 pub const GAS_FOR_FT_TRANSFER: Gas = 10_000_000_000_000;
+/// Amount of gas for routing reward through ft_transfer_call, swap included.
+pub const GAS_FOR_FT_TRANSFER_CALL: Gas = 60_000_000_000_000;
 /// Amount of gas for reward token transfers resolve.
 pub const GAS_FOR_RESOLVE_TRANSFER: Gas = 10_000_000_000_000;
 /// Amount of gas for seed token transfers resolve.
@@ -27,6 +29,7 @@ construct_uint! {
 #[ext_contract(ext_fungible_token)]
 pub trait FungibleToken {
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+    fn ft_transfer_call(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>, msg: String);
 }
 
 /// TODO: this should be in the near_standard_contracts
@@ -57,6 +60,19 @@ pub trait TokenPostActions {
         sender_id: AccountId,
         amount: U128,
     );
+
+    fn callback_post_withdraw_seed_slashed(
+        &mut self,
+        seed_id: SeedId,
+        amount: U128,
+    );
+
+    fn callback_post_route_reward(
+        &mut self,
+        token_id: AccountId,
+        sender_id: AccountId,
+        amount: U128,
+    );
 }
 
 /// Assert that 1 yoctoNEAR was attached.
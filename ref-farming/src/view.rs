@@ -56,6 +56,22 @@ pub struct FarmInfo {
     pub claimed_reward: U128,
     pub unclaimed_reward: U128,
     pub beneficiary_reward: U128,
+    /// For a MULTI_REWARD_FARM, the reward fields above are of its first reward token,
+    /// this one has all of them. Empty for other kinds.
+    pub rewards: Vec<FarmRewardInfo>,
+    /// who can modify, cancel the farm and claim its beneficiary reward
+    pub creator_id: AccountId,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FarmRewardInfo {
+    pub reward_token: AccountId,
+    pub reward_per_session: U128,
+    pub total_reward: U128,
+    pub claimed_reward: U128,
+    pub unclaimed_reward: U128,
+    pub beneficiary_reward: U128,
 }
 
 impl From<&Farm> for FarmInfo {
@@ -86,6 +102,8 @@ impl From<&Farm> for FarmInfo {
                         claimed_reward: farm.amount_of_claimed.into(),
                         unclaimed_reward: dis.unclaimed.into(),
                         beneficiary_reward: farm.amount_of_beneficiary.into(),
+                        rewards: vec![],
+                        creator_id: String::new(),
                     }
                 } else {
                     Self {
@@ -105,13 +123,217 @@ impl From<&Farm> for FarmInfo {
                         // unclaimed_reward: (farm.amount_of_reward - farm.amount_of_claimed).into(),
                         unclaimed_reward: farm.last_distribution.unclaimed.into(),
                         beneficiary_reward: farm.amount_of_beneficiary.into(),
+                        rewards: vec![],
+                        creator_id: String::new(),
                     }
                 }                
             }
+            Farm::ContinuousFarm(farm) => {
+                // rounds of a continuous farm are just sessions passed since start_at
+                let round_at = |ts: u32| ts.saturating_sub(farm.terms.start_at) / farm.terms.session_interval;
+                let mut farm_status: String = (&farm.status).into();
+                let (cur_round, unclaimed) = if let Some(dis) = farm.try_distribute(&DENOM) {
+                    if farm_status == "Running".to_string()
+                        && dis.undistributed == 0
+                    {
+                        farm_status = "Ended".to_string();
+                    }
+                    (round_at(dis.distributed_at), dis.unclaimed)
+                } else {
+                    (round_at(farm.last_distribution.distributed_at), farm.last_distribution.unclaimed)
+                };
+                Self {
+                    farm_id: farm.farm_id.clone(),
+                    farm_kind,
+                    farm_status,
+                    seed_id: farm.terms.seed_id.clone(),
+                    reward_token: farm.terms.reward_token.clone(),
+                    start_at: farm.terms.start_at,
+                    reward_per_session: farm.terms.reward_per_session.into(),
+                    session_interval: farm.terms.session_interval,
+
+                    total_reward: farm.amount_of_reward.into(),
+                    cur_round,
+                    last_round: round_at(farm.last_distribution.distributed_at),
+                    claimed_reward: farm.amount_of_claimed.into(),
+                    unclaimed_reward: unclaimed.into(),
+                    beneficiary_reward: farm.amount_of_beneficiary.into(),
+                    rewards: vec![],
+                    creator_id: String::new(),
+                }
+            }
+            Farm::MultiRewardFarm(farm) => {
+                let mut farm_status: String = (&farm.status).into();
+                let (cur_round, unclaimed) = if let Some(dis) = farm.try_distribute(&DENOM) {
+                    if farm_status == "Running".to_string()
+                        && farm.funded_sessions(&dis.undistributed) == 0
+                    {
+                        farm_status = "Ended".to_string();
+                    }
+                    (dis.rr, dis.unclaimed)
+                } else {
+                    (farm.last_distribution.rr, farm.last_distribution.unclaimed.clone())
+                };
+                let rewards: Vec<FarmRewardInfo> = farm.terms.reward_tokens.iter().enumerate()
+                    .map(|(index, token)| FarmRewardInfo {
+                        reward_token: token.clone(),
+                        reward_per_session: farm.terms.rewards_per_session[index].into(),
+                        total_reward: farm.amount_of_reward[index].into(),
+                        claimed_reward: farm.amount_of_claimed[index].into(),
+                        unclaimed_reward: unclaimed[index].into(),
+                        beneficiary_reward: farm.amount_of_beneficiary[index].into(),
+                    })
+                    .collect();
+                Self {
+                    farm_id: farm.farm_id.clone(),
+                    farm_kind,
+                    farm_status,
+                    seed_id: farm.terms.seed_id.clone(),
+                    reward_token: rewards[0].reward_token.clone(),
+                    start_at: farm.terms.start_at,
+                    reward_per_session: rewards[0].reward_per_session,
+                    session_interval: farm.terms.session_interval,
+
+                    total_reward: rewards[0].total_reward,
+                    cur_round,
+                    last_round: farm.last_distribution.rr,
+                    claimed_reward: rewards[0].claimed_reward,
+                    unclaimed_reward: rewards[0].unclaimed_reward,
+                    beneficiary_reward: rewards[0].beneficiary_reward,
+                    rewards,
+                    creator_id: String::new(),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FarmRewardProjection {
+    pub reward_token: AccountId,
+    pub reward_per_session: U128,
+    /// reward released per day at current rate
+    pub daily_reward: U128,
+    pub undistributed_reward: U128,
+}
+
+/// When the farm runs dry at current rate, and what APR is calculated from,
+/// APR = daily_reward * 365 * reward price / (seed_amount * seed price)
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FarmProjection {
+    pub farm_id: FarmId,
+    pub farm_kind: String,
+    pub farm_status: String,
+    pub seed_id: SeedId,
+    /// total staked seed amount
+    pub seed_amount: U128,
+    pub session_interval: u32,
+    /// sessions the undistributed reward can fund, a partial session counted as one
+    pub remaining_sessions: u64,
+    /// timestamp in sec that undistributed reward runs out,
+    /// 0 if the farm waits for its first reward deposit to start
+    pub end_at: u32,
+    pub rewards: Vec<FarmRewardProjection>,
+}
+
+/// Reward a farmer has claimed so far, per farm and in total per reward token.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FarmerClaimHistory {
+    pub farms: HashMap<FarmId, HashMap<AccountId, U128>>,
+    pub tokens: HashMap<AccountId, U128>,
+}
+
+fn ceil_div(a: u128, b: u128) -> u128 {
+    a / b + if a % b == 0 { 0 } else { 1 }
+}
+
+fn to_timestamp_sec(ts: u128) -> u32 {
+    std::cmp::min(ts, u32::MAX as u128) as u32
+}
+
+fn reward_projection(reward_token: &AccountId, reward_per_session: Balance, session_interval: u32, undistributed: Balance) -> FarmRewardProjection {
+    FarmRewardProjection {
+        reward_token: reward_token.clone(),
+        reward_per_session: reward_per_session.into(),
+        daily_reward: (U256::from(reward_per_session) * U256::from(86400) / U256::from(session_interval)).as_u128().into(),
+        undistributed_reward: undistributed.into(),
+    }
+}
+
+impl Farm {
+    pub(crate) fn get_projection(&self, seed_amount: Balance) -> FarmProjection {
+        let farm_info: FarmInfo = self.into();
+        let (remaining_sessions, end_at, rewards) = match self {
+            Farm::SimpleFarm(farm) => {
+                let (rr, undistributed) = match farm.try_distribute(&seed_amount) {
+                    Some(dis) => (dis.rr, dis.undistributed),
+                    None => (farm.last_distribution.rr, farm.last_distribution.undistributed),
+                };
+                let remaining = ceil_div(undistributed, farm.terms.reward_per_session);
+                let end_at = if farm.terms.start_at == 0 { 0 } else {
+                    to_timestamp_sec((rr as u128).saturating_add(remaining).saturating_mul(farm.terms.session_interval as u128).saturating_add(farm.terms.start_at as u128))
+                };
+                (remaining, end_at, vec![reward_projection(
+                    &farm.terms.reward_token, farm.terms.reward_per_session, farm.terms.session_interval, undistributed
+                )])
+            },
+            Farm::MultiRewardFarm(farm) => {
+                let (rr, undistributed) = match farm.try_distribute(&seed_amount) {
+                    Some(dis) => (dis.rr, dis.undistributed),
+                    None => (farm.last_distribution.rr, farm.last_distribution.undistributed.clone()),
+                };
+                // only whole sessions funded by every token would be distributed
+                let remaining = farm.funded_sessions(&undistributed);
+                let end_at = if farm.terms.start_at == 0 { 0 } else {
+                    to_timestamp_sec((rr as u128).saturating_add(remaining).saturating_mul(farm.terms.session_interval as u128).saturating_add(farm.terms.start_at as u128))
+                };
+                let rewards = farm.terms.reward_tokens.iter().enumerate()
+                    .map(|(index, token)| reward_projection(
+                        token, farm.terms.rewards_per_session[index], farm.terms.session_interval, undistributed[index]
+                    ))
+                    .collect();
+                (remaining, end_at, rewards)
+            },
+            Farm::ContinuousFarm(farm) => {
+                let dis = farm.try_distribute(&seed_amount).unwrap_or(farm.last_distribution.clone());
+                let remaining = ceil_div(dis.undistributed, farm.terms.reward_per_session);
+                let end_at = if farm.terms.start_at == 0 { 0 } else {
+                    let secs = (
+                        U256::from(dis.undistributed) * U256::from(farm.terms.session_interval)
+                        + U256::from(farm.terms.reward_per_session - 1)
+                    ) / U256::from(farm.terms.reward_per_session);
+                    to_timestamp_sec(dis.distributed_at as u128 + std::cmp::min(secs, U256::from(u32::MAX)).as_u128())
+                };
+                (remaining, end_at, vec![reward_projection(
+                    &farm.terms.reward_token, farm.terms.reward_per_session, farm.terms.session_interval, dis.undistributed
+                )])
+            },
+        };
+        FarmProjection {
+            farm_id: farm_info.farm_id,
+            farm_kind: farm_info.farm_kind,
+            farm_status: farm_info.farm_status,
+            seed_id: farm_info.seed_id,
+            seed_amount: seed_amount.into(),
+            session_interval: farm_info.session_interval,
+            remaining_sessions: std::cmp::min(remaining_sessions, u64::MAX as u128) as u64,
+            end_at,
+            rewards,
         }
     }
 }
 
+impl Contract {
+    fn internal_get_farm_info(&self, farm: &Farm) -> FarmInfo {
+        let mut farm_info: FarmInfo = farm.into();
+        farm_info.creator_id = self.internal_get_farm_creator(&farm_info.farm_id);
+        farm_info
+    }
+}
+
 #[near_bindgen]
 impl Contract {
     pub fn get_metadata(&self) -> Metadata {
@@ -141,7 +363,7 @@ impl Contract {
 
         (from_index..std::cmp::min(from_index + limit, keys.len()))
             .map(|index| 
-                (&self.data().farms.get(&keys.get(index).unwrap()).unwrap()).into()
+                self.internal_get_farm_info(&self.data().farms.get(&keys.get(index).unwrap()).unwrap())
             )
             .collect()
     }
@@ -151,7 +373,7 @@ impl Contract {
 
         (from_index..std::cmp::min(from_index + limit, keys.len()))
             .map(|index| 
-                (&self.data().outdated_farms.get(&keys.get(index).unwrap()).unwrap()).into()
+                self.internal_get_farm_info(&self.data().outdated_farms.get(&keys.get(index).unwrap()).unwrap())
             )
             .collect()
     }
@@ -162,7 +384,7 @@ impl Contract {
             .farms
             .iter()
             .map(|farm_id| 
-                (&self.data().farms.get(&farm_id).unwrap()).into()
+                self.internal_get_farm_info(&self.data().farms.get(&farm_id).unwrap())
             )
             .collect()
     }
@@ -170,7 +392,20 @@ impl Contract {
     /// Returns information about specified farm.
     pub fn get_farm(&self, farm_id: FarmId) -> Option<FarmInfo> {
         if let Some(farm) = self.data().farms.get(&farm_id) {
-            Some((&farm).into())
+            Some(self.internal_get_farm_info(&farm))
+        } else {
+            None
+        }
+    }
+
+    /// Returns when the farm runs dry at current rate and the APR inputs of it.
+    pub fn get_farm_projection(&self, farm_id: FarmId) -> Option<FarmProjection> {
+        let (seed_id, _) = parse_farm_id(&farm_id);
+        if let (Some(farm), Some(farm_seed)) = (
+            self.data().farms.get(&farm_id),
+            self.get_seed_wrapped(&seed_id),
+        ) {
+            Some(farm.get_projection(farm_seed.get_ref().amount))
         } else {
             None
         }
@@ -178,7 +413,7 @@ impl Contract {
 
     pub fn get_outdated_farm(&self, farm_id: FarmId) -> Option<FarmInfo> {
         if let Some(farm) = self.data().outdated_farms.get(&farm_id) {
-            Some((&farm).into())
+            Some(self.internal_get_farm_info(&farm))
         } else {
             None
         }
@@ -217,7 +452,8 @@ impl Contract {
             .into()
     }
 
-    pub fn get_unclaimed_reward(&self, account_id: ValidAccountId, farm_id: FarmId) -> U128 {
+    /// Returns unclaimed amount of each reward token of given farm.
+    pub fn get_unclaimed_reward(&self, account_id: ValidAccountId, farm_id: FarmId) -> HashMap<AccountId, U128> {
         let (seed_id, _) = parse_farm_id(&farm_id);
 
         if let (Some(farmer), Some(farm_seed)) = (
@@ -225,21 +461,49 @@ impl Contract {
             self.get_seed_wrapped(&seed_id),
         ) {
             if let Some(farm) = self.data().farms.get(&farm_id) {
-                let reward_amount = farm.view_farmer_unclaimed_reward(
+                let reward_amounts = farm.view_farmer_unclaimed_reward(
                     &farmer.get_ref().get_rps(&farm.get_farm_id()),
-                    farmer.get_ref().seeds.get(&seed_id).unwrap_or(&0_u128),
+                    &farmer.get_ref().get_seed_power(&seed_id),
                     &farm_seed.get_ref().amount,
                 );
-                reward_amount.into()
+                farm.get_reward_tokens()
+                    .into_iter()
+                    .zip(reward_amounts.into_iter())
+                    .map(|(token_id, amount)| (token_id, U128(amount)))
+                    .collect()
             } else {
-                0.into()
+                HashMap::new()
             }
         } else {
-            0.into()
+            HashMap::new()
+        }
+    }
+
+    /// Returns cumulative claimed reward of given user,
+    /// empty if the user is not registered.
+    pub fn get_farmer_claim_history(&self, account_id: ValidAccountId) -> FarmerClaimHistory {
+        let farmer = self.get_farmer_default(account_id.as_ref()).get();
+        FarmerClaimHistory {
+            farms: farmer
+                .farm_claimed
+                .into_iter()
+                .map(|(farm_id, claimed)| {
+                    (
+                        farm_id,
+                        claimed.into_iter().map(|(token_id, bal)| (token_id, U128(bal))).collect(),
+                    )
+                })
+                .collect(),
+            tokens: farmer
+                .token_claimed
+                .into_iter()
+                .map(|(token_id, bal)| (token_id, U128(bal)))
+                .collect(),
         }
     }
 
-    /// return all seed and its amount staked in this contract in a hashmap
+    /// return all seed and its total seed power in this contract in a hashmap,
+    /// which equals to the staked amount if nothing locked
     pub fn list_seeds(&self, from_index: u64, limit: u64) -> HashMap<SeedId, U128> {
         let keys = self.data().seeds.keys_as_vector();
         (from_index..std::cmp::min(from_index + limit, keys.len()))
@@ -255,7 +519,8 @@ impl Contract {
             .collect()
     }
 
-    /// return user staked seeds and its amount in a hashmap
+    /// return user staked seeds and its amount in a hashmap,
+    /// locked seeds are in list_user_seed_locks
     pub fn list_user_seeds(&self, account_id: ValidAccountId) -> HashMap<SeedId, U128> {
         if let Some(farmer) = self.get_farmer_wrapped(account_id.as_ref()) {
             farmer
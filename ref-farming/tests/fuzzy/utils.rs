@@ -141,7 +141,7 @@ pub fn prepair_env(
             start_at: 0,
             reward_per_session: to_yocto("1").into(),
             session_interval: 60,
-        }, None),
+        }, None, None),
         deposit = to_yocto("1")
     );
     out_come.assert_success();
@@ -269,7 +269,8 @@ pub(crate) fn show_unclaim(
 ) -> U128 {
     let farm_info = get_farminfo(farming, farm_id.clone());
     let ret = view!(farming.get_unclaimed_reward(to_va(user_id.clone()), farm_id.clone()))
-        .unwrap_json::<U128>();
+        .unwrap_json::<HashMap<String, U128>>()
+        .get(&farm_info.reward_token).cloned().unwrap_or(U128(0));
     if show_print {
         println!(
             "User Unclaimed for {}@{}:[CRR:{}, LRR:{}] {}",
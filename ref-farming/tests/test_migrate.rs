@@ -46,7 +46,7 @@ fn test_upgrade() {
     .assert_success();
 
     let metadata = view!(farming.get_metadata()).unwrap_json_value();
-    assert_eq!("1.1.0".to_string(), *metadata.get("version").unwrap());
+    assert_eq!("1.2.0".to_string(), *metadata.get("version").unwrap());
     assert_eq!("Running".to_string(), *metadata.get("state").unwrap());
 
     root.call(
@@ -44,7 +44,7 @@ fn single_farm_startat_0() {
             start_at: 0,
             reward_per_session: to_yocto("1").into(),
             session_interval: 60,
-        }, None),
+        }, None, None),
         deposit = to_yocto("1")
     );
     out_come.assert_success();
@@ -424,7 +424,7 @@ fn single_farm_startat_180() {
             start_at: 180,
             reward_per_session: to_yocto("1").into(),
             session_interval: 60,
-        }, None),
+        }, None, None),
         deposit = to_yocto("1")
     );
     out_come.assert_success();
@@ -0,0 +1,224 @@
+//! Keepers turn farming reward back into staked LP in two steps:
+//! harvest claims and withdraws the reward of the vault's seed,
+//! compound deposits the harvested reward into the exchange, swaps it into the pool's tokens,
+//! adds liquidity and stakes the LP shares add_liquidity returns.
+//! Only the returned shares are counted as compounded,
+//! any other LP the vault holds in the exchange is left out of the share price.
+//! Tokens left by the swaps and liquidity stay in the vault's exchange account.
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseOrValue};
+
+use crate::errors::*;
+use crate::utils::{
+    ext_exchange, ext_farming, ext_fungible_token, ext_self, is_promise_success, promise_result_as_u128,
+    GAS_FOR_ADD_LIQUIDITY, GAS_FOR_CLAIM_REWARD, GAS_FOR_DEPOSIT_REWARD, GAS_FOR_RESOLVE_ADD_LIQUIDITY,
+    GAS_FOR_RESOLVE_COMPOUND, GAS_FOR_RESOLVE_HARVEST, GAS_FOR_RESOLVE_SWAP, GAS_FOR_SWAP,
+    GAS_FOR_WITHDRAW_REWARD, STORAGE_DEPOSIT_FOR_ADD_LIQUIDITY,
+};
+use crate::*;
+
+/// Single swap action of the exchange.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct SwapAction {
+    pub pool_id: u64,
+    pub token_in: AccountId,
+    pub amount_in: Option<U128>,
+    pub token_out: AccountId,
+    pub min_amount_out: U128,
+}
+
+/// Liquidity added to the pool after swaps,
+/// min_shares goes to add_stable_liquidity and min_amounts to add_liquidity.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct AddLiquidityInfo {
+    pub pool_id: u64,
+    pub amounts: Vec<U128>,
+    pub min_amounts: Option<Vec<U128>>,
+    pub min_shares: Option<U128>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Claims reward of the vault's seed and withdraws all of token_id from farming.
+    pub fn harvest(&mut self, token_id: ValidAccountId) -> Promise {
+        self.assert_keeper();
+        ext_farming::claim_reward_by_seed(
+            self.seed_id(),
+            &self.farming_id,
+            0,
+            GAS_FOR_CLAIM_REWARD,
+        )
+        .then(ext_farming::withdraw_reward(
+            token_id.clone().into(),
+            None,
+            &self.farming_id,
+            1, // one yocto near
+            GAS_FOR_WITHDRAW_REWARD,
+        ))
+        .then(ext_self::callback_post_harvest(
+            token_id.into(),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_HARVEST,
+        ))
+    }
+
+    #[private]
+    pub fn callback_post_harvest(&mut self, token_id: AccountId) -> U128 {
+        let amount = promise_result_as_u128().unwrap_or(0);
+        if amount > 0 {
+            let harvested = self.harvested.get(&token_id).unwrap_or(0);
+            self.harvested.insert(&token_id, &(harvested + amount));
+        }
+        env::log(
+            format!(
+                "harvest {} of {}",
+                amount, token_id,
+            )
+            .as_bytes(),
+        );
+        amount.into()
+    }
+
+    /// Deposits amount of harvested token_id into the exchange,
+    /// swaps it by swap_actions and adds liquidity to the vault's pool, then stakes the LP.
+    pub fn compound(
+        &mut self,
+        token_id: ValidAccountId,
+        amount: U128,
+        swap_actions: Vec<SwapAction>,
+        add_liquidity_info: AddLiquidityInfo,
+    ) -> Promise {
+        self.assert_keeper();
+        let token_id: AccountId = token_id.into();
+        let amount: Balance = amount.into();
+        assert!(
+            !swap_actions.is_empty() && swap_actions[0].token_in == token_id,
+            "{}", ERR21_INVALID_COMPOUND
+        );
+        assert_eq!(add_liquidity_info.pool_id, self.pool_id, "{}", ERR21_INVALID_COMPOUND);
+        let harvested = self.harvested.get(&token_id).unwrap_or(0);
+        assert!(amount > 0 && amount <= harvested, "{}", ERR22_NOT_ENOUGH_HARVESTED);
+        self.harvested.insert(&token_id, &(harvested - amount));
+
+        ext_fungible_token::ft_transfer_call(
+            self.exchange_id.clone(),
+            amount.into(),
+            None,
+            String::new(),
+            &token_id,
+            1, // one yocto near
+            GAS_FOR_DEPOSIT_REWARD,
+        )
+        .then(ext_self::callback_post_compound(
+            token_id,
+            amount.into(),
+            swap_actions,
+            add_liquidity_info,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_COMPOUND,
+        ))
+    }
+
+    #[private]
+    pub fn callback_post_compound(
+        &mut self,
+        token_id: AccountId,
+        amount: U128,
+        swap_actions: Vec<SwapAction>,
+        add_liquidity_info: AddLiquidityInfo,
+    ) -> PromiseOrValue<U128> {
+        let amount: Balance = amount.into();
+        // ft_transfer_call returns the used amount
+        let used = promise_result_as_u128()
+            .map(|used| std::cmp::min(used, amount))
+            .unwrap_or(0);
+        if used < amount {
+            let harvested = self.harvested.get(&token_id).unwrap_or(0);
+            self.harvested.insert(&token_id, &(harvested + amount - used));
+        }
+        env::log(
+            format!(
+                "compound {} of {} with {} refunded",
+                amount, token_id, amount - used,
+            )
+            .as_bytes(),
+        );
+        if used == 0 {
+            return PromiseOrValue::Value(U128(0));
+        }
+        ext_exchange::swap(
+            swap_actions,
+            None,
+            None,
+            None,
+            &self.exchange_id,
+            1, // one yocto near
+            GAS_FOR_SWAP,
+        )
+        .then(ext_self::callback_post_swap(
+            add_liquidity_info,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_SWAP,
+        ))
+        .into()
+    }
+
+    /// Swapped tokens stay deposited in the exchange if the swap fails,
+    /// the next compound can add them as liquidity.
+    #[private]
+    pub fn callback_post_swap(&mut self, add_liquidity_info: AddLiquidityInfo) -> PromiseOrValue<U128> {
+        if !is_promise_success() {
+            env::log(b"compound swap failed");
+            return PromiseOrValue::Value(U128(0));
+        }
+        let add_liquidity = match add_liquidity_info.min_shares {
+            Some(min_shares) => ext_exchange::add_stable_liquidity(
+                add_liquidity_info.pool_id,
+                add_liquidity_info.amounts,
+                min_shares,
+                &self.exchange_id,
+                STORAGE_DEPOSIT_FOR_ADD_LIQUIDITY,
+                GAS_FOR_ADD_LIQUIDITY,
+            ),
+            None => ext_exchange::add_liquidity(
+                add_liquidity_info.pool_id,
+                add_liquidity_info.amounts,
+                add_liquidity_info.min_amounts,
+                &self.exchange_id,
+                STORAGE_DEPOSIT_FOR_ADD_LIQUIDITY,
+                GAS_FOR_ADD_LIQUIDITY,
+            ),
+        };
+        add_liquidity
+            .then(ext_self::callback_post_add_liquidity(
+                &env::current_account_id(),
+                0,
+                GAS_FOR_RESOLVE_ADD_LIQUIDITY,
+            ))
+            .into()
+    }
+
+    /// add_liquidity returns the LP shares minted to the vault, those are the compounded.
+    #[private]
+    pub fn callback_post_add_liquidity(&mut self) -> U128 {
+        let compounded = promise_result_as_u128().unwrap_or(0);
+        self.idle_lp += compounded;
+        env::log(
+            format!(
+                "compounded {} LP",
+                compounded,
+            )
+            .as_bytes(),
+        );
+        self.internal_stake_idle();
+        compounded.into()
+    }
+}
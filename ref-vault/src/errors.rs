@@ -0,0 +1,13 @@
+// Permission errors //
+pub const ERR10_NOT_ALLOWED: &str = "E10: not allowed";
+
+// Deposit errors //
+pub const ERR11_INVALID_LP_TOKEN: &str = "E11: invalid LP token";
+pub const ERR12_INVALID_MSG: &str = "E12: msg should be empty";
+pub const ERR13_ZERO_SHARES: &str = "E13: amount too small to mint or burn any shares";
+
+// Compound errors //
+pub const ERR21_INVALID_COMPOUND: &str = "E21: invalid compound actions";
+pub const ERR22_NOT_ENOUGH_HARVESTED: &str = "E22: not enough harvested reward";
+
+pub const ERR30_CALLBACK_INVALID: &str = "E30: expected 1 promise result";
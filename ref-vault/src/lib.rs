@@ -0,0 +1,228 @@
+/*!
+* Ref-Vault
+*
+* An auto-compound vault of one ref-exchange pool.
+* LP shares deposited are staked into ref-farming,
+* keepers harvest the farming reward and zap it back into LP shares,
+* depositors hold vault shares, a NEP-141 token, of the growing LP.
+*
+* lib.rs is the main entry point.
+*/
+use near_contract_standards::fungible_token::metadata::{
+    FungibleTokenMetadata, FungibleTokenMetadataProvider, FT_METADATA_SPEC,
+};
+use near_contract_standards::fungible_token::FungibleToken;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{UnorderedMap, UnorderedSet};
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::{
+    env, near_bindgen, AccountId, Balance, BorshStorageKey, PanicOnDefault, PromiseOrValue,
+};
+
+use crate::utils::mul_div;
+
+pub use crate::compound::{AddLiquidityInfo, SwapAction};
+pub use crate::views::VaultInfo;
+
+mod compound;
+mod errors;
+mod owner;
+mod stake;
+mod utils;
+mod views;
+mod withdraw;
+
+near_sdk::setup_alloc!();
+
+/// Vault shares minted to the vault itself on the first deposit and never burnt,
+/// so the share price can't be pushed up from a dust total supply.
+pub const DEAD_SHARES: Balance = 1_000;
+
+#[derive(BorshStorageKey, BorshSerialize)]
+pub(crate) enum StorageKeys {
+    Shares,
+    Keepers,
+    Harvested,
+}
+
+#[near_bindgen]
+#[derive(BorshSerialize, BorshDeserialize, PanicOnDefault)]
+pub struct Contract {
+    owner_id: AccountId,
+    exchange_id: AccountId,
+    farming_id: AccountId,
+    pool_id: u64,
+    share_decimals: u8,
+    /// Vault shares.
+    token: FungibleToken,
+    /// LP shares staked in farming, including those on the way in or out.
+    staked_lp: Balance,
+    /// LP shares held by the vault in the exchange.
+    idle_lp: Balance,
+    /// Reward withdrawn from farming and not compounded yet.
+    harvested: UnorderedMap<AccountId, Balance>,
+    /// Accounts allowed to harvest and compound besides owner.
+    keepers: UnorderedSet<AccountId>,
+}
+
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn new(
+        owner_id: ValidAccountId,
+        exchange_id: ValidAccountId,
+        farming_id: ValidAccountId,
+        pool_id: u64,
+        share_decimals: u8,
+    ) -> Self {
+        assert!(!env::state_exists(), "Already initialized");
+        let mut token = FungibleToken::new(StorageKeys::Shares);
+        // owner takes back shares of those unregistered during a failed withdraw.
+        token.internal_register_account(owner_id.as_ref());
+        // vault holds the dead shares.
+        if !token.accounts.contains_key(&env::current_account_id()) {
+            token.internal_register_account(&env::current_account_id());
+        }
+        Self {
+            owner_id: owner_id.into(),
+            exchange_id: exchange_id.into(),
+            farming_id: farming_id.into(),
+            pool_id,
+            share_decimals,
+            token,
+            staked_lp: 0,
+            idle_lp: 0,
+            harvested: UnorderedMap::new(StorageKeys::Harvested),
+            keepers: UnorderedSet::new(StorageKeys::Keepers),
+        }
+    }
+}
+
+near_contract_standards::impl_fungible_token_core!(Contract, token);
+near_contract_standards::impl_fungible_token_storage!(Contract, token);
+
+#[near_bindgen]
+impl FungibleTokenMetadataProvider for Contract {
+    fn ft_metadata(&self) -> FungibleTokenMetadata {
+        FungibleTokenMetadata {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: format!("Ref Vault #{}", self.pool_id),
+            symbol: format!("RV-{}", self.pool_id),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals: self.share_decimals,
+        }
+    }
+}
+
+impl Contract {
+    /// Token id of the pool's LP shares in the exchange.
+    pub(crate) fn lp_token_id(&self) -> String {
+        format!(":{}", self.pool_id)
+    }
+
+    /// Seed id of the pool's LP shares in farming.
+    pub(crate) fn seed_id(&self) -> String {
+        format!("{}@{}", self.exchange_id, self.pool_id)
+    }
+
+    pub(crate) fn total_lp(&self) -> Balance {
+        self.staked_lp + self.idle_lp
+    }
+
+    /// Vault shares minted for amount of LP deposited.
+    pub(crate) fn lp_to_shares(&self, amount: Balance) -> Balance {
+        let total_supply = self.token.total_supply;
+        let total_lp = self.total_lp();
+        if total_supply == 0 || total_lp == 0 {
+            amount
+        } else {
+            mul_div(amount, total_supply, total_lp)
+        }
+    }
+
+    /// LP shares redeemed by burning vault shares.
+    pub(crate) fn shares_to_lp(&self, shares: Balance) -> Balance {
+        let total_supply = self.token.total_supply;
+        if total_supply == 0 {
+            0
+        } else {
+            mul_div(shares, self.total_lp(), total_supply)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    use super::*;
+    use crate::stake::MFTTokenReceiver;
+
+    fn setup_contract() -> (VMContextBuilder, Contract) {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context
+            .current_account_id(accounts(5))
+            .predecessor_account_id(accounts(0))
+            .build());
+        let contract = Contract::new(accounts(0), accounts(1), accounts(2), 0, 24);
+        (context, contract)
+    }
+
+    fn register_user(context: &mut VMContextBuilder, contract: &mut Contract, user: ValidAccountId) {
+        testing_env!(context
+            .predecessor_account_id(user.clone())
+            .attached_deposit(env::storage_byte_cost() * 125)
+            .build());
+        contract.storage_deposit(None, None);
+    }
+
+    fn deposit_lp(context: &mut VMContextBuilder, contract: &mut Contract, user: ValidAccountId, amount: Balance) {
+        testing_env!(context
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(0)
+            .build());
+        contract.mft_on_transfer(":0".to_string(), user.into(), U128(amount), String::new());
+    }
+
+    #[test]
+    fn test_deposit_shares() {
+        let (mut context, mut contract) = setup_contract();
+        register_user(&mut context, &mut contract, accounts(3));
+        register_user(&mut context, &mut contract, accounts(4));
+
+        deposit_lp(&mut context, &mut contract, accounts(3), 10_000);
+        assert_eq!(contract.ft_balance_of(accounts(3)).0, 10_000 - DEAD_SHARES);
+        assert_eq!(contract.ft_balance_of(accounts(5)).0, DEAD_SHARES);
+        let info = contract.get_vault_info();
+        assert_eq!(info.total_shares.0, 10_000);
+        assert_eq!(info.staked_lp.0, 10_000);
+        assert_eq!(info.idle_lp.0, 0);
+
+        // compounded LP raises the share price
+        contract.staked_lp += 10_000;
+        deposit_lp(&mut context, &mut contract, accounts(4), 10_000);
+        assert_eq!(contract.ft_balance_of(accounts(4)).0, 5_000);
+        assert_eq!(contract.get_user_lp(accounts(3)).0, 18_000);
+        assert_eq!(contract.get_user_lp(accounts(4)).0, 10_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "E13: amount too small to mint or burn any shares")]
+    fn test_first_deposit_below_dead_shares() {
+        let (mut context, mut contract) = setup_contract();
+        register_user(&mut context, &mut contract, accounts(3));
+        deposit_lp(&mut context, &mut contract, accounts(3), DEAD_SHARES);
+    }
+
+    #[test]
+    #[should_panic(expected = "E11: invalid LP token")]
+    fn test_deposit_other_pool() {
+        let (mut context, mut contract) = setup_contract();
+        register_user(&mut context, &mut contract, accounts(3));
+        testing_env!(context.predecessor_account_id(accounts(1)).build());
+        contract.mft_on_transfer(":1".to_string(), accounts(3).into(), U128(1000), String::new());
+    }
+}
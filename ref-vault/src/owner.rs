@@ -0,0 +1,60 @@
+//! Implement all the relevant logic for owner of this contract.
+
+use near_sdk::json_types::ValidAccountId;
+use near_sdk::{env, near_bindgen};
+
+use crate::errors::*;
+use crate::utils::assert_one_yocto;
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    #[payable]
+    pub fn set_owner(&mut self, owner_id: ValidAccountId) {
+        assert_one_yocto();
+        self.assert_owner();
+        self.owner_id = owner_id.into();
+        if !self.token.accounts.contains_key(&self.owner_id) {
+            let owner_id = self.owner_id.clone();
+            self.token.internal_register_account(&owner_id);
+        }
+    }
+
+    /// Extend keepers with new accounts.
+    #[payable]
+    pub fn extend_keepers(&mut self, keepers: Vec<ValidAccountId>) {
+        assert_one_yocto();
+        self.assert_owner();
+        for keeper in keepers {
+            self.keepers.insert(keeper.as_ref());
+        }
+    }
+
+    /// Remove keepers.
+    #[payable]
+    pub fn remove_keepers(&mut self, keepers: Vec<ValidAccountId>) {
+        assert_one_yocto();
+        self.assert_owner();
+        for keeper in keepers {
+            self.keepers.remove(keeper.as_ref());
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner_id,
+            "{}", ERR10_NOT_ALLOWED
+        );
+    }
+
+    pub(crate) fn assert_keeper(&self) {
+        let sender_id = env::predecessor_account_id();
+        assert!(
+            sender_id == self.owner_id || self.keepers.contains(&sender_id),
+            "{}", ERR10_NOT_ALLOWED
+        );
+    }
+}
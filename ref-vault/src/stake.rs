@@ -0,0 +1,119 @@
+//! LP shares come in by mft_transfer_call on the exchange,
+//! vault shares are minted in proportion to the LP the vault holds,
+//! then the idle LP is staked into farming.
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, AccountId, Balance, PromiseOrValue};
+
+use crate::errors::*;
+use crate::utils::{
+    ext_exchange, ext_self, promise_result_as_u128, GAS_FOR_RESOLVE_STAKE, GAS_FOR_STAKE,
+};
+use crate::*;
+
+pub trait MFTTokenReceiver {
+    fn mft_on_transfer(
+        &mut self,
+        token_id: String,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128>;
+}
+
+#[near_bindgen]
+impl MFTTokenReceiver for Contract {
+    /// Callback on receiving LP shares of the vault's pool,
+    /// sender must have registered in the vault.
+    fn mft_on_transfer(
+        &mut self,
+        token_id: String,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        assert_eq!(env::predecessor_account_id(), self.exchange_id, "{}", ERR11_INVALID_LP_TOKEN);
+        assert_eq!(token_id, self.lp_token_id(), "{}", ERR11_INVALID_LP_TOKEN);
+        assert!(msg.is_empty(), "{}", ERR12_INVALID_MSG);
+
+        let amount: Balance = amount.into();
+        let mut shares = self.lp_to_shares(amount);
+        if self.token.total_supply == 0 {
+            assert!(shares > DEAD_SHARES, "{}", ERR13_ZERO_SHARES);
+            self.token.internal_deposit(&env::current_account_id(), DEAD_SHARES);
+            shares -= DEAD_SHARES;
+        }
+        assert!(shares > 0, "{}", ERR13_ZERO_SHARES);
+        self.token.internal_deposit(&sender_id, shares);
+        self.idle_lp += amount;
+
+        env::log(
+            format!(
+                "{} deposit {} LP for {} shares",
+                sender_id, amount, shares,
+            )
+            .as_bytes(),
+        );
+
+        self.internal_stake_idle();
+        PromiseOrValue::Value(U128(0))
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Stakes all idle LP into farming,
+    /// in case some of them failed to be staked.
+    pub fn stake_idle(&mut self) {
+        self.internal_stake_idle();
+    }
+
+    #[private]
+    pub fn callback_post_stake(&mut self, amount: U128) -> U128 {
+        let amount: Balance = amount.into();
+        // the unused part was refunded by the exchange
+        let unused = promise_result_as_u128()
+            .map(|unused| std::cmp::min(unused, amount))
+            .unwrap_or(amount);
+        if unused > 0 {
+            self.staked_lp -= unused;
+            self.idle_lp += unused;
+        }
+        env::log(
+            format!(
+                "stake {} LP with {} unstaked",
+                amount, unused,
+            )
+            .as_bytes(),
+        );
+        (amount - unused).into()
+    }
+}
+
+impl Contract {
+    /// Moves all idle LP to staked and sends them to farming,
+    /// the callback puts back those failed to stake.
+    pub(crate) fn internal_stake_idle(&mut self) {
+        let amount = self.idle_lp;
+        if amount == 0 {
+            return;
+        }
+        self.idle_lp = 0;
+        self.staked_lp += amount;
+        ext_exchange::mft_transfer_call(
+            self.lp_token_id(),
+            self.farming_id.clone(),
+            amount.into(),
+            None,
+            String::new(),
+            &self.exchange_id,
+            1, // one yocto near
+            GAS_FOR_STAKE,
+        )
+        .then(ext_self::callback_post_stake(
+            amount.into(),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_STAKE,
+        ));
+    }
+}
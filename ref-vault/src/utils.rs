@@ -0,0 +1,126 @@
+use near_sdk::json_types::{U128, U64};
+use near_sdk::{env, ext_contract, AccountId, Balance, Gas, PromiseResult};
+use near_sdk::serde_json;
+use uint::construct_uint;
+
+use crate::compound::{AddLiquidityInfo, SwapAction};
+use crate::errors::*;
+
+/// Amount of gas for staking LP shares into farming, including its mft_on_transfer.
+pub const GAS_FOR_STAKE: Gas = 80_000_000_000_000;
+pub const GAS_FOR_RESOLVE_STAKE: Gas = 10_000_000_000_000;
+/// Amount of gas for unstaking seed from farming, including its withdraw callback.
+pub const GAS_FOR_UNSTAKE: Gas = 100_000_000_000_000;
+/// Amount of gas for resolving unstake, including the LP transfer to user.
+pub const GAS_FOR_RESOLVE_UNSTAKE: Gas = 40_000_000_000_000;
+pub const GAS_FOR_MFT_TRANSFER: Gas = 10_000_000_000_000;
+pub const GAS_FOR_RESOLVE_WITHDRAW: Gas = 10_000_000_000_000;
+pub const GAS_FOR_CLAIM_REWARD: Gas = 20_000_000_000_000;
+/// Amount of gas for withdrawing reward from farming, including its withdraw callback.
+pub const GAS_FOR_WITHDRAW_REWARD: Gas = 40_000_000_000_000;
+pub const GAS_FOR_RESOLVE_HARVEST: Gas = 10_000_000_000_000;
+/// Amount of gas for depositing reward into the exchange, including its ft_on_transfer.
+pub const GAS_FOR_DEPOSIT_REWARD: Gas = 50_000_000_000_000;
+pub const GAS_FOR_SWAP: Gas = 30_000_000_000_000;
+/// Amount of gas for adding liquidity, including the storage deposit refund.
+pub const GAS_FOR_ADD_LIQUIDITY: Gas = 30_000_000_000_000;
+/// Amount of gas for crediting compounded LP, including staking it.
+pub const GAS_FOR_RESOLVE_ADD_LIQUIDITY: Gas = 100_000_000_000_000;
+/// Amount of gas for resolving swap, including adding liquidity and its callback.
+pub const GAS_FOR_RESOLVE_SWAP: Gas = 140_000_000_000_000;
+/// Amount of gas for resolving reward deposit, including swap and its callback.
+pub const GAS_FOR_RESOLVE_COMPOUND: Gas = 180_000_000_000_000;
+
+/// Attached to add_liquidity for the storage of new LP, the exchange refunds the rest.
+pub const STORAGE_DEPOSIT_FOR_ADD_LIQUIDITY: Balance = 10_000_000_000_000_000_000_000;
+
+construct_uint! {
+    /// 256-bit unsigned integer.
+    pub struct U256(4);
+}
+
+#[ext_contract(ext_exchange)]
+pub trait Exchange {
+    fn mft_transfer(&mut self, token_id: String, receiver_id: AccountId, amount: U128, memo: Option<String>);
+    fn mft_transfer_call(
+        &mut self,
+        token_id: String,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    );
+    fn swap(
+        &mut self,
+        actions: Vec<SwapAction>,
+        referral_id: Option<AccountId>,
+        skip_degen_price_sync: Option<bool>,
+        deadline_ns: Option<U64>,
+    );
+    fn add_liquidity(&mut self, pool_id: u64, amounts: Vec<U128>, min_amounts: Option<Vec<U128>>);
+    fn add_stable_liquidity(&mut self, pool_id: u64, amounts: Vec<U128>, min_shares: U128);
+}
+
+#[ext_contract(ext_farming)]
+pub trait Farming {
+    fn withdraw_seed(&mut self, seed_id: String, amount: U128);
+    fn claim_reward_by_seed(&mut self, seed_id: String);
+    fn withdraw_reward(&mut self, token_id: AccountId, amount: Option<U128>);
+}
+
+#[ext_contract(ext_fungible_token)]
+pub trait FungibleTokenCall {
+    fn ft_transfer_call(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>, msg: String);
+}
+
+#[ext_contract(ext_self)]
+pub trait VaultCallbacks {
+    fn callback_post_stake(&mut self, amount: U128);
+    fn callback_post_unstake(
+        &mut self,
+        sender_id: AccountId,
+        shares: U128,
+        idle_amount: U128,
+        staked_amount: U128,
+    );
+    fn callback_post_withdraw(&mut self, sender_id: AccountId, shares: U128, amount: U128);
+    fn callback_post_harvest(&mut self, token_id: AccountId);
+    fn callback_post_compound(
+        &mut self,
+        token_id: AccountId,
+        amount: U128,
+        swap_actions: Vec<SwapAction>,
+        add_liquidity_info: AddLiquidityInfo,
+    );
+    fn callback_post_swap(&mut self, add_liquidity_info: AddLiquidityInfo);
+    fn callback_post_add_liquidity(&mut self);
+}
+
+/// Assert that 1 yoctoNEAR was attached.
+pub fn assert_one_yocto() {
+    assert_eq!(env::attached_deposit(), 1, "Requires attached deposit of exactly 1 yoctoNEAR")
+}
+
+/// Returns a * b / c, rounding down.
+pub fn mul_div(a: Balance, b: Balance, c: Balance) -> Balance {
+    (U256::from(a) * U256::from(b) / U256::from(c)).as_u128()
+}
+
+/// Returns the U128 value of the only promise result,
+/// None if the promise failed or returned something else.
+pub fn promise_result_as_u128() -> Option<Balance> {
+    assert_eq!(env::promise_results_count(), 1, "{}", ERR30_CALLBACK_INVALID);
+    match env::promise_result(0) {
+        PromiseResult::NotReady => unreachable!(),
+        PromiseResult::Failed => None,
+        PromiseResult::Successful(value) => {
+            serde_json::from_slice::<U128>(&value).ok().map(|v| v.0)
+        }
+    }
+}
+
+/// Whether the only promise succeeded.
+pub fn is_promise_success() -> bool {
+    assert_eq!(env::promise_results_count(), 1, "{}", ERR30_CALLBACK_INVALID);
+    matches!(env::promise_result(0), PromiseResult::Successful(_))
+}
@@ -0,0 +1,53 @@
+//! View functions for the contract.
+
+use std::collections::HashMap;
+
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{near_bindgen, AccountId};
+
+use crate::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+pub struct VaultInfo {
+    pub owner_id: AccountId,
+    pub exchange_id: AccountId,
+    pub farming_id: AccountId,
+    pub pool_id: u64,
+    pub seed_id: String,
+    pub total_shares: U128,
+    pub staked_lp: U128,
+    pub idle_lp: U128,
+    pub harvested: HashMap<AccountId, U128>,
+    pub keepers: Vec<AccountId>,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_vault_info(&self) -> VaultInfo {
+        VaultInfo {
+            owner_id: self.owner_id.clone(),
+            exchange_id: self.exchange_id.clone(),
+            farming_id: self.farming_id.clone(),
+            pool_id: self.pool_id,
+            seed_id: self.seed_id(),
+            total_shares: self.token.total_supply.into(),
+            staked_lp: self.staked_lp.into(),
+            idle_lp: self.idle_lp.into(),
+            harvested: self
+                .harvested
+                .iter()
+                .map(|(token_id, amount)| (token_id, amount.into()))
+                .collect(),
+            keepers: self.keepers.to_vec(),
+        }
+    }
+
+    /// LP shares the user's vault shares are worth.
+    pub fn get_user_lp(&self, account_id: ValidAccountId) -> U128 {
+        let shares = self.token.accounts.get(account_id.as_ref()).unwrap_or(0);
+        self.shares_to_lp(shares).into()
+    }
+}
@@ -0,0 +1,152 @@
+//! Vault shares are burnt for LP shares,
+//! taken from idle LP first and then unstaked from farming.
+//! Any failure along the way puts LP and shares back.
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseOrValue};
+
+use crate::errors::*;
+use crate::utils::{
+    assert_one_yocto, ext_exchange, ext_farming, ext_self, is_promise_success, promise_result_as_u128,
+    GAS_FOR_MFT_TRANSFER, GAS_FOR_RESOLVE_UNSTAKE, GAS_FOR_RESOLVE_WITHDRAW, GAS_FOR_UNSTAKE,
+};
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /// Burns shares of the caller and sends the LP to the caller.
+    #[payable]
+    pub fn withdraw(&mut self, shares: U128) -> Promise {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let shares: Balance = shares.into();
+        let amount = self.shares_to_lp(shares);
+        assert!(amount > 0, "{}", ERR13_ZERO_SHARES);
+        self.token.internal_withdraw(&sender_id, shares);
+
+        let idle_amount = std::cmp::min(amount, self.idle_lp);
+        let staked_amount = amount - idle_amount;
+        self.idle_lp -= idle_amount;
+        self.staked_lp -= staked_amount;
+
+        env::log(
+            format!(
+                "{} withdraw {} shares for {} LP",
+                sender_id, shares, amount,
+            )
+            .as_bytes(),
+        );
+
+        if staked_amount > 0 {
+            ext_farming::withdraw_seed(
+                self.seed_id(),
+                staked_amount.into(),
+                &self.farming_id,
+                1, // one yocto near
+                GAS_FOR_UNSTAKE,
+            )
+            .then(ext_self::callback_post_unstake(
+                sender_id,
+                shares.into(),
+                idle_amount.into(),
+                staked_amount.into(),
+                &env::current_account_id(),
+                0,
+                GAS_FOR_RESOLVE_UNSTAKE,
+            ))
+        } else {
+            self.internal_send_lp(sender_id, shares, amount)
+        }
+    }
+
+    #[private]
+    pub fn callback_post_unstake(
+        &mut self,
+        sender_id: AccountId,
+        shares: U128,
+        idle_amount: U128,
+        staked_amount: U128,
+    ) -> PromiseOrValue<U128> {
+        let idle_amount: Balance = idle_amount.into();
+        let staked_amount: Balance = staked_amount.into();
+        // farming returns 0 if the seed went back to it
+        let unstaked = promise_result_as_u128().unwrap_or(0);
+        if unstaked == staked_amount {
+            self.internal_send_lp(sender_id, shares.into(), idle_amount + staked_amount).into()
+        } else {
+            env::log(
+                format!(
+                    "{} withdraw {} shares, unstake failed",
+                    sender_id, shares.0,
+                )
+                .as_bytes(),
+            );
+            self.staked_lp += staked_amount;
+            self.idle_lp += idle_amount;
+            self.internal_refund_shares(&sender_id, shares.into());
+            PromiseOrValue::Value(U128(0))
+        }
+    }
+
+    #[private]
+    pub fn callback_post_withdraw(
+        &mut self,
+        sender_id: AccountId,
+        shares: U128,
+        amount: U128,
+    ) -> U128 {
+        if is_promise_success() {
+            amount
+        } else {
+            env::log(
+                format!(
+                    "{} withdraw {} LP, transfer failed",
+                    sender_id, amount.0,
+                )
+                .as_bytes(),
+            );
+            // LP is still in the vault's exchange account
+            self.idle_lp += amount.0;
+            self.internal_refund_shares(&sender_id, shares.into());
+            U128(0)
+        }
+    }
+}
+
+impl Contract {
+    fn internal_send_lp(&self, receiver_id: AccountId, shares: Balance, amount: Balance) -> Promise {
+        ext_exchange::mft_transfer(
+            self.lp_token_id(),
+            receiver_id.clone(),
+            amount.into(),
+            None,
+            &self.exchange_id,
+            1, // one yocto near
+            GAS_FOR_MFT_TRANSFER,
+        )
+        .then(ext_self::callback_post_withdraw(
+            receiver_id,
+            shares.into(),
+            amount.into(),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_WITHDRAW,
+        ))
+    }
+
+    /// Mints burnt shares back, to owner if the account has unregistered meanwhile.
+    fn internal_refund_shares(&mut self, account_id: &AccountId, shares: Balance) {
+        if self.token.accounts.contains_key(account_id) {
+            self.token.internal_deposit(account_id, shares);
+        } else {
+            env::log(
+                format!(
+                    "{} unregistered, {} shares go to owner",
+                    account_id, shares,
+                )
+                .as_bytes(),
+            );
+            let owner_id = self.owner_id.clone();
+            self.token.internal_deposit(&owner_id, shares);
+        }
+    }
+}
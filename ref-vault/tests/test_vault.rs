@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::AccountId;
+use near_sdk_sim::{
+    call, deploy, init_simulator, to_yocto, view, ContractAccount, ExecutionResult, UserAccount,
+};
+
+use ref_exchange::{ContractContract as Exchange, PoolInfo};
+use ref_farming::{ContractContract as Farming, HRSimpleFarmTerms};
+use ref_vault::{AddLiquidityInfo, ContractContract as Vault, SwapAction, VaultInfo, DEAD_SHARES};
+use test_token::ContractContract as TestToken;
+
+near_sdk_sim::lazy_static_include::lazy_static_include_bytes! {
+    TEST_TOKEN_WASM_BYTES => "../res/test_token.wasm",
+    EXCHANGE_WASM_BYTES => "../res/ref_exchange.wasm",
+    FARM_WASM_BYTES => "../res/ref_farming.wasm",
+    VAULT_WASM_BYTES => "../res/ref_vault.wasm",
+}
+
+fn dai() -> AccountId {
+    "dai".to_string()
+}
+
+fn eth() -> AccountId {
+    "eth".to_string()
+}
+
+fn swap() -> AccountId {
+    "swap".to_string()
+}
+
+fn farming_id() -> AccountId {
+    "farming".to_string()
+}
+
+fn vault_id() -> AccountId {
+    "vault".to_string()
+}
+
+fn to_va(a: AccountId) -> ValidAccountId {
+    ValidAccountId::try_from(a).unwrap()
+}
+
+fn deploy_token(root: &UserAccount, token_id: AccountId) -> ContractAccount<TestToken> {
+    let t = deploy!(
+        contract: TestToken,
+        contract_id: token_id,
+        bytes: &TEST_TOKEN_WASM_BYTES,
+        signer_account: root
+    );
+    call!(root, t.new()).assert_success();
+    call!(
+        root,
+        t.storage_deposit(Some(to_va(swap())), None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    t
+}
+
+fn add_liquidity(
+    user: &UserAccount,
+    exchange: &ContractAccount<Exchange>,
+    token1: &ContractAccount<TestToken>,
+    token2: &ContractAccount<TestToken>,
+) {
+    for token in [token1, token2].iter() {
+        call!(user, token.mint(to_va(user.account_id()), to_yocto("100").into())).assert_success();
+    }
+    call!(user, exchange.storage_deposit(None, None), deposit = to_yocto("1")).assert_success();
+    for token in [token1, token2].iter() {
+        call!(
+            user,
+            token.ft_transfer_call(to_va(swap()), to_yocto("100").into(), None, "".to_string()),
+            deposit = 1
+        )
+        .assert_success();
+    }
+    call!(
+        user,
+        exchange.add_liquidity(0, vec![U128(to_yocto("100")), U128(to_yocto("100"))], None),
+        deposit = to_yocto("0.01")
+    )
+    .assert_success();
+}
+
+fn lp_balance(exchange: &ContractAccount<Exchange>, account_id: AccountId) -> u128 {
+    view!(exchange.mft_balance_of(":0".to_string(), to_va(account_id))).unwrap_json::<U128>().0
+}
+
+fn vault_info(vault: &ContractAccount<Vault>) -> VaultInfo {
+    view!(vault.get_vault_info()).unwrap_json::<VaultInfo>()
+}
+
+fn lp_supply(exchange: &ContractAccount<Exchange>) -> u128 {
+    view!(exchange.get_pool(0)).unwrap_json::<PoolInfo>().shares_total_supply.0
+}
+
+fn vault_seed(farming: &ContractAccount<Farming>) -> u128 {
+    view!(farming.list_user_seeds(to_va(vault_id())))
+        .unwrap_json::<HashMap<String, U128>>()
+        .get("swap@0")
+        .map(|seed| seed.0)
+        .unwrap_or(0)
+}
+
+fn vault_shares(vault: &ContractAccount<Vault>, account_id: AccountId) -> u128 {
+    view!(vault.ft_balance_of(to_va(account_id))).unwrap_json::<U128>().0
+}
+
+/// Exchange with a dai-eth pool where alice provides liquidity,
+/// farming with a dai reward farm on the pool and a vault registered everywhere it holds assets.
+/// Returns alice's LP as well.
+fn setup_vault() -> (
+    UserAccount,
+    UserAccount,
+    UserAccount,
+    ContractAccount<Exchange>,
+    ContractAccount<Farming>,
+    ContractAccount<Vault>,
+    ContractAccount<TestToken>,
+    u128,
+) {
+    let root = init_simulator(None);
+    let owner = root.create_user("owner".to_string(), to_yocto("100"));
+    let alice = root.create_user("alice".to_string(), to_yocto("100"));
+
+    let exchange = deploy!(
+        contract: Exchange,
+        contract_id: swap(),
+        bytes: &EXCHANGE_WASM_BYTES,
+        signer_account: root,
+        init_method: new(to_va(owner.account_id()), to_va("boost_farm".to_string()), to_va("burrowland".to_string()), 4, 1)
+    );
+    let token1 = deploy_token(&root, dai());
+    let token2 = deploy_token(&root, eth());
+    call!(owner, exchange.extend_whitelisted_tokens(vec![to_va(dai()), to_va(eth())]), deposit = 1)
+        .assert_success();
+    call!(
+        root,
+        exchange.add_simple_pool(vec![to_va(dai()), to_va(eth())], 25),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    add_liquidity(&alice, &exchange, &token1, &token2);
+    let lp = lp_balance(&exchange, alice.account_id());
+    assert!(lp > 0);
+
+    let farming = deploy!(
+        contract: Farming,
+        contract_id: farming_id(),
+        bytes: &FARM_WASM_BYTES,
+        signer_account: root,
+        init_method: new(to_va(owner.account_id()))
+    );
+    call!(
+        owner,
+        farming.create_simple_farm(HRSimpleFarmTerms{
+            seed_id: format!("{}@0", swap()),
+            reward_token: to_va(dai()),
+            start_at: 0,
+            reward_per_session: to_yocto("1").into(),
+            session_interval: 60,
+        }, None, None),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+    call!(root, token1.storage_deposit(Some(to_va(farming_id())), None), deposit = to_yocto("1"))
+        .assert_success();
+    call!(root, token1.mint(to_va(root.account_id()), to_yocto("10").into())).assert_success();
+    call!(
+        root,
+        token1.ft_transfer_call(to_va(farming_id()), to_yocto("10").into(), None, "swap@0#0".to_string()),
+        deposit = 1
+    )
+    .assert_success();
+    call!(root, exchange.mft_register(":0".to_string(), to_va(farming_id())), deposit = to_yocto("1"))
+        .assert_success();
+
+    let vault = deploy!(
+        contract: Vault,
+        contract_id: vault_id(),
+        bytes: &VAULT_WASM_BYTES,
+        signer_account: root,
+        init_method: new(to_va(owner.account_id()), to_va(swap()), to_va(farming_id()), 0, 24)
+    );
+    call!(root, exchange.mft_register(":0".to_string(), to_va(vault_id())), deposit = to_yocto("1"))
+        .assert_success();
+    call!(root, exchange.storage_deposit(Some(to_va(vault_id())), None), deposit = to_yocto("1"))
+        .assert_success();
+    call!(root, farming.storage_deposit(Some(to_va(vault_id())), None), deposit = to_yocto("1"))
+        .assert_success();
+    call!(root, token1.storage_deposit(Some(to_va(vault_id())), None), deposit = to_yocto("1"))
+        .assert_success();
+    call!(alice, vault.storage_deposit(None, None), deposit = to_yocto("1")).assert_success();
+
+    (root, owner, alice, exchange, farming, vault, token1, lp)
+}
+
+fn deposit_lp(user: &UserAccount, exchange: &ContractAccount<Exchange>, amount: u128) -> ExecutionResult {
+    call!(
+        user,
+        exchange.mft_transfer_call(":0".to_string(), to_va(vault_id()), amount.into(), None, "".to_string()),
+        deposit = 1
+    )
+}
+
+/// Harvests dai reward after some farming sessions.
+fn harvest(root: &UserAccount, owner: &UserAccount, vault: &ContractAccount<Vault>) -> u128 {
+    assert!(root.borrow_runtime_mut().produce_blocks(120).is_ok());
+    call!(owner, vault.harvest(to_va(dai()))).assert_success();
+    let harvested = vault_info(vault).harvested.get(&dai()).unwrap().0;
+    assert!(harvested > 0);
+    harvested
+}
+
+/// Swaps half of the harvested dai to eth and adds liquidity with both.
+fn compound(
+    owner: &UserAccount,
+    exchange: &ContractAccount<Exchange>,
+    vault: &ContractAccount<Vault>,
+    harvested: u128,
+) -> ExecutionResult {
+    let half = harvested / 2;
+    let eth_out = view!(exchange.get_return(0, to_va(dai()), half.into(), to_va(eth()))).unwrap_json::<U128>();
+    call!(
+        owner,
+        vault.compound(
+            to_va(dai()),
+            harvested.into(),
+            vec![SwapAction {
+                pool_id: 0,
+                token_in: dai(),
+                amount_in: Some(half.into()),
+                token_out: eth(),
+                min_amount_out: U128(0),
+            }],
+            AddLiquidityInfo {
+                pool_id: 0,
+                amounts: vec![(harvested - half).into(), eth_out],
+                min_amounts: Some(vec![U128(0), U128(0)]),
+                min_shares: None,
+            }
+        ),
+        gas = 300_000_000_000_000
+    )
+}
+
+#[test]
+fn test_vault_compound() {
+    let (root, owner, alice, exchange, farming, vault, token1, lp) = setup_vault();
+
+    // alice deposits all her LP, which goes staked into farming
+    deposit_lp(&alice, &exchange, lp).assert_success();
+    assert_eq!(vault_shares(&vault, alice.account_id()), lp - DEAD_SHARES);
+    assert_eq!(vault_shares(&vault, vault_id()), DEAD_SHARES);
+    let info = vault_info(&vault);
+    assert_eq!(info.staked_lp.0, lp);
+    assert_eq!(info.idle_lp.0, 0);
+    assert_eq!(vault_seed(&farming), lp);
+
+    // keeper harvests the reward
+    assert!(root.borrow_runtime_mut().produce_blocks(120).is_ok());
+    let out_come = call!(alice, vault.harvest(to_va(dai())));
+    let ex_status = format!("{:?}", out_come.promise_errors()[0].as_ref().unwrap().status());
+    assert!(ex_status.contains("E10: not allowed"));
+    let harvested = harvest(&root, &owner, &vault);
+    assert_eq!(view!(token1.ft_balance_of(to_va(vault_id()))).unwrap_json::<U128>().0, harvested);
+
+    // keeper swaps half of it to eth and adds liquidity, the minted LP is staked
+    let lp_supply_before = lp_supply(&exchange);
+    compound(&owner, &exchange, &vault, harvested).assert_success();
+    let compounded = lp_supply(&exchange) - lp_supply_before;
+    assert!(compounded > 0);
+    let info = vault_info(&vault);
+    assert_eq!(info.harvested.get(&dai()).unwrap().0, 0);
+    assert_eq!(info.idle_lp.0, 0);
+    assert_eq!(info.staked_lp.0, lp + compounded);
+    assert_eq!(vault_seed(&farming), info.staked_lp.0);
+    let user_lp = view!(vault.get_user_lp(to_va(alice.account_id()))).unwrap_json::<U128>().0;
+    assert!(user_lp > lp - DEAD_SHARES && user_lp < info.staked_lp.0);
+
+    // alice withdraws all her shares for the compounded LP, the dead shares keep their part
+    call!(alice, vault.withdraw((lp - DEAD_SHARES).into()), deposit = 1).assert_success();
+    assert_eq!(lp_balance(&exchange, alice.account_id()), user_lp);
+    assert_eq!(vault_shares(&vault, alice.account_id()), 0);
+    let info = vault_info(&vault);
+    assert_eq!(info.staked_lp.0, lp + compounded - user_lp);
+    assert_eq!(info.total_shares.0, DEAD_SHARES);
+}
+
+#[test]
+fn test_vault_stake_failed() {
+    let (_root, owner, alice, exchange, farming, vault, _token1, lp) = setup_vault();
+
+    // farming rejects the seed while paused, LP comes back to the vault as idle
+    call!(owner, farming.pause_contract(), deposit = 1).assert_success();
+    deposit_lp(&alice, &exchange, lp);
+    assert_eq!(vault_shares(&vault, alice.account_id()), lp - DEAD_SHARES);
+    let info = vault_info(&vault);
+    assert_eq!(info.staked_lp.0, 0);
+    assert_eq!(info.idle_lp.0, lp);
+    assert_eq!(lp_balance(&exchange, vault_id()), lp);
+    assert_eq!(vault_seed(&farming), 0);
+
+    // idle LP is staked again once farming resumes
+    call!(owner, farming.resume_contract(), deposit = 1).assert_success();
+    call!(alice, vault.stake_idle()).assert_success();
+    let info = vault_info(&vault);
+    assert_eq!(info.staked_lp.0, lp);
+    assert_eq!(info.idle_lp.0, 0);
+    assert_eq!(lp_balance(&exchange, vault_id()), 0);
+    assert_eq!(vault_seed(&farming), lp);
+}
+
+#[test]
+fn test_vault_unstake_failed() {
+    let (_root, owner, alice, exchange, farming, vault, _token1, lp) = setup_vault();
+    deposit_lp(&alice, &exchange, lp).assert_success();
+
+    // farming refuses to withdraw seed while paused, alice keeps her shares
+    call!(owner, farming.pause_contract(), deposit = 1).assert_success();
+    let out_come = call!(alice, vault.withdraw((lp / 2).into()), deposit = 1);
+    let ex_status = format!("{:?}", out_come.promise_errors()[0].as_ref().unwrap().status());
+    assert!(ex_status.contains("E600: contract paused"));
+    assert_eq!(vault_shares(&vault, alice.account_id()), lp - DEAD_SHARES);
+    assert_eq!(lp_balance(&exchange, alice.account_id()), 0);
+    let info = vault_info(&vault);
+    assert_eq!(info.staked_lp.0, lp);
+    assert_eq!(info.idle_lp.0, 0);
+    assert_eq!(vault_seed(&farming), lp);
+
+    call!(owner, farming.resume_contract(), deposit = 1).assert_success();
+    call!(alice, vault.withdraw((lp / 2).into()), deposit = 1).assert_success();
+    assert_eq!(lp_balance(&exchange, alice.account_id()), lp / 2);
+    assert_eq!(vault_info(&vault).staked_lp.0, lp - lp / 2);
+    assert_eq!(vault_seed(&farming), lp - lp / 2);
+}
+
+#[test]
+fn test_vault_compound_counts_minted_lp_only() {
+    let (root, owner, alice, exchange, farming, vault, _token1, lp) = setup_vault();
+    let deposited = lp / 2;
+    deposit_lp(&alice, &exchange, deposited).assert_success();
+
+    // LP sent to the vault without mft_transfer_call is not the vault's to compound
+    let donated = to_yocto("1");
+    call!(
+        alice,
+        exchange.mft_transfer(":0".to_string(), to_va(vault_id()), donated.into(), None),
+        deposit = 1
+    )
+    .assert_success();
+
+    // a withdraw failing to unstake before compound leaves the vault's LP as it was
+    let harvested = harvest(&root, &owner, &vault);
+    call!(owner, farming.pause_contract(), deposit = 1).assert_success();
+    call!(alice, vault.withdraw((deposited / 2).into()), deposit = 1);
+    assert_eq!(vault_info(&vault).staked_lp.0, deposited);
+
+    // the compounded LP fails to stake and stays idle, only the minted part counts
+    let lp_supply_before = lp_supply(&exchange);
+    compound(&owner, &exchange, &vault, harvested);
+    let compounded = lp_supply(&exchange) - lp_supply_before;
+    assert!(compounded > 0);
+    let info = vault_info(&vault);
+    assert_eq!(info.staked_lp.0, deposited);
+    assert_eq!(info.idle_lp.0, compounded);
+    assert_eq!(lp_balance(&exchange, vault_id()), donated + compounded);
+
+    // idle LP is staked once farming resumes, the donated LP stays out
+    call!(owner, farming.resume_contract(), deposit = 1).assert_success();
+    call!(owner, vault.stake_idle()).assert_success();
+    let info = vault_info(&vault);
+    assert_eq!(info.staked_lp.0, deposited + compounded);
+    assert_eq!(info.idle_lp.0, 0);
+    assert_eq!(vault_seed(&farming), deposited + compounded);
+    assert_eq!(lp_balance(&exchange, vault_id()), donated);
+}